@@ -0,0 +1,86 @@
+pragma circom 2.1.0;
+
+/*
+ * Verifica di una firma BLS su BLS12-381 (variante "chiavi pubbliche in G1")
+ *
+ * È lo schema di Ethereum consensus: chiave pubblica pk = sk · g1 in G1
+ * (48 byte compressi), firma σ = sk · H(m) in G2 (96 byte compressi).
+ * Il circuito vincola e(pk, H(m)) = e(g1, σ), riscritta come
+ *
+ *     e(pk, H(m)) · e(-g1, σ) = 1
+ *
+ * con un Miller loop su due coppie e un solo controllo dell'esponenziazione
+ * finale, come in bls_verify.circom. L'aritmetica su Fp (381 bit) è non
+ * nativa: sei limb da 64 bit (lib/bigint.circom).
+ *
+ * Input: ogni coordinata in Fp arriva come tre parole da 128 bit, little
+ * endian. H(m) è già mappato su G2 fuori dal circuito, con la suite
+ * BLS12381G2_XMD:SHA-256_SSWU_RO_ (src/hash_to_curve.rs). I punti devono
+ * essere affini (nessun punto all'infinito).
+ * Il circuito controlla che pk, H(m) e σ stiano sulle rispettive curve e che
+ * σ, privata, stia in G2. L'appartenenza di pk a G1 e di H(m) a G2 riguarda
+ * input pubblici e va verificata fuori (la decompressione delle chiavi lo fa).
+ */
+
+include "lib/pairing.circom";
+
+template BLSVerifyG1Keys(CURVE) {
+    var k = curve_k(CURVE);
+    var w = k \ 2;
+    var g1[2][20] = curve_g1_generator(CURVE);
+
+    // Input pubblici
+    signal input publicKey[2][w];            // pk: x, y
+    signal input messageHash[2][2][w];       // H(m): x.c0, x.c1, y.c0, y.c1
+
+    // Input privati (witness)
+    signal input signature[2][2][w];         // σ: x.c0, x.c1, y.c0, y.c1
+
+    // Parole da 128 bit → limb da 64 bit
+    component pkLimbs[2];
+    component hashLimbs[2][2];
+    component sigLimbs[2][2];
+    for (var a = 0; a < 2; a++) {
+        pkLimbs[a] = FpFromWords(CURVE);
+        pkLimbs[a].in <== publicKey[a];
+        for (var c = 0; c < 2; c++) {
+            hashLimbs[a][c] = FpFromWords(CURVE);
+            hashLimbs[a][c].in <== messageHash[a][c];
+            sigLimbs[a][c] = FpFromWords(CURVE);
+            sigLimbs[a][c].in <== signature[a][c];
+        }
+    }
+
+    component pkOnCurve = G1OnCurve(CURVE);
+    component hashOnCurve = G2OnCurve(CURVE);
+    component sigOnCurve = G2OnCurve(CURVE);
+    component sigInG2 = G2SubgroupCheck(CURVE);
+    for (var a = 0; a < 2; a++) {
+        pkOnCurve.in[a] <== pkLimbs[a].out;
+        for (var c = 0; c < 2; c++) {
+            hashOnCurve.in[a][c] <== hashLimbs[a][c].out;
+            sigOnCurve.in[a][c] <== sigLimbs[a][c].out;
+            sigInG2.in[a][c] <== sigLimbs[a][c].out;
+        }
+    }
+
+    // Coppie (pk, H(m)) e (-g1, σ): -g1 ha i limb della y negati
+    component miller = MillerLoop(CURVE, 2);
+    for (var i = 0; i < k; i++) {
+        miller.P[0][0][i] <== pkLimbs[0].out[i];
+        miller.P[0][1][i] <== pkLimbs[1].out[i];
+        miller.P[1][0][i] <== g1[0][i];
+        miller.P[1][1][i] <== -g1[1][i];
+        for (var a = 0; a < 2; a++) {
+            for (var c = 0; c < 2; c++) {
+                miller.Q[0][a][c][i] <== hashLimbs[a][c].out[i];
+                miller.Q[1][a][c][i] <== sigLimbs[a][c].out[i];
+            }
+        }
+    }
+
+    component finalExp = FinalExpCheck(CURVE);
+    finalExp.in <== miller.out;
+}
+
+component main {public [publicKey, messageHash]} = BLSVerifyG1Keys(381);
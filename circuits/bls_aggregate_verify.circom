@@ -0,0 +1,198 @@
+pragma circom 2.1.0;
+
+/*
+ * Verifica di una firma BLS aggregata (chiavi in G2, stesso messaggio)
+ *
+ * N chiavi di un comitato firmano lo stesso messaggio; la firma aggregata è
+ * σ = Σ σ_i e la chiave aggregata apk = Σ b_i pk_i, con b la bitmap dei
+ * partecipanti. Il circuito vincola, come bls_verify.circom,
+ *
+ *     e(σ, g2) · e(-H(m), apk) = 1
+ *
+ * più la somma in G2 delle chiavi selezionate dalla bitmap: una sola prova
+ * per tutto il comitato.
+ *
+ * Le N chiavi sono witness e vengono legate all'input pubblico committeeRoot,
+ * una catena di Poseidon (circomlib, due ingressi) sulle parole da 128 bit
+ * delle chiavi, nell'ordine x.c0, x.c1, y.c0, y.c1:
+ *
+ *     root = 0;  per ogni chiave e ogni parola v: root = Poseidon(root, v)
+ *
+ * Input pubblici: committeeRoot, H(m) e la bitmap in parole da 128 bit (il
+ * partecipante i è il bit i % 128 della parola i / 128); i bit oltre N devono
+ * essere nulli.
+ *
+ * L'accumulatore parte da un punto fisso R (curve_g2_offset) e alla fine si
+ * sottrae R, così non passa mai dal punto all'infinito. A ogni passo la bitmap
+ * sceglie l'operando prima della somma: pk_i per chi ha firmato, un secondo
+ * punto fisso P (curve_g2_padding) per gli altri, il cui risultato viene
+ * scartato. Le chiavi dei non partecipanti non entrano quindi in G2Add.
+ * G2Add non è completa: se l'accumulatore coincide con ±operando i vincoli
+ * non sono soddisfacibili. R e P sono derivati da un hash senza logaritmo
+ * discreto noto e le chiavi hanno proof of possession, quindi nessun membro
+ * può scegliere una chiave che lo provochi. Una bitmap vuota non è
+ * dimostrabile.
+ *
+ * Il circuito controlla solo che le chiavi stiano sul twist: sottogruppo di
+ * ordine r e proof of possession (contro le rogue key) vanno verificati alla
+ * registrazione del comitato.
+ */
+
+include "lib/pairing.circom";
+include "circomlib/circuits/poseidon.circom";
+
+template BLSAggregateVerify(CURVE, N) {
+    var n = curve_n(CURVE);
+    var k = curve_k(CURVE);
+    var w = k \ 2;
+    var p[20] = curve_prime(CURVE);
+    var g2[2][2][20] = curve_g2_generator(CURVE);
+    var offset[2][2][20] = curve_g2_offset(CURVE);
+    var padding[2][2][20] = curve_g2_padding(CURVE);
+    var B = (N + 127) \ 128;
+
+    // Input pubblici
+    signal input committeeRoot;
+    signal input messageHash[2][w];       // H(m): x, y
+    signal input bitmap[B];
+
+    // Input privati (witness)
+    signal input publicKeys[N][2][2][w];  // pk_i: x.c0, x.c1, y.c0, y.c1
+    signal input signature[2][w];         // σ aggregata: x, y
+
+    // Bitmap → bit dei partecipanti
+    component bitmapBits[B];
+    signal bits[N];
+    for (var j = 0; j < B; j++) {
+        bitmapBits[j] = Num2Bits(128);
+        bitmapBits[j].in <== bitmap[j];
+        for (var i = 0; i < 128; i++) {
+            if (128 * j + i < N) {
+                bits[128 * j + i] <== bitmapBits[j].out[i];
+            } else {
+                bitmapBits[j].out[i] === 0;
+            }
+        }
+    }
+
+    // Impegno sulle chiavi del comitato
+    var words = N * 4 * w;
+    component commit[words];
+    signal root[words + 1];
+    root[0] <== 0;
+    var idx = 0;
+    for (var i = 0; i < N; i++) {
+        for (var a = 0; a < 2; a++) {
+            for (var c = 0; c < 2; c++) {
+                for (var l = 0; l < w; l++) {
+                    commit[idx] = Poseidon(2);
+                    commit[idx].inputs[0] <== root[idx];
+                    commit[idx].inputs[1] <== publicKeys[i][a][c][l];
+                    root[idx + 1] <== commit[idx].out;
+                    idx++;
+                }
+            }
+        }
+    }
+    committeeRoot === root[words];
+
+    // Parole da 128 bit → limb da 64 bit
+    component hashLimbs[2];
+    component sigLimbs[2];
+    component pkLimbs[N][2][2];
+    for (var a = 0; a < 2; a++) {
+        hashLimbs[a] = FpFromWords(CURVE);
+        hashLimbs[a].in <== messageHash[a];
+        sigLimbs[a] = FpFromWords(CURVE);
+        sigLimbs[a].in <== signature[a];
+        for (var i = 0; i < N; i++) {
+            for (var c = 0; c < 2; c++) {
+                pkLimbs[i][a][c] = FpFromWords(CURVE);
+                pkLimbs[i][a][c].in <== publicKeys[i][a][c];
+            }
+        }
+    }
+
+    component hashOnCurve = G1OnCurve(CURVE);
+    component sigOnCurve = G1OnCurve(CURVE);
+    component pkOnCurve[N];
+    for (var a = 0; a < 2; a++) {
+        hashOnCurve.in[a] <== hashLimbs[a].out;
+        sigOnCurve.in[a] <== sigLimbs[a].out;
+    }
+    for (var i = 0; i < N; i++) {
+        pkOnCurve[i] = G2OnCurve(CURVE);
+        for (var a = 0; a < 2; a++) {
+            for (var c = 0; c < 2; c++) {
+                pkOnCurve[i].in[a][c] <== pkLimbs[i][a][c].out;
+            }
+        }
+    }
+
+    // acc[0] = R, acc[i + 1] = b_i ? acc[i] + pk_i : acc[i]
+    // L'operando di G2Add è pk_i se b_i = 1 e P altrimenti
+    signal acc[N + 1][2][2][k];
+    signal operand[N][2][2][k];
+    component keyAdd[N];
+    for (var a = 0; a < 2; a++) {
+        for (var c = 0; c < 2; c++) {
+            for (var l = 0; l < k; l++) {
+                acc[0][a][c][l] <== offset[a][c][l];
+            }
+        }
+    }
+    for (var i = 0; i < N; i++) {
+        keyAdd[i] = G2Add(CURVE);
+        keyAdd[i].T <== acc[i];
+        for (var a = 0; a < 2; a++) {
+            for (var c = 0; c < 2; c++) {
+                for (var l = 0; l < k; l++) {
+                    operand[i][a][c][l] <== padding[a][c][l] + bits[i] * (pkLimbs[i][a][c].out[l] - padding[a][c][l]);
+                }
+                keyAdd[i].Q[a][c] <== operand[i][a][c];
+                for (var l = 0; l < k; l++) {
+                    acc[i + 1][a][c][l] <== acc[i][a][c][l] + bits[i] * (keyAdd[i].out[a][c][l] - acc[i][a][c][l]);
+                }
+            }
+        }
+    }
+
+    // apk = acc[N] - R
+    var zero[2][20];
+    var offsetY[2][20];
+    for (var c = 0; c < 2; c++) {
+        for (var l = 0; l < k; l++) {
+            offsetY[c][l] = offset[1][c][l];
+        }
+    }
+    var negOffsetY[2][20] = fp2_sub(n, k, zero, offsetY, p);
+    component apk = G2Add(CURVE);
+    apk.T <== acc[N];
+    for (var c = 0; c < 2; c++) {
+        for (var l = 0; l < k; l++) {
+            apk.Q[0][c][l] <== offset[0][c][l];
+            apk.Q[1][c][l] <== negOffsetY[c][l];
+        }
+    }
+
+    // Coppie (σ, g2) e (-H(m), apk): -H(m) ha i limb della y negati
+    component miller = MillerLoop(CURVE, 2);
+    for (var i = 0; i < k; i++) {
+        miller.P[0][0][i] <== sigLimbs[0].out[i];
+        miller.P[0][1][i] <== sigLimbs[1].out[i];
+        miller.P[1][0][i] <== hashLimbs[0].out[i];
+        miller.P[1][1][i] <== -hashLimbs[1].out[i];
+        for (var a = 0; a < 2; a++) {
+            for (var c = 0; c < 2; c++) {
+                miller.Q[0][a][c][i] <== g2[a][c][i];
+                miller.Q[1][a][c][i] <== apk.out[a][c][i];
+            }
+        }
+    }
+
+    component finalExp = FinalExpCheck(CURVE);
+    finalExp.in <== miller.out;
+}
+
+// N = 16: deve coincidere con committee_size del ProverConfig (DEFAULT_COMMITTEE_SIZE)
+component main {public [committeeRoot, messageHash, bitmap]} = BLSAggregateVerify(254, 16);
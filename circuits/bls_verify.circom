@@ -1,113 +1,84 @@
-pragma circom 2.0.0;
+pragma circom 2.1.0;
 
 /*
- * BLS Signature Verification Circuit (Simplified)
+ * Verifica di una firma BLS (variante "chiavi pubbliche in G2")
  *
- * Questo circuito dimostra il pattern di verifica di una firma BLS
- * in un contesto zero-knowledge. Per semplicità didattica, utilizziamo
- * una versione semplificata che opera su BN254 (la curva supportata
- * dalle precompilate Ethereum) invece di BLS12-381.
+ * Firma σ = sk · H(m) in G1, chiave pubblica pk = sk · g2 in G2.
+ * Il circuito vincola e(σ, g2) = e(H(m), pk), riscritta come
  *
- * In produzione, si userebbe BLS12-381 con implementazione completa
- * delle operazioni di pairing. Qui dimostriamo il concetto.
+ *     e(σ, g2) · e(-H(m), pk) = 1
+ *
+ * cioè un Miller loop su due coppie e un solo controllo dell'esponenziazione
+ * finale. L'aritmetica su Fp è non nativa (lib/bigint.circom).
+ *
+ * Input: ogni coordinata in Fp arriva come k / 2 parole da 128 bit,
+ * little endian (per BN254 due parole). H(m) è già mappato su G1 fuori dal
+ * circuito, con la suite BN254G1_XMD:SHA-256_SVDW_RO_ di RFC 9380
+ * (src/hash_to_curve.rs). I punti devono essere affini (nessun punto
+ * all'infinito).
+ * Il circuito controlla che σ, H(m) e pk stiano sulle rispettive curve; G1 di
+ * BN254 ha cofattore 1, mentre l'appartenenza di pk al sottogruppo di ordine r
+ * del twist va verificata fuori (una volta per chiave, come la proof of
+ * possession).
  */
 
-include "circomlib/circuits/poseidon.circom";
-include "circomlib/circuits/comparators.circom";
+include "lib/pairing.circom";
+
+template BLSVerify(CURVE) {
+    var k = curve_k(CURVE);
+    var w = k \ 2;
+    var g2[2][2][20] = curve_g2_generator(CURVE);
 
-// Template principale per verifica firma BLS semplificata
-template BLSVerifySimplified() {
     // Input pubblici
-    signal input messageHash;      // Hash del messaggio (pubblico)
-    signal input publicKeyX;       // Coordinata X della chiave pubblica
-    signal input publicKeyY;       // Coordinata Y della chiave pubblica
+    signal input messageHash[2][w];       // H(m): x, y
+    signal input publicKey[2][2][w];      // pk: x.c0, x.c1, y.c0, y.c1
 
     // Input privati (witness)
-    signal input signatureX;       // Coordinata X della firma
-    signal input signatureY;       // Coordinata Y della firma
-
-    // Output
-    signal output isValid;
-
-    // Componenti
-    component poseidon = Poseidon(4);
-    component isEqual = IsEqual();
-
-    // Calcola hash di verifica usando Poseidon
-    // In una implementazione reale, qui ci sarebbero le operazioni
-    // di pairing e1(S, g2) == e2(H(m), PK)
-    poseidon.inputs[0] <== messageHash;
-    poseidon.inputs[1] <== signatureX;
-    poseidon.inputs[2] <== signatureY;
-    poseidon.inputs[3] <== publicKeyX;
-
-    // Per semplicità, verifichiamo che il risultato del hash
-    // corrisponda ad un valore atteso derivato dalla chiave pubblica
-    // In produzione: verifica pairing completa
-    signal expectedValue;
-    expectedValue <== publicKeyX + publicKeyY;
-
-    // Verifica che i valori corrispondano
-    isEqual.in[0] <== poseidon.out;
-    isEqual.in[1] <== expectedValue;
-
-    isValid <== isEqual.out;
-}
-
-// Template helper per calcolo commitment della firma
-template SignatureCommitment() {
-    signal input signatureX;
-    signal input signatureY;
-    signal output commitment;
-
-    component poseidon = Poseidon(2);
-    poseidon.inputs[0] <== signatureX;
-    poseidon.inputs[1] <== signatureY;
-
-    commitment <== poseidon.out;
-}
-
-// Template helper per range check (verifica che i valori siano nel campo)
-template RangeCheck(n) {
-    signal input in;
-    signal bits[n];
-
-    var sum = 0;
-    for (var i = 0; i < n; i++) {
-        bits[i] <-- (in >> i) & 1;
-        bits[i] * (bits[i] - 1) === 0;  // Deve essere 0 o 1
-        sum += bits[i] * (2 ** i);
+    signal input signature[2][w];         // σ: x, y
+
+    // Parole da 128 bit → limb da 64 bit
+    component hashLimbs[2];
+    component sigLimbs[2];
+    component pkLimbs[2][2];
+    for (var a = 0; a < 2; a++) {
+        hashLimbs[a] = FpFromWords(CURVE);
+        hashLimbs[a].in <== messageHash[a];
+        sigLimbs[a] = FpFromWords(CURVE);
+        sigLimbs[a].in <== signature[a];
+        for (var c = 0; c < 2; c++) {
+            pkLimbs[a][c] = FpFromWords(CURVE);
+            pkLimbs[a][c].in <== publicKey[a][c];
+        }
     }
 
-    sum === in;
-}
-
-// Template completo con range checks
-template BLSVerifyComplete() {
-    signal input messageHash;
-    signal input publicKeyX;
-    signal input publicKeyY;
-    signal input signatureX;
-    signal input signatureY;
-    signal output isValid;
-
-    // Range checks per assicurare che i valori siano nel campo
-    component rangeCheckSigX = RangeCheck(254);
-    component rangeCheckSigY = RangeCheck(254);
-
-    rangeCheckSigX.in <== signatureX;
-    rangeCheckSigY.in <== signatureY;
+    component hashOnCurve = G1OnCurve(CURVE);
+    component sigOnCurve = G1OnCurve(CURVE);
+    component pkOnCurve = G2OnCurve(CURVE);
+    for (var a = 0; a < 2; a++) {
+        hashOnCurve.in[a] <== hashLimbs[a].out;
+        sigOnCurve.in[a] <== sigLimbs[a].out;
+        for (var c = 0; c < 2; c++) {
+            pkOnCurve.in[a][c] <== pkLimbs[a][c].out;
+        }
+    }
 
-    // Verifica principale
-    component verify = BLSVerifySimplified();
-    verify.messageHash <== messageHash;
-    verify.publicKeyX <== publicKeyX;
-    verify.publicKeyY <== publicKeyY;
-    verify.signatureX <== signatureX;
-    verify.signatureY <== signatureY;
+    // Coppie (σ, g2) e (-H(m), pk): -H(m) ha i limb della y negati
+    component miller = MillerLoop(CURVE, 2);
+    for (var i = 0; i < k; i++) {
+        miller.P[0][0][i] <== sigLimbs[0].out[i];
+        miller.P[0][1][i] <== sigLimbs[1].out[i];
+        miller.P[1][0][i] <== hashLimbs[0].out[i];
+        miller.P[1][1][i] <== -hashLimbs[1].out[i];
+        for (var a = 0; a < 2; a++) {
+            for (var c = 0; c < 2; c++) {
+                miller.Q[0][a][c][i] <== g2[a][c][i];
+                miller.Q[1][a][c][i] <== pkLimbs[a][c].out[i];
+            }
+        }
+    }
 
-    isValid <== verify.isValid;
+    component finalExp = FinalExpCheck(CURVE);
+    finalExp.in <== miller.out;
 }
 
-// Entry point del circuito
-component main {public [messageHash, publicKeyX, publicKeyY]} = BLSVerifyComplete();
\ No newline at end of file
+component main {public [messageHash, publicKey]} = BLSVerify(254);
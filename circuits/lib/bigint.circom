@@ -0,0 +1,237 @@
+pragma circom 2.1.0;
+
+/*
+ * Aritmetica non nativa: elementi di Fp (p diverso dal campo di circom)
+ * come k limb da n bit.
+ *
+ * I prodotti si calcolano senza riporti (polinomi in X = 2^n con coefficienti
+ * interi, anche negativi) e si riducono modulo p solo alla fine con
+ * FpReduce: il quoziente è un witness e la correttezza si vincola mostrando
+ * che il polinomio resto si annulla in X = 2^n (CheckCarryToZero).
+ *
+ * Convenzione sui limiti: gli input dei prodotti hanno limb con |limb| < 2^n;
+ * gli input di FpReduce/FpCheckZero sono 2k - 1 coefficienti con
+ * |coeff| < 2^(2n + 12), sufficiente per un prodotto in Fp12 accumulato.
+ */
+
+include "circomlib/circuits/bitify.circom";
+include "bigint_func.circom";
+include "curves.circom";
+
+// Prodotto di polinomi: out[i] = Σ a[j] b[i - j].
+// Vincolato valutando entrambi i lati in ka + kb - 1 punti, un vincolo per punto.
+template BigMulNoCarry(ka, kb) {
+    signal input a[ka];
+    signal input b[kb];
+    signal output out[ka + kb - 1];
+
+    var len = ka + kb - 1;
+    var prod[len];
+    for (var i = 0; i < len; i++) {
+        prod[i] = 0;
+        for (var j = 0; j < ka; j++) {
+            if (i - j >= 0 && i - j < kb) {
+                prod[i] += a[j] * b[i - j];
+            }
+        }
+    }
+    for (var i = 0; i < len; i++) {
+        out[i] <-- prod[i];
+    }
+
+    var aEval;
+    var bEval;
+    var outEval;
+    for (var x = 0; x < len; x++) {
+        aEval = 0;
+        bEval = 0;
+        outEval = 0;
+        for (var j = 0; j < ka; j++) {
+            aEval += a[j] * (x ** j);
+        }
+        for (var j = 0; j < kb; j++) {
+            bEval += b[j] * (x ** j);
+        }
+        for (var j = 0; j < len; j++) {
+            outEval += out[j] * (x ** j);
+        }
+        outEval === aEval * bEval;
+    }
+}
+
+// Vincola Σ in[i] 2^(n i) = 0 sugli interi, con |in[i]| < 2^m.
+// I riporti sono witness con |carry| < 2^(m - n + 1), controllati con Num2Bits.
+template CheckCarryToZero(n, m, len) {
+    signal input in[len];
+    signal carry[len - 1];
+
+    component range[len - 1];
+    for (var i = 0; i < len - 1; i++) {
+        if (i == 0) {
+            carry[i] <-- in[i] / (1 << n);
+            in[i] === carry[i] * (1 << n);
+        } else {
+            carry[i] <-- (in[i] + carry[i - 1]) / (1 << n);
+            in[i] + carry[i - 1] === carry[i] * (1 << n);
+        }
+        range[i] = Num2Bits(m - n + 2);
+        range[i].in <== carry[i] + (1 << (m - n + 1));
+    }
+    in[len - 1] + carry[len - 2] === 0;
+}
+
+// Parametri della riduzione di 2k - 1 coefficienti con |coeff| < 2^(2n + 12).
+// Al valore (che può essere negativo) si somma p 2^(n shift) per renderlo
+// positivo; il quoziente ha shift + 1 limb.
+function reduce_shift(CURVE) {
+    var n = curve_n(CURVE);
+    var k = curve_k(CURVE);
+    var logBound = 2 * n + 12;
+    // |valore| + p < 2^(logBound + n (2k - 2) + 2) <= p 2^(n shift)
+    var bits = logBound + n * (2 * k - 2) + 2 - (curve_prime_bits(CURVE) - 1);
+    return (bits + n - 1) \ n;
+}
+
+// Quoziente e resto di (Σ in[i] 2^(n i) + p 2^(n shift)) / p
+function reduce_witness(CURVE, in) {
+    var n = curve_n(CURVE);
+    var k = curve_k(CURVE);
+    var p[20] = curve_prime(CURVE);
+    var shift = reduce_shift(CURVE);
+    var len = shift + k + 1;
+    var v[20];
+    for (var i = 0; i < 2 * k - 1; i++) {
+        v[i] = in[i];
+    }
+    for (var i = 0; i < k; i++) {
+        v[shift + i] += p[i];
+    }
+    var limbs[20] = signed_to_limbs(n, len, len, v);
+    return long_div(n, len, k, limbs, p);
+}
+
+// Vincola Σ in[i] 2^(n i) ≡ Σ rem[i] 2^(n i) (mod p), dato il quoziente q
+// calcolato da reduce_witness: in - rem + p 2^(n shift) - p q = 0 sugli interi
+template FpReduceCheck(CURVE) {
+    var n = curve_n(CURVE);
+    var k = curve_k(CURVE);
+    var p[20] = curve_prime(CURVE);
+    var shift = reduce_shift(CURVE);
+    var kq = shift + 1;
+    var len = shift + k;
+
+    signal input in[2 * k - 1];
+    signal input rem[k];
+    signal input q[kq];
+
+    component range[kq];
+    for (var i = 0; i < kq; i++) {
+        range[i] = Num2Bits(n);
+        range[i].in <== q[i];
+    }
+
+    // |coeff| < 2^(2n + 12) + 2 2^n + k 2^(2n) < 2^(2n + 13)
+    component carry = CheckCarryToZero(n, 2 * n + 13, len);
+    var e;
+    for (var i = 0; i < len; i++) {
+        e = 0;
+        if (i < 2 * k - 1) {
+            e += in[i];
+        }
+        if (i < k) {
+            e -= rem[i];
+        }
+        if (i >= shift) {
+            e += p[i - shift];
+        }
+        for (var j = 0; j < k; j++) {
+            if (i - j >= 0 && i - j < kq) {
+                e -= p[j] * q[i - j];
+            }
+        }
+        carry.in[i] <== e;
+    }
+}
+
+// Riduzione modulo p: out ≡ in, con limb di out in [0, 2^n).
+// out non è necessariamente < p.
+template FpReduce(CURVE) {
+    var n = curve_n(CURVE);
+    var k = curve_k(CURVE);
+    var kq = reduce_shift(CURVE) + 1;
+
+    signal input in[2 * k - 1];
+    signal output out[k];
+    signal q[kq];
+
+    var qr[2][20] = reduce_witness(CURVE, in);
+    component range[k];
+    for (var i = 0; i < k; i++) {
+        out[i] <-- qr[1][i];
+        range[i] = Num2Bits(n);
+        range[i].in <== out[i];
+    }
+    for (var i = 0; i < kq; i++) {
+        q[i] <-- qr[0][i];
+    }
+
+    component check = FpReduceCheck(CURVE);
+    for (var i = 0; i < 2 * k - 1; i++) {
+        check.in[i] <== in[i];
+    }
+    for (var i = 0; i < k; i++) {
+        check.rem[i] <== out[i];
+    }
+    for (var i = 0; i < kq; i++) {
+        check.q[i] <== q[i];
+    }
+}
+
+// Vincola in ≡ 0 (mod p)
+template FpCheckZero(CURVE) {
+    var k = curve_k(CURVE);
+    var kq = reduce_shift(CURVE) + 1;
+
+    signal input in[2 * k - 1];
+    signal q[kq];
+
+    var qr[2][20] = reduce_witness(CURVE, in);
+    for (var i = 0; i < kq; i++) {
+        q[i] <-- qr[0][i];
+    }
+
+    component check = FpReduceCheck(CURVE);
+    for (var i = 0; i < 2 * k - 1; i++) {
+        check.in[i] <== in[i];
+    }
+    for (var i = 0; i < k; i++) {
+        check.rem[i] <== 0;
+    }
+    for (var i = 0; i < kq; i++) {
+        check.q[i] <== q[i];
+    }
+}
+
+// Elemento di Fp dato in parole da 2n bit (come arrivano dagli input
+// pubblici, che devono stare nel campo di circom) → k limb da n bit
+template FpFromWords(CURVE) {
+    var n = curve_n(CURVE);
+    var k = curve_k(CURVE);
+
+    signal input in[k \ 2];
+    signal output out[k];
+
+    component range[k];
+    for (var i = 0; i < k; i++) {
+        if (i % 2 == 0) {
+            out[i] <-- in[i \ 2] % (1 << n);
+        } else {
+            out[i] <-- in[i \ 2] \ (1 << n);
+        }
+        range[i] = Num2Bits(n);
+        range[i].in <== out[i];
+    }
+    for (var i = 0; i < k \ 2; i++) {
+        in[i] === out[2 * i] + out[2 * i + 1] * (1 << n);
+    }
+}
@@ -0,0 +1,315 @@
+pragma circom 2.1.0;
+
+/*
+ * Aritmetica su interi grandi per il calcolo del witness.
+ *
+ * Solo funzioni (nessun vincolo): servono ai template per calcolare quozienti,
+ * resti e inversi che poi vengono vincolati. Un intero è un array di limb da
+ * n bit, little endian, di dimensione fissa 20; i limb oltre la lunghezza
+ * logica valgono 0. Gli array non vengono mai riassegnati in blocco: si copia
+ * limb per limb.
+ */
+
+// 1 se a >= b (k limb)
+function long_gte(n, k, a, b) {
+    for (var i = k - 1; i >= 0; i--) {
+        if (a[i] > b[i]) {
+            return 1;
+        }
+        if (a[i] < b[i]) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+function long_is_zero(k, a) {
+    for (var i = 0; i < k; i++) {
+        if (a[i] != 0) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+function long_is_one(k, a) {
+    if (a[0] != 1) {
+        return 0;
+    }
+    for (var i = 1; i < k; i++) {
+        if (a[i] != 0) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+// a + b, k + 1 limb
+function long_add(n, k, a, b) {
+    var out[20];
+    var carry = 0;
+    var t;
+    for (var i = 0; i < k; i++) {
+        t = a[i] + b[i] + carry;
+        out[i] = t % (1 << n);
+        carry = t \ (1 << n);
+    }
+    out[k] = carry;
+    return out;
+}
+
+// a - b con a >= b
+function long_sub(n, k, a, b) {
+    var out[20];
+    var borrow = 0;
+    var t;
+    for (var i = 0; i < k; i++) {
+        t = a[i] - b[i] - borrow;
+        if (t < 0) {
+            out[i] = t + (1 << n);
+            borrow = 1;
+        } else {
+            out[i] = t;
+            borrow = 0;
+        }
+    }
+    return out;
+}
+
+// a / 2
+function long_half(n, k, a) {
+    var out[20];
+    for (var i = 0; i < k; i++) {
+        out[i] = a[i] \ 2;
+        if (i + 1 < k) {
+            out[i] += (a[i + 1] % 2) << (n - 1);
+        }
+    }
+    return out;
+}
+
+// a * b, ka + kb limb
+function long_mul(n, ka, kb, a, b) {
+    var out[20];
+    var carry = 0;
+    var t;
+    for (var i = 0; i < ka + kb; i++) {
+        t = carry;
+        for (var j = 0; j < ka; j++) {
+            if (i - j >= 0 && i - j < kb) {
+                t += a[j] * b[i - j];
+            }
+        }
+        out[i] = t % (1 << n);
+        carry = t \ (1 << n);
+    }
+    return out;
+}
+
+// Quoziente (ka limb) e resto (k limb) di a / b, con b di k limb.
+// Divisione binaria: lenta ma senza stime del quoziente.
+function long_div(n, ka, k, a, b) {
+    var out[2][20];
+    var rem[20];
+    var carry;
+    var t;
+    for (var i = ka * n - 1; i >= 0; i--) {
+        // rem = 2 * rem + bit i di a
+        carry = (a[i \ n] >> (i % n)) & 1;
+        for (var j = 0; j <= k; j++) {
+            t = rem[j] * 2 + carry;
+            rem[j] = t % (1 << n);
+            carry = t \ (1 << n);
+        }
+        if (long_gte(n, k + 1, rem, b) == 1) {
+            var diff[20] = long_sub(n, k + 1, rem, b);
+            for (var j = 0; j <= k; j++) {
+                rem[j] = diff[j];
+            }
+            out[0][i \ n] += 1 << (i % n);
+        }
+    }
+    for (var j = 0; j < k; j++) {
+        out[1][j] = rem[j];
+    }
+    return out;
+}
+
+// Intero dato come polinomio in 2^n con coefficienti con segno (len termini)
+// → limb canonici (outlen). Il valore deve essere >= 0 e stare in outlen limb.
+function signed_to_limbs(n, len, outlen, a) {
+    var out[20];
+    var carry = 0;
+    var t;
+    var limb;
+    for (var i = 0; i < outlen; i++) {
+        t = carry;
+        if (i < len) {
+            t += a[i];
+        }
+        if (t < 0) {
+            limb = ((1 << n) - ((-t) % (1 << n))) % (1 << n);
+        } else {
+            limb = t % (1 << n);
+        }
+        out[i] = limb;
+        carry = (t - limb) / (1 << n);
+    }
+    return out;
+}
+
+function mod_reduce(n, ka, k, a, p) {
+    var qr[2][20] = long_div(n, ka, k, a, p);
+    var out[20];
+    for (var i = 0; i < k; i++) {
+        out[i] = qr[1][i];
+    }
+    return out;
+}
+
+function mod_add(n, k, a, b, p) {
+    var sum[20] = long_add(n, k, a, b);
+    return mod_reduce(n, k + 1, k, sum, p);
+}
+
+// a - b mod p, con a, b < p
+function mod_sub(n, k, a, b, p) {
+    if (long_gte(n, k, a, b) == 1) {
+        return long_sub(n, k, a, b);
+    }
+    var sum[20] = long_add(n, k, a, p);
+    return long_sub(n, k + 1, sum, b);
+}
+
+function mod_mul(n, k, a, b, p) {
+    var prod[20] = long_mul(n, k, k, a, b);
+    return mod_reduce(n, 2 * k, k, prod, p);
+}
+
+// Inverso modulo p (primo dispari) con l'algoritmo binario di Euclide esteso.
+// Invarianti: x1 * a ≡ u, x2 * a ≡ v (mod p). Restituisce 0 se a ≡ 0.
+function mod_inv(n, k, a, p) {
+    var u[20] = mod_reduce(n, k, k, a, p);
+    var v[20];
+    var x1[20];
+    var x2[20];
+    var zero[20];
+    for (var i = 0; i < k; i++) {
+        v[i] = p[i];
+    }
+    if (long_is_zero(k, u) == 1) {
+        return zero;
+    }
+    x1[0] = 1;
+    while (long_is_one(k, u) == 0 && long_is_one(k, v) == 0) {
+        while (u[0] % 2 == 0) {
+            var hu[20] = long_half(n, k, u);
+            var hx[20] = half_mod(n, k, x1, p);
+            for (var i = 0; i < k; i++) {
+                u[i] = hu[i];
+                x1[i] = hx[i];
+            }
+        }
+        while (v[0] % 2 == 0) {
+            var hv[20] = long_half(n, k, v);
+            var hx[20] = half_mod(n, k, x2, p);
+            for (var i = 0; i < k; i++) {
+                v[i] = hv[i];
+                x2[i] = hx[i];
+            }
+        }
+        if (long_gte(n, k, u, v) == 1) {
+            var du[20] = long_sub(n, k, u, v);
+            var dx[20] = mod_sub(n, k, x1, x2, p);
+            for (var i = 0; i < k; i++) {
+                u[i] = du[i];
+                x1[i] = dx[i];
+            }
+        } else {
+            var dv[20] = long_sub(n, k, v, u);
+            var dx[20] = mod_sub(n, k, x2, x1, p);
+            for (var i = 0; i < k; i++) {
+                v[i] = dv[i];
+                x2[i] = dx[i];
+            }
+        }
+    }
+    if (long_is_one(k, u) == 1) {
+        return x1;
+    }
+    return x2;
+}
+
+// a / 2 mod p (p dispari)
+function half_mod(n, k, a, p) {
+    if (a[0] % 2 == 0) {
+        return long_half(n, k, a);
+    }
+    var sum[20] = long_add(n, k, a, p);
+    return long_half(n, k + 1, sum);
+}
+
+// ----------------------------------------------------------------------------
+// Fp2 = Fp[u] / (u^2 + 1), elementi [2][20]
+// ----------------------------------------------------------------------------
+
+function fp2_add(n, k, a, b, p) {
+    var out[2][20];
+    var c0[20] = mod_add(n, k, a[0], b[0], p);
+    var c1[20] = mod_add(n, k, a[1], b[1], p);
+    for (var i = 0; i < k; i++) {
+        out[0][i] = c0[i];
+        out[1][i] = c1[i];
+    }
+    return out;
+}
+
+function fp2_sub(n, k, a, b, p) {
+    var out[2][20];
+    var c0[20] = mod_sub(n, k, a[0], b[0], p);
+    var c1[20] = mod_sub(n, k, a[1], b[1], p);
+    for (var i = 0; i < k; i++) {
+        out[0][i] = c0[i];
+        out[1][i] = c1[i];
+    }
+    return out;
+}
+
+// (a0 b0 - a1 b1) + (a0 b1 + a1 b0) u
+function fp2_mul(n, k, a, b, p) {
+    var out[2][20];
+    var t0[20] = mod_mul(n, k, a[0], b[0], p);
+    var t1[20] = mod_mul(n, k, a[1], b[1], p);
+    var t2[20] = mod_mul(n, k, a[0], b[1], p);
+    var t3[20] = mod_mul(n, k, a[1], b[0], p);
+    var c0[20] = mod_sub(n, k, t0, t1, p);
+    var c1[20] = mod_add(n, k, t2, t3, p);
+    for (var i = 0; i < k; i++) {
+        out[0][i] = c0[i];
+        out[1][i] = c1[i];
+    }
+    return out;
+}
+
+// 1 / a = conj(a) / (a0^2 + a1^2)
+function fp2_inv(n, k, a, p) {
+    var out[2][20];
+    var zero[20];
+    var t0[20] = mod_mul(n, k, a[0], a[0], p);
+    var t1[20] = mod_mul(n, k, a[1], a[1], p);
+    var norm[20] = mod_add(n, k, t0, t1, p);
+    var inv[20] = mod_inv(n, k, norm, p);
+    var neg[20] = mod_sub(n, k, zero, a[1], p);
+    var c0[20] = mod_mul(n, k, a[0], inv, p);
+    var c1[20] = mod_mul(n, k, neg, inv, p);
+    for (var i = 0; i < k; i++) {
+        out[0][i] = c0[i];
+        out[1][i] = c1[i];
+    }
+    return out;
+}
+
+function fp2_div(n, k, a, b, p) {
+    var inv[2][20] = fp2_inv(n, k, b, p);
+    return fp2_mul(n, k, a, inv, p);
+}
@@ -0,0 +1,737 @@
+pragma circom 2.1.0;
+
+/*
+ * Parametri delle curve, selezionate dal parametro CURVE dei template:
+ *
+ *   254  BN254 (alt_bn128), la curva delle precompilate Ethereum
+ *   381  BLS12-381, la curva delle firme di Ethereum consensus
+ *
+ * Torri di estensione: Fp2 = Fp[u] / (u^2 + 1), Fp12 = Fp2[w] / (w^6 - ξ).
+ * G2 è rappresentato sul twist E'(Fp2): di tipo D per BN254, di tipo M per
+ * BLS12-381. Le costanti sono limb da 64 bit little endian in array di 20,
+ * come in bigint_func.circom.
+ */
+
+function curve_n(CURVE) {
+    return 64;
+}
+
+function curve_k(CURVE) {
+    if (CURVE == 254) {
+        return 4;
+    }
+    if (CURVE == 381) {
+        return 6;
+    }
+    return 0;
+}
+
+function curve_prime_bits(CURVE) {
+    if (CURVE == 254) {
+        return 254;
+    }
+    if (CURVE == 381) {
+        return 381;
+    }
+    return 0;
+}
+
+function curve_prime(CURVE) {
+    var p[20];
+    if (CURVE == 254) {
+        p[0] = 4332616871279656263;
+        p[1] = 10917124144477883021;
+        p[2] = 13281191951274694749;
+        p[3] = 3486998266802970665;
+    }
+    if (CURVE == 381) {
+        p[0] = 13402431016077863595;
+        p[1] = 2210141511517208575;
+        p[2] = 7435674573564081700;
+        p[3] = 7239337960414712511;
+        p[4] = 5412103778470702295;
+        p[5] = 1873798617647539866;
+    }
+    return p;
+}
+
+// ξ = xi[0] + xi[1] u, con w^6 = ξ
+function curve_xi(CURVE) {
+    var xi[2];
+    if (CURVE == 254) {
+        xi[0] = 9;
+        xi[1] = 1;
+    }
+    if (CURVE == 381) {
+        xi[0] = 1;
+        xi[1] = 1;
+    }
+    return xi;
+}
+
+// E: y^2 = x^3 + b
+function curve_b(CURVE) {
+    if (CURVE == 254) {
+        return 3;
+    }
+    if (CURVE == 381) {
+        return 4;
+    }
+    return 0;
+}
+
+// E': y^2 = x^3 + b', con b' = b / ξ (twist D) o b' = b ξ (twist M)
+function curve_twist_b(CURVE) {
+    var b[2][20];
+    if (CURVE == 254) {
+        b[0][0] = 3632125457679333605;
+        b[0][1] = 13093307605518643107;
+        b[0][2] = 9348936922344483523;
+        b[0][3] = 3104278944836790958;
+        b[1][0] = 16474938222586303954;
+        b[1][1] = 12056031220135172178;
+        b[1][2] = 14784384838321896948;
+        b[1][3] = 42524369107353300;
+    }
+    if (CURVE == 381) {
+        b[0][0] = 4;
+        b[1][0] = 4;
+    }
+    return b;
+}
+
+// Posizioni in Fp12 dei tre coefficienti di una linea (yP, -λ xP, λ xT - yT).
+// Twist D: ψ(x, y) = (x w^2, y w^3) e la linea vale yP - λ xP w + (λ xT - yT) w^3.
+// Twist M: ψ(x, y) = (x w^-2, y w^-3); moltiplicata per w^3 (che sparisce
+// nell'esponenziazione finale) la linea vale yP w^3 - λ xP w^2 + (λ xT - yT).
+function curve_line_positions(CURVE) {
+    var pos[3];
+    if (CURVE == 254) {
+        pos[0] = 0;
+        pos[1] = 1;
+        pos[2] = 3;
+    }
+    if (CURVE == 381) {
+        pos[0] = 3;
+        pos[1] = 2;
+        pos[2] = 0;
+    }
+    return pos;
+}
+
+// Miller loop: numero di bit del parametro del loop (6x + 2 per BN254, |x| per
+// BLS12-381: x < 0 inverte f, irrilevante per il controllo del prodotto = 1)
+function curve_loop_length(CURVE) {
+    if (CURVE == 254) {
+        return 65;
+    }
+    if (CURVE == 381) {
+        return 64;
+    }
+    return 0;
+}
+
+// Bit del parametro del loop, dal più significativo
+function curve_loop_bits(CURVE) {
+    var bits[70];
+    if (CURVE == 254) {
+        bits[0] = 1;
+        bits[1] = 1;
+        bits[4] = 1;
+        bits[5] = 1;
+        bits[6] = 1;
+        bits[8] = 1;
+        bits[10] = 1;
+        bits[11] = 1;
+        bits[12] = 1;
+        bits[13] = 1;
+        bits[16] = 1;
+        bits[18] = 1;
+        bits[19] = 1;
+        bits[20] = 1;
+        bits[27] = 1;
+        bits[28] = 1;
+        bits[29] = 1;
+        bits[32] = 1;
+        bits[33] = 1;
+        bits[35] = 1;
+        bits[36] = 1;
+        bits[37] = 1;
+        bits[38] = 1;
+        bits[39] = 1;
+        bits[42] = 1;
+        bits[43] = 1;
+        bits[44] = 1;
+        bits[46] = 1;
+        bits[47] = 1;
+        bits[51] = 1;
+        bits[52] = 1;
+        bits[53] = 1;
+        bits[55] = 1;
+        bits[56] = 1;
+        bits[57] = 1;
+        bits[59] = 1;
+        bits[61] = 1;
+    }
+    if (CURVE == 381) {
+        bits[0] = 1;
+        bits[1] = 1;
+        bits[3] = 1;
+        bits[6] = 1;
+        bits[15] = 1;
+        bits[47] = 1;
+    }
+    return bits;
+}
+
+// 1 se dopo il loop servono le linee con π(Q) e -π^2(Q) (ate ottimale su BN)
+function curve_is_bn(CURVE) {
+    if (CURVE == 254) {
+        return 1;
+    }
+    return 0;
+}
+
+// Frobenius su Fp12: (Σ a_i w^i)^(p^j) = Σ conj^j(a_i) γ(j, i) w^i,
+// con γ(j, i) = ξ^(i (p^j - 1) / 6)
+function curve_frobenius(CURVE, j) {
+    var g[6][2][20];
+    if (CURVE == 254) {
+            if (j == 1) {
+                g[0][0][0] = 1;
+                g[1][0][0] = 15423480562983756912;
+                g[1][0][1] = 6652412619979170166;
+                g[1][0][2] = 16769610461022161760;
+                g[1][0][3] = 1334392721173227487;
+                g[1][1][0] = 14581793986494816940;
+                g[1][1][1] = 8392900422778406885;
+                g[1][1][2] = 11975771789798476686;
+                g[1][1][3] = 2623794231377586150;
+                g[2][0][0] = 11088870908804158781;
+                g[2][0][1] = 13226160682434769676;
+                g[2][0][2] = 5479733118184829251;
+                g[2][0][3] = 3437169660107756023;
+                g[2][1][0] = 1613930359396748194;
+                g[2][1][1] = 3651902652079185358;
+                g[2][1][2] = 5450706350010664852;
+                g[2][1][3] = 1642095672556236320;
+                g[3][0][0] = 15876315988453495642;
+                g[3][0][1] = 15828711151707445656;
+                g[3][0][2] = 15879347695360604601;
+                g[3][0][3] = 449501266848708060;
+                g[3][1][0] = 9427018508834943203;
+                g[3][1][1] = 2414067704922266578;
+                g[3][1][2] = 505728791003885355;
+                g[3][1][3] = 558513134835401882;
+                g[4][0][0] = 9550480412176721762;
+                g[4][0][1] = 15218619680543796338;
+                g[4][0][2] = 9291982349918543492;
+                g[4][0][3] = 411322207813150721;
+                g[4][1][0] = 13923800814728216870;
+                g[4][1][1] = 3928778152882390883;
+                g[4][1][2] = 11473624495072943250;
+                g[4][1][3] = 3176267935786044142;
+                g[5][0][0] = 3360468246954731823;
+                g[5][0][1] = 4781773437820083155;
+                g[5][0][2] = 16805804752481107956;
+                g[5][0][3] = 109144015201994313;
+                g[5][1][0] = 2650008764942134347;
+                g[5][1][1] = 12718389207422085824;
+                g[5][1][2] = 11709273573250211709;
+                g[5][1][3] = 1345717340070545013;
+            }
+            if (j == 2) {
+                g[0][0][0] = 1;
+                g[1][0][0] = 16482405963202886985;
+                g[1][0][1] = 14019431448144408125;
+                g[1][0][2] = 6804334201956781232;
+                g[1][0][3] = 3486998266802970665;
+                g[2][0][0] = 16482405963202886984;
+                g[2][0][1] = 14019431448144408125;
+                g[2][0][2] = 6804334201956781232;
+                g[2][0][3] = 3486998266802970665;
+                g[3][0][0] = 4332616871279656262;
+                g[3][0][1] = 10917124144477883021;
+                g[3][0][2] = 13281191951274694749;
+                g[3][0][3] = 3486998266802970665;
+                g[4][0][0] = 6296954981786320894;
+                g[4][0][1] = 15344436770043026511;
+                g[4][0][2] = 6476857749317913516;
+                g[5][0][0] = 6296954981786320895;
+                g[5][0][1] = 15344436770043026511;
+                g[5][0][2] = 6476857749317913516;
+            }
+            if (j == 3) {
+                g[0][0][0] = 1;
+                g[1][0][0] = 16748743223498024575;
+                g[1][0][1] = 9893479902791193162;
+                g[1][0][2] = 17287454802551024272;
+                g[1][0][3] = 1863507075313886395;
+                g[1][1][0] = 8544641498795262209;
+                g[1][1][1] = 9152341264670407148;
+                g[1][1][2] = 489747644121748381;
+                g[1][1][3] = 48405681784978803;
+                g[2][0][0] = 8895857108753120109;
+                g[2][0][1] = 9250390599644299987;
+                g[2][0][2] = 13474519910052734380;
+                g[2][0][3] = 600914409377099530;
+                g[2][1][0] = 4038791169879410398;
+                g[2][1][1] = 1143687529350768177;
+                g[2][1][2] = 12246147677026650447;
+                g[2][1][3] = 356310219310069359;
+                g[3][0][0] = 6903044956535712237;
+                g[3][0][1] = 13535157066479988980;
+                g[3][0][2] = 15848588329623641763;
+                g[3][0][3] = 3037496999954262604;
+                g[3][1][0] = 13352342436154264676;
+                g[3][1][1] = 8503056439555616442;
+                g[3][1][2] = 12775463160270809394;
+                g[3][1][3] = 2928485131967568783;
+                g[4][0][0] = 1016286003096973414;
+                g[4][0][1] = 16587612046467558347;
+                g[4][0][2] = 1855167451033125966;
+                g[4][0][3] = 848238474841591211;
+                g[4][1][0] = 6909627513812296607;
+                g[4][1][1] = 11838448329631438565;
+                g[4][1][2] = 18352439988107484420;
+                g[4][1][3] = 2582226808490494482;
+                g[5][0][0] = 16996167662296243011;
+                g[5][0][1] = 3375026363608892581;
+                g[5][0][2] = 8148869875789283177;
+                g[5][0][3] = 1424422006390786309;
+                g[5][1][0] = 2244204471182173337;
+                g[5][1][1] = 4480477903397748769;
+                g[5][1][2] = 11507174408486150973;
+                g[5][1][3] = 1646969919133310219;
+            }
+    }
+    if (CURVE == 381) {
+            if (j == 1) {
+                g[0][0][0] = 1;
+                g[1][0][0] = 10162220747404304312;
+                g[1][0][1] = 17761815663483519293;
+                g[1][0][2] = 8873291758750579140;
+                g[1][0][3] = 1141103941765652303;
+                g[1][0][4] = 13993175198059990303;
+                g[1][0][5] = 1802798568193066599;
+                g[1][1][0] = 3240210268673559283;
+                g[1][1][1] = 2895069921743240898;
+                g[1][1][2] = 17009126888523054175;
+                g[1][1][3] = 6098234018649060207;
+                g[1][1][4] = 9865672654120263608;
+                g[1][1][5] = 71000049454473266;
+                g[2][1][0] = 10087218740379822764;
+                g[2][1][1] = 4653388206581612541;
+                g[2][1][2] = 9907120269317136283;
+                g[2][1][3] = 12253596935368579796;
+                g[2][1][4] = 17006226088849104517;
+                g[2][1][5] = 1873798617647539865;
+                g[3][0][0] = 14416168624775744521;
+                g[3][0][1] = 17178867732698629620;
+                g[3][0][2] = 8644499054833844677;
+                g[3][0][3] = 5204293836692734814;
+                g[3][0][4] = 7508032112903159806;
+                g[3][0][5] = 481619096434065419;
+                g[3][1][0] = 14416168624775744521;
+                g[3][1][1] = 17178867732698629620;
+                g[3][1][2] = 8644499054833844677;
+                g[3][1][3] = 5204293836692734814;
+                g[3][1][4] = 7508032112903159806;
+                g[3][1][5] = 481619096434065419;
+                g[4][0][0] = 10087218740379822765;
+                g[4][0][1] = 4653388206581612541;
+                g[4][0][2] = 9907120269317136283;
+                g[4][0][3] = 12253596935368579796;
+                g[4][0][4] = 17006226088849104517;
+                g[4][0][5] = 1873798617647539865;
+                g[5][0][0] = 11175958356102185238;
+                g[5][0][1] = 14283797810955388722;
+                g[5][0][2] = 10082116240020342118;
+                g[5][0][3] = 17552803891753226222;
+                g[5][0][4] = 16089103532492447813;
+                g[5][0][5] = 410619046979592152;
+                g[5][1][0] = 2226472659975678357;
+                g[5][1][1] = 6373087774271371469;
+                g[5][1][2] = 15800302407253291197;
+                g[5][1][3] = 8133278142371037904;
+                g[5][1][4] = 7769744319687806097;
+                g[5][1][5] = 1463179570667947713;
+            }
+            if (j == 2) {
+                g[0][0][0] = 1;
+                g[1][0][0] = 3315212275698040831;
+                g[1][0][1] = 16003497378645147650;
+                g[1][0][2] = 15975298377956497032;
+                g[1][0][3] = 13432485098755684330;
+                g[1][0][4] = 6852621763331149393;
+                g[2][0][0] = 3315212275698040830;
+                g[2][0][1] = 16003497378645147650;
+                g[2][0][2] = 15975298377956497032;
+                g[2][0][3] = 13432485098755684330;
+                g[2][0][4] = 6852621763331149393;
+                g[3][0][0] = 13402431016077863594;
+                g[3][0][1] = 2210141511517208575;
+                g[3][0][2] = 7435674573564081700;
+                g[3][0][3] = 7239337960414712511;
+                g[3][0][4] = 5412103778470702295;
+                g[3][0][5] = 1873798617647539866;
+                g[4][0][0] = 10087218740379822764;
+                g[4][0][1] = 4653388206581612541;
+                g[4][0][2] = 9907120269317136283;
+                g[4][0][3] = 12253596935368579796;
+                g[4][0][4] = 17006226088849104517;
+                g[4][0][5] = 1873798617647539865;
+                g[5][0][0] = 10087218740379822765;
+                g[5][0][1] = 4653388206581612541;
+                g[5][0][2] = 9907120269317136283;
+                g[5][0][3] = 12253596935368579796;
+                g[5][0][4] = 17006226088849104517;
+                g[5][0][5] = 1873798617647539865;
+            }
+            if (j == 3) {
+                g[0][0][0] = 1;
+                g[1][0][0] = 17433006465011670690;
+                g[1][0][1] = 3478017852528130570;
+                g[1][0][2] = 17237919592439788638;
+                g[1][0][3] = 2035044123721977696;
+                g[1][0][4] = 16350815739277094105;
+                g[1][0][5] = 1392179521213474446;
+                g[1][1][0] = 14416168624775744521;
+                g[1][1][1] = 17178867732698629620;
+                g[1][1][2] = 8644499054833844677;
+                g[1][1][3] = 5204293836692734814;
+                g[1][1][4] = 7508032112903159806;
+                g[1][1][5] = 481619096434065419;
+                g[2][1][0] = 1;
+                g[3][0][0] = 17433006465011670690;
+                g[3][0][1] = 3478017852528130570;
+                g[3][0][2] = 17237919592439788638;
+                g[3][0][3] = 2035044123721977696;
+                g[3][0][4] = 16350815739277094105;
+                g[3][0][5] = 1392179521213474446;
+                g[3][1][0] = 17433006465011670690;
+                g[3][1][1] = 3478017852528130570;
+                g[3][1][2] = 17237919592439788638;
+                g[3][1][3] = 2035044123721977696;
+                g[3][1][4] = 16350815739277094105;
+                g[3][1][5] = 1392179521213474446;
+                g[4][0][0] = 13402431016077863594;
+                g[4][0][1] = 2210141511517208575;
+                g[4][0][2] = 7435674573564081700;
+                g[4][0][3] = 7239337960414712511;
+                g[4][0][4] = 5412103778470702295;
+                g[4][0][5] = 1873798617647539866;
+                g[5][0][0] = 14416168624775744521;
+                g[5][0][1] = 17178867732698629620;
+                g[5][0][2] = 8644499054833844677;
+                g[5][0][3] = 5204293836692734814;
+                g[5][0][4] = 7508032112903159806;
+                g[5][0][5] = 481619096434065419;
+                g[5][1][0] = 17433006465011670690;
+                g[5][1][1] = 3478017852528130570;
+                g[5][1][2] = 17237919592439788638;
+                g[5][1][3] = 2035044123721977696;
+                g[5][1][4] = 16350815739277094105;
+                g[5][1][5] = 1392179521213474446;
+            }
+    }
+    return g;
+}
+
+// Frobenius sul twist: π(x, y) = (conj(x) c[0][0], conj(y) c[0][1]) e
+// π^2(x, y) = (x c[1][0], y c[1][1]), con c[j] = ξ^((p^j - 1) / 3), ξ^((p^j - 1) / 2)
+// per il twist D e i loro inversi per il twist M
+function curve_twist_frobenius(CURVE) {
+    var c[2][2][2][20];
+    if (CURVE == 254) {
+        c[0][0][0][0] = 11088870908804158781;
+        c[0][0][0][1] = 13226160682434769676;
+        c[0][0][0][2] = 5479733118184829251;
+        c[0][0][0][3] = 3437169660107756023;
+        c[0][0][1][0] = 1613930359396748194;
+        c[0][0][1][1] = 3651902652079185358;
+        c[0][0][1][2] = 5450706350010664852;
+        c[0][0][1][3] = 1642095672556236320;
+        c[0][1][0][0] = 15876315988453495642;
+        c[0][1][0][1] = 15828711151707445656;
+        c[0][1][0][2] = 15879347695360604601;
+        c[0][1][0][3] = 449501266848708060;
+        c[0][1][1][0] = 9427018508834943203;
+        c[0][1][1][1] = 2414067704922266578;
+        c[0][1][1][2] = 505728791003885355;
+        c[0][1][1][3] = 558513134835401882;
+        c[1][0][0][0] = 16482405963202886984;
+        c[1][0][0][1] = 14019431448144408125;
+        c[1][0][0][2] = 6804334201956781232;
+        c[1][0][0][3] = 3486998266802970665;
+        c[1][1][0][0] = 4332616871279656262;
+        c[1][1][0][1] = 10917124144477883021;
+        c[1][1][0][2] = 13281191951274694749;
+        c[1][1][0][3] = 3486998266802970665;
+    }
+    if (CURVE == 381) {
+        c[0][0][1][0] = 10087218740379822765;
+        c[0][0][1][1] = 4653388206581612541;
+        c[0][0][1][2] = 9907120269317136283;
+        c[0][0][1][3] = 12253596935368579796;
+        c[0][0][1][4] = 17006226088849104517;
+        c[0][0][1][5] = 1873798617647539865;
+        c[0][1][0][0] = 17433006465011670690;
+        c[0][1][0][1] = 3478017852528130570;
+        c[0][1][0][2] = 17237919592439788638;
+        c[0][1][0][3] = 2035044123721977696;
+        c[0][1][0][4] = 16350815739277094105;
+        c[0][1][0][5] = 1392179521213474446;
+        c[0][1][1][0] = 14416168624775744521;
+        c[0][1][1][1] = 17178867732698629620;
+        c[0][1][1][2] = 8644499054833844677;
+        c[0][1][1][3] = 5204293836692734814;
+        c[0][1][1][4] = 7508032112903159806;
+        c[0][1][1][5] = 481619096434065419;
+        c[1][0][0][0] = 10087218740379822764;
+        c[1][0][0][1] = 4653388206581612541;
+        c[1][0][0][2] = 9907120269317136283;
+        c[1][0][0][3] = 12253596935368579796;
+        c[1][0][0][4] = 17006226088849104517;
+        c[1][0][0][5] = 1873798617647539865;
+        c[1][1][0][0] = 13402431016077863594;
+        c[1][1][0][1] = 2210141511517208575;
+        c[1][1][0][2] = 7435674573564081700;
+        c[1][1][0][3] = 7239337960414712511;
+        c[1][1][0][4] = 5412103778470702295;
+        c[1][1][0][5] = 1873798617647539866;
+    }
+    return c;
+}
+
+// Parte difficile dell'esponenziazione finale, (p^4 - p^2 + 1) / r, in base p:
+// d[0] + d[1] p + d[2] p^2 + d[3] p^3
+function curve_final_exp_digits(CURVE) {
+    var d[4][20];
+    if (CURVE == 254) {
+        d[0][0] = 9626607166659623569;
+        d[0][1] = 6695204439272418282;
+        d[0][2] = 327476452638867716;
+        d[0][3] = 3486998266802970665;
+        d[1][0] = 1409399592883728581;
+        d[1][1] = 4288879887904940004;
+        d[1][2] = 327476452638867717;
+        d[1][3] = 3486998266802970665;
+        d[2][0] = 17887900258952609094;
+        d[2][1] = 8020209761171036667;
+        d[3][0] = 1;
+    }
+    if (CURVE == 381) {
+        d[0][0] = 12297641735351872172;
+        d[0][1] = 3711314990438422528;
+        d[0][2] = 7375212142131963423;
+        d[0][3] = 17844177929790668596;
+        d[0][4] = 3127896524026985830;
+        d[0][5] = 1873798617647539866;
+        d[1][0] = 8358680908399596884;
+        d[1][1] = 11337932287949712042;
+        d[1][2] = 14168084603834201570;
+        d[1][3] = 2784505619821483117;
+        d[2][0] = 2209297089338583723;
+        d[2][1] = 12861315885567174654;
+        d[2][2] = 4041292767038534792;
+        d[2][3] = 7239337960414712511;
+        d[2][4] = 5412103778470702295;
+        d[2][5] = 1873798617647539866;
+        d[3][0] = 10088250816726084266;
+        d[3][1] = 4137836090706223446;
+    }
+    return d;
+}
+
+// Numero di bit della cifra più lunga di curve_final_exp_digits
+function curve_final_exp_bits(CURVE) {
+    if (CURVE == 254) {
+        return 254;
+    }
+    if (CURVE == 381) {
+        return 381;
+    }
+    return 0;
+}
+
+// Generatore di G2 sul twist: g[0] = x, g[1] = y
+function curve_g2_generator(CURVE) {
+    var g[2][2][20];
+    if (CURVE == 254) {
+        g[0][0][0] = 5106727233969649389;
+        g[0][0][1] = 7440829307424791261;
+        g[0][0][2] = 4785637993704342649;
+        g[0][0][3] = 1729627375292849782;
+        g[0][1][0] = 10945020018377822914;
+        g[0][1][1] = 17413811393473931026;
+        g[0][1][2] = 8241798111626485029;
+        g[0][1][3] = 1841571559660931130;
+        g[1][0][0] = 5541340697920699818;
+        g[1][0][1] = 16416156555105522555;
+        g[1][0][2] = 5380518976772849807;
+        g[1][0][3] = 1353435754470862315;
+        g[1][1][0] = 6173549831154472795;
+        g[1][1][1] = 13567992399387660019;
+        g[1][1][2] = 17050234209342075797;
+        g[1][1][3] = 650358724130500725;
+    }
+    if (CURVE == 381) {
+        g[0][0][0] = 15312334153293348280;
+        g[0][0][1] = 841050694974028783;
+        g[0][0][2] = 12993178926126977399;
+        g[0][0][3] = 14331714969349929730;
+        g[0][0][4] = 2740446039084699729;
+        g[0][0][5] = 165123225776229009;
+        g[0][1][0] = 16549740192668593022;
+        g[0][1][1] = 3696594454104530263;
+        g[0][1][2] = 13103893525273989193;
+        g[0][1][3] = 6443473286224459290;
+        g[0][1][4] = 9055845637167730533;
+        g[0][1][5] = 1432192374203850592;
+        g[1][0][0] = 16254428414758889473;
+        g[1][0][1] = 10536956157198377609;
+        g[1][0][2] = 7873024875724591404;
+        g[1][0][3] = 12537348094477325223;
+        g[1][0][4] = 10144865889576432922;
+        g[1][0][5] = 929383263523139089;
+        g[1][1][0] = 12297368366147926462;
+        g[1][1][1] = 4555124010822409633;
+        g[1][1][2] = 2771000935339432363;
+        g[1][1][3] = 14645187562128761775;
+        g[1][1][4] = 3651525051980876697;
+        g[1][1][5] = 434250606344352972;
+    }
+    return g;
+}
+
+// Punto fisso R, partenza della somma delle chiavi in bls_aggregate_verify
+// (l'accumulatore non passa dal punto all'infinito). Derivato dal seed
+// "bls-zk-prover/aggregate/offset" senza logaritmo discreto noto: su BN254
+// try-and-increment con il DST BLS-ZK-PROVER-V01-BN254G2_XMD:SHA-256_TAI_ e
+// moltiplicazione per il cofattore (committee.rs), su BLS12-381 hash-to-curve
+// RFC 9380 con il DST BLS-ZK-PROVER-V01-BLS12381G2_XMD:SHA-256_SSWU_RO_.
+function curve_g2_offset(CURVE) {
+    var g[2][2][20];
+    if (CURVE == 254) {
+        g[0][0][0] = 14760406115719230257;
+        g[0][0][1] = 16139158871587419658;
+        g[0][0][2] = 10188379423395138044;
+        g[0][0][3] = 3381097473202838142;
+        g[0][1][0] = 10695585440889126963;
+        g[0][1][1] = 3499301302742205865;
+        g[0][1][2] = 16784360664764148129;
+        g[0][1][3] = 3122161144843285662;
+        g[1][0][0] = 15108288346785395276;
+        g[1][0][1] = 6629018052973005305;
+        g[1][0][2] = 3015586209522636997;
+        g[1][0][3] = 1121948015992178948;
+        g[1][1][0] = 7719627373716612414;
+        g[1][1][1] = 13584481062055341594;
+        g[1][1][2] = 6649333060471946184;
+        g[1][1][3] = 3460652361959529096;
+    }
+    if (CURVE == 381) {
+        g[0][0][0] = 1664899052279263196;
+        g[0][0][1] = 11445533588098833115;
+        g[0][0][2] = 2495011828932281545;
+        g[0][0][3] = 11136011882776668771;
+        g[0][0][4] = 17056994576369739887;
+        g[0][0][5] = 264909655913018644;
+        g[0][1][0] = 8035134064379320759;
+        g[0][1][1] = 5131842587010659740;
+        g[0][1][2] = 12236334838452098054;
+        g[0][1][3] = 13079558581440546198;
+        g[0][1][4] = 9722633986960293355;
+        g[0][1][5] = 1239862801702413624;
+        g[1][0][0] = 13336587011437041904;
+        g[1][0][1] = 15890502750775591682;
+        g[1][0][2] = 4845522188033790728;
+        g[1][0][3] = 9466546929528872273;
+        g[1][0][4] = 6739680193193385591;
+        g[1][0][5] = 1132620997139814663;
+        g[1][1][0] = 17390137833948700125;
+        g[1][1][1] = 1998610821980227721;
+        g[1][1][2] = 1137359788943387549;
+        g[1][1][3] = 16031119565632961267;
+        g[1][1][4] = 12607089011170924302;
+        g[1][1][5] = 83367790584533315;
+    }
+    return g;
+}
+
+// Punto fisso P, sommato al posto della chiave dei non partecipanti (il
+// risultato viene scartato). Stessa derivazione di R dal seed
+// "bls-zk-prover/aggregate/padding".
+function curve_g2_padding(CURVE) {
+    var g[2][2][20];
+    if (CURVE == 254) {
+        g[0][0][0] = 10867182280854602743;
+        g[0][0][1] = 7660046841670075716;
+        g[0][0][2] = 16288611864026047655;
+        g[0][0][3] = 1328189540984720874;
+        g[0][1][0] = 13318516379784436088;
+        g[0][1][1] = 11756581808478496263;
+        g[0][1][2] = 7323979549359269537;
+        g[0][1][3] = 1770904628499933812;
+        g[1][0][0] = 6921012613139787981;
+        g[1][0][1] = 14231098452356767323;
+        g[1][0][2] = 16083284063595162831;
+        g[1][0][3] = 1851131047310350689;
+        g[1][1][0] = 145822496835250848;
+        g[1][1][1] = 3531990050178819886;
+        g[1][1][2] = 11753588812172717315;
+        g[1][1][3] = 1457263762101388731;
+    }
+    if (CURVE == 381) {
+        g[0][0][0] = 9168897808228402235;
+        g[0][0][1] = 14407687313181196628;
+        g[0][0][2] = 16264157647500891858;
+        g[0][0][3] = 18275191591518225882;
+        g[0][0][4] = 8400800360474222789;
+        g[0][0][5] = 594526805578619623;
+        g[0][1][0] = 4400522088678693458;
+        g[0][1][1] = 17628860194866239883;
+        g[0][1][2] = 7547763392100775196;
+        g[0][1][3] = 12160858108393308206;
+        g[0][1][4] = 667774459930080418;
+        g[0][1][5] = 1595573410952113398;
+        g[1][0][0] = 1429131078321785494;
+        g[1][0][1] = 12031169581227264033;
+        g[1][0][2] = 14336171567953200333;
+        g[1][0][3] = 10451027956856822162;
+        g[1][0][4] = 12749653296897213985;
+        g[1][0][5] = 480668778166785906;
+        g[1][1][0] = 17945216466470803817;
+        g[1][1][1] = 14540184707769945719;
+        g[1][1][2] = 4438527573219992959;
+        g[1][1][3] = 17233261388821916332;
+        g[1][1][4] = 14247491658150793818;
+        g[1][1][5] = 825039503856252090;
+    }
+    return g;
+}
+
+// Generatore di G1: g[0] = x, g[1] = y
+function curve_g1_generator(CURVE) {
+    var g[2][20];
+    if (CURVE == 254) {
+        g[0][0] = 1;
+        g[1][0] = 2;
+    }
+    if (CURVE == 381) {
+        g[0][0] = 18103045581585958587;
+        g[0][1] = 7806400890582735599;
+        g[0][2] = 11623291730934869080;
+        g[0][3] = 14080658508445169925;
+        g[0][4] = 2780237799254240271;
+        g[0][5] = 1725392847304644500;
+        g[1][0] = 912580534683953121;
+        g[1][1] = 15005087156090211044;
+        g[1][2] = 61670280795567085;
+        g[1][3] = 18227722000993880822;
+        g[1][4] = 11573741888802228964;
+        g[1][5] = 627113611842199793;
+    }
+    return g;
+}
@@ -0,0 +1,193 @@
+pragma circom 2.1.0;
+
+/*
+ * Estensioni Fp2 = Fp[u] / (u^2 + 1) e Fp12 = Fp2[w] / (w^6 - ξ).
+ *
+ * Un elemento di Fp2 è [2][k] (c0 + c1 u), uno di Fp12 è [6][2][k]
+ * (Σ a_i w^i). I prodotti si accumulano senza riporti e ogni coordinata di
+ * Fp si riduce una sola volta alla fine.
+ */
+
+include "bigint.circom";
+
+// a b in Fp2 senza riduzione (Karatsuba):
+// c0 = a0 b0 - a1 b1, c1 = (a0 + a1)(b0 + b1) - a0 b0 - a1 b1.
+// Con |limb| < 2^n: |c0| < 2k 2^(2n), |c1| < 6k 2^(2n).
+template Fp2MulNoCarry(k) {
+    signal input a[2][k];
+    signal input b[2][k];
+    signal output out[2][2 * k - 1];
+
+    component m0 = BigMulNoCarry(k, k);
+    component m1 = BigMulNoCarry(k, k);
+    component m2 = BigMulNoCarry(k, k);
+    for (var i = 0; i < k; i++) {
+        m0.a[i] <== a[0][i];
+        m0.b[i] <== b[0][i];
+        m1.a[i] <== a[1][i];
+        m1.b[i] <== b[1][i];
+        m2.a[i] <== a[0][i] + a[1][i];
+        m2.b[i] <== b[0][i] + b[1][i];
+    }
+    for (var i = 0; i < 2 * k - 1; i++) {
+        out[0][i] <== m0.out[i] - m1.out[i];
+        out[1][i] <== m2.out[i] - m0.out[i] - m1.out[i];
+    }
+}
+
+template Fp2Reduce(CURVE) {
+    var k = curve_k(CURVE);
+
+    signal input in[2][2 * k - 1];
+    signal output out[2][k];
+
+    component reduce[2];
+    for (var c = 0; c < 2; c++) {
+        reduce[c] = FpReduce(CURVE);
+        for (var i = 0; i < 2 * k - 1; i++) {
+            reduce[c].in[i] <== in[c][i];
+        }
+        for (var i = 0; i < k; i++) {
+            out[c][i] <== reduce[c].out[i];
+        }
+    }
+}
+
+template Fp2CheckZero(CURVE) {
+    var k = curve_k(CURVE);
+
+    signal input in[2][2 * k - 1];
+
+    component check[2];
+    for (var c = 0; c < 2; c++) {
+        check[c] = FpCheckZero(CURVE);
+        for (var i = 0; i < 2 * k - 1; i++) {
+            check[c].in[i] <== in[c][i];
+        }
+    }
+}
+
+// a b in Fp12. I 36 prodotti in Fp2 si sommano per potenza di w, riportando
+// w^(i + j) con i + j >= 6 a ξ w^(i + j - 6), e si riduce una volta per coordinata.
+template Fp12Mul(CURVE) {
+    var k = curve_k(CURVE);
+    var xi[2] = curve_xi(CURVE);
+
+    signal input a[6][2][k];
+    signal input b[6][2][k];
+    signal output out[6][2][k];
+
+    var acc[6][2][2 * k - 1];
+    component mul[6][6];
+    for (var i = 0; i < 6; i++) {
+        for (var j = 0; j < 6; j++) {
+            mul[i][j] = Fp2MulNoCarry(k);
+            for (var c = 0; c < 2; c++) {
+                for (var l = 0; l < k; l++) {
+                    mul[i][j].a[c][l] <== a[i][c][l];
+                    mul[i][j].b[c][l] <== b[j][c][l];
+                }
+            }
+            for (var l = 0; l < 2 * k - 1; l++) {
+                if (i + j < 6) {
+                    acc[i + j][0][l] += mul[i][j].out[0][l];
+                    acc[i + j][1][l] += mul[i][j].out[1][l];
+                } else {
+                    acc[i + j - 6][0][l] += xi[0] * mul[i][j].out[0][l] - xi[1] * mul[i][j].out[1][l];
+                    acc[i + j - 6][1][l] += xi[0] * mul[i][j].out[1][l] + xi[1] * mul[i][j].out[0][l];
+                }
+            }
+        }
+    }
+
+    component reduce[6];
+    for (var i = 0; i < 6; i++) {
+        reduce[i] = Fp2Reduce(CURVE);
+        for (var c = 0; c < 2; c++) {
+            for (var l = 0; l < 2 * k - 1; l++) {
+                reduce[i].in[c][l] <== acc[i][c][l];
+            }
+        }
+        out[i] <== reduce[i].out;
+    }
+}
+
+// a l in Fp12, con l linea: tre coefficienti in Fp2 nelle posizioni
+// curve_line_positions (18 prodotti invece di 36)
+template Fp12MulByLine(CURVE) {
+    var k = curve_k(CURVE);
+    var xi[2] = curve_xi(CURVE);
+    var pos[3] = curve_line_positions(CURVE);
+
+    signal input a[6][2][k];
+    signal input line[3][2][k];
+    signal output out[6][2][k];
+
+    var acc[6][2][2 * k - 1];
+    component mul[6][3];
+    for (var i = 0; i < 6; i++) {
+        for (var j = 0; j < 3; j++) {
+            mul[i][j] = Fp2MulNoCarry(k);
+            for (var c = 0; c < 2; c++) {
+                for (var l = 0; l < k; l++) {
+                    mul[i][j].a[c][l] <== a[i][c][l];
+                    mul[i][j].b[c][l] <== line[j][c][l];
+                }
+            }
+            var e = i + pos[j];
+            for (var l = 0; l < 2 * k - 1; l++) {
+                if (e < 6) {
+                    acc[e][0][l] += mul[i][j].out[0][l];
+                    acc[e][1][l] += mul[i][j].out[1][l];
+                } else {
+                    acc[e - 6][0][l] += xi[0] * mul[i][j].out[0][l] - xi[1] * mul[i][j].out[1][l];
+                    acc[e - 6][1][l] += xi[0] * mul[i][j].out[1][l] + xi[1] * mul[i][j].out[0][l];
+                }
+            }
+        }
+    }
+
+    component reduce[6];
+    for (var i = 0; i < 6; i++) {
+        reduce[i] = Fp2Reduce(CURVE);
+        for (var c = 0; c < 2; c++) {
+            for (var l = 0; l < 2 * k - 1; l++) {
+                reduce[i].in[c][l] <== acc[i][c][l];
+            }
+        }
+        out[i] <== reduce[i].out;
+    }
+}
+
+// a^(p^j): coniugio dei coefficienti se j è dispari e prodotto per le
+// costanti γ(j, i) di curve_frobenius. Prodotti per costanti: solo vincoli lineari
+// più la riduzione.
+template Fp12Frobenius(CURVE, j) {
+    var k = curve_k(CURVE);
+    var g[6][2][20] = curve_frobenius(CURVE, j);
+    var sign = 1;
+    if (j % 2 == 1) {
+        sign = -1;
+    }
+
+    signal input in[6][2][k];
+    signal output out[6][2][k];
+
+    component reduce[6];
+    for (var i = 0; i < 6; i++) {
+        reduce[i] = Fp2Reduce(CURVE);
+        for (var l = 0; l < 2 * k - 1; l++) {
+            var re = 0;
+            var im = 0;
+            for (var s = 0; s < k; s++) {
+                if (l - s >= 0 && l - s < k) {
+                    re += g[i][0][s] * in[i][0][l - s] - sign * g[i][1][s] * in[i][1][l - s];
+                    im += sign * g[i][0][s] * in[i][1][l - s] + g[i][1][s] * in[i][0][l - s];
+                }
+            }
+            reduce[i].in[0][l] <== re;
+            reduce[i].in[1][l] <== im;
+        }
+        out[i] <== reduce[i].out;
+    }
+}
@@ -0,0 +1,709 @@
+pragma circom 2.1.0;
+
+/*
+ * Punti, Miller loop e controllo dell'esponenziazione finale.
+ *
+ * G1 è in coordinate affini su Fp, G2 in coordinate affini sul twist E'(Fp2).
+ * Le pendenze delle linee sono witness calcolati fuori dai vincoli e poi
+ * verificati (λ 2y = 3x^2 nel raddoppio, λ (xQ - xT) = yQ - yT nella somma),
+ * così ogni passo costa solo prodotti e riduzioni, senza inversi vincolati.
+ */
+
+include "fields.circom";
+
+// y^2 = x^3 + b su Fp
+template G1OnCurve(CURVE) {
+    var k = curve_k(CURVE);
+
+    signal input in[2][k];
+
+    component xx = BigMulNoCarry(k, k);
+    component xxReduce = FpReduce(CURVE);
+    component xxx = BigMulNoCarry(k, k);
+    component yy = BigMulNoCarry(k, k);
+    for (var i = 0; i < k; i++) {
+        xx.a[i] <== in[0][i];
+        xx.b[i] <== in[0][i];
+        yy.a[i] <== in[1][i];
+        yy.b[i] <== in[1][i];
+    }
+    for (var i = 0; i < 2 * k - 1; i++) {
+        xxReduce.in[i] <== xx.out[i];
+    }
+    for (var i = 0; i < k; i++) {
+        xxx.a[i] <== xxReduce.out[i];
+        xxx.b[i] <== in[0][i];
+    }
+
+    component check = FpCheckZero(CURVE);
+    for (var i = 0; i < 2 * k - 1; i++) {
+        if (i == 0) {
+            check.in[i] <== yy.out[i] - xxx.out[i] - curve_b(CURVE);
+        } else {
+            check.in[i] <== yy.out[i] - xxx.out[i];
+        }
+    }
+}
+
+// y^2 = x^3 + b' sul twist
+template G2OnCurve(CURVE) {
+    var k = curve_k(CURVE);
+    var b[2][20] = curve_twist_b(CURVE);
+
+    signal input in[2][2][k];
+
+    component xx = Fp2MulNoCarry(k);
+    component xxReduce = Fp2Reduce(CURVE);
+    component xxx = Fp2MulNoCarry(k);
+    component yy = Fp2MulNoCarry(k);
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < k; i++) {
+            xx.a[c][i] <== in[0][c][i];
+            xx.b[c][i] <== in[0][c][i];
+            yy.a[c][i] <== in[1][c][i];
+            yy.b[c][i] <== in[1][c][i];
+        }
+    }
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < 2 * k - 1; i++) {
+            xxReduce.in[c][i] <== xx.out[c][i];
+        }
+    }
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < k; i++) {
+            xxx.a[c][i] <== xxReduce.out[c][i];
+            xxx.b[c][i] <== in[0][c][i];
+        }
+    }
+
+    component check = Fp2CheckZero(CURVE);
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < 2 * k - 1; i++) {
+            if (i < k) {
+                check.in[c][i] <== yy.out[c][i] - xxx.out[c][i] - b[c][i];
+            } else {
+                check.in[c][i] <== yy.out[c][i] - xxx.out[c][i];
+            }
+        }
+    }
+}
+
+// Dato λ, il terzo punto della retta per T con ascissa xOther:
+// x3 = λ^2 - xT - xOther, y3 = λ (xT - x3) - yT
+template G2SlopeStep(CURVE) {
+    var k = curve_k(CURVE);
+
+    signal input lambda[2][k];
+    signal input T[2][2][k];
+    signal input xOther[2][k];
+    signal output out[2][2][k];
+
+    component ll = Fp2MulNoCarry(k);
+    component xReduce = Fp2Reduce(CURVE);
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < k; i++) {
+            ll.a[c][i] <== lambda[c][i];
+            ll.b[c][i] <== lambda[c][i];
+        }
+    }
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < 2 * k - 1; i++) {
+            if (i < k) {
+                xReduce.in[c][i] <== ll.out[c][i] - T[0][c][i] - xOther[c][i];
+            } else {
+                xReduce.in[c][i] <== ll.out[c][i];
+            }
+        }
+    }
+
+    component ld = Fp2MulNoCarry(k);
+    component yReduce = Fp2Reduce(CURVE);
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < k; i++) {
+            ld.a[c][i] <== lambda[c][i];
+            ld.b[c][i] <== T[0][c][i] - xReduce.out[c][i];
+        }
+    }
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < 2 * k - 1; i++) {
+            if (i < k) {
+                yReduce.in[c][i] <== ld.out[c][i] - T[1][c][i];
+            } else {
+                yReduce.in[c][i] <== ld.out[c][i];
+            }
+        }
+    }
+    out[0] <== xReduce.out;
+    out[1] <== yReduce.out;
+}
+
+// Coefficienti della linea per T con pendenza λ, valutata in P:
+// (yP, -λ xP, λ xT - yT), da porre nelle posizioni curve_line_positions
+template LineCoefficients(CURVE) {
+    var k = curve_k(CURVE);
+
+    signal input lambda[2][k];
+    signal input T[2][2][k];
+    signal input P[2][k];
+    signal output out[3][2][k];
+
+    for (var i = 0; i < k; i++) {
+        out[0][0][i] <== P[1][i];
+        out[0][1][i] <== 0;
+    }
+
+    component lx[2];
+    component lxReduce = Fp2Reduce(CURVE);
+    for (var c = 0; c < 2; c++) {
+        lx[c] = BigMulNoCarry(k, k);
+        for (var i = 0; i < k; i++) {
+            lx[c].a[i] <== lambda[c][i];
+            lx[c].b[i] <== P[0][i];
+        }
+        for (var i = 0; i < 2 * k - 1; i++) {
+            lxReduce.in[c][i] <== -lx[c].out[i];
+        }
+    }
+
+    component lt = Fp2MulNoCarry(k);
+    component ltReduce = Fp2Reduce(CURVE);
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < k; i++) {
+            lt.a[c][i] <== lambda[c][i];
+            lt.b[c][i] <== T[0][c][i];
+        }
+    }
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < 2 * k - 1; i++) {
+            if (i < k) {
+                ltReduce.in[c][i] <== lt.out[c][i] - T[1][c][i];
+            } else {
+                ltReduce.in[c][i] <== lt.out[c][i];
+            }
+        }
+    }
+    out[1] <== lxReduce.out;
+    out[2] <== ltReduce.out;
+}
+
+// T -> 2T, con la pendenza λ della tangente in T
+template G2Double(CURVE) {
+    var n = curve_n(CURVE);
+    var k = curve_k(CURVE);
+    var p[20] = curve_prime(CURVE);
+
+    signal input T[2][2][k];
+    signal output out[2][2][k];
+    signal output lambda[2][k];
+
+    // λ = 3 x^2 / 2y
+    var x[2][20];
+    var y[2][20];
+    var three[2][20];
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < k; i++) {
+            x[c][i] = T[0][c][i];
+            y[c][i] = T[1][c][i];
+        }
+    }
+    three[0][0] = 3;
+    var xx[2][20] = fp2_mul(n, k, x, x, p);
+    var num[2][20] = fp2_mul(n, k, three, xx, p);
+    var den[2][20] = fp2_add(n, k, y, y, p);
+    var slope[2][20] = fp2_div(n, k, num, den, p);
+
+    component range[2][k];
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < k; i++) {
+            lambda[c][i] <-- slope[c][i];
+            range[c][i] = Num2Bits(n);
+            range[c][i].in <== lambda[c][i];
+        }
+    }
+
+    // λ 2y - 3x^2 ≡ 0
+    component ly = Fp2MulNoCarry(k);
+    component sq = Fp2MulNoCarry(k);
+    component check = Fp2CheckZero(CURVE);
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < k; i++) {
+            ly.a[c][i] <== lambda[c][i];
+            ly.b[c][i] <== T[1][c][i];
+            sq.a[c][i] <== T[0][c][i];
+            sq.b[c][i] <== T[0][c][i];
+        }
+    }
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < 2 * k - 1; i++) {
+            check.in[c][i] <== 2 * ly.out[c][i] - 3 * sq.out[c][i];
+        }
+    }
+
+    component step = G2SlopeStep(CURVE);
+    step.lambda <== lambda;
+    step.T <== T;
+    step.xOther <== T[0];
+    out <== step.out;
+}
+
+// T -> 2T e linea tangente in T valutata in P
+template G2DoubleStep(CURVE) {
+    var k = curve_k(CURVE);
+
+    signal input T[2][2][k];
+    signal input P[2][k];
+    signal output out[2][2][k];
+    signal output line[3][2][k];
+
+    component dbl = G2Double(CURVE);
+    dbl.T <== T;
+
+    component lineCoeffs = LineCoefficients(CURVE);
+    lineCoeffs.lambda <== dbl.lambda;
+    lineCoeffs.T <== T;
+    lineCoeffs.P <== P;
+    out <== dbl.out;
+    line <== lineCoeffs.out;
+}
+
+// T -> T + Q, con la pendenza λ della retta per T e Q. xQ != xT è vincolato
+// con un inverso: senza, per T = Q la pendenza sarebbe libera.
+template G2Add(CURVE) {
+    var n = curve_n(CURVE);
+    var k = curve_k(CURVE);
+    var p[20] = curve_prime(CURVE);
+
+    signal input T[2][2][k];
+    signal input Q[2][2][k];
+    signal output out[2][2][k];
+    signal output lambda[2][k];
+    signal inv[2][k];
+
+    // λ = (yQ - yT) / (xQ - xT)
+    var xt[2][20];
+    var yt[2][20];
+    var xq[2][20];
+    var yq[2][20];
+    var one[2][20];
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < k; i++) {
+            xt[c][i] = T[0][c][i];
+            yt[c][i] = T[1][c][i];
+            xq[c][i] = Q[0][c][i];
+            yq[c][i] = Q[1][c][i];
+        }
+    }
+    one[0][0] = 1;
+    var dx[2][20] = fp2_sub(n, k, xq, xt, p);
+    var dy[2][20] = fp2_sub(n, k, yq, yt, p);
+    var dxInv[2][20] = fp2_div(n, k, one, dx, p);
+    var slope[2][20] = fp2_mul(n, k, dy, dxInv, p);
+
+    component range[2][2][k];
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < k; i++) {
+            lambda[c][i] <-- slope[c][i];
+            inv[c][i] <-- dxInv[c][i];
+            range[0][c][i] = Num2Bits(n);
+            range[0][c][i].in <== lambda[c][i];
+            range[1][c][i] = Num2Bits(n);
+            range[1][c][i].in <== inv[c][i];
+        }
+    }
+
+    // inv (xQ - xT) ≡ 1 e λ (xQ - xT) ≡ yQ - yT
+    component dxInvMul = Fp2MulNoCarry(k);
+    component slopeMul = Fp2MulNoCarry(k);
+    component invCheck = Fp2CheckZero(CURVE);
+    component slopeCheck = Fp2CheckZero(CURVE);
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < k; i++) {
+            dxInvMul.a[c][i] <== inv[c][i];
+            dxInvMul.b[c][i] <== Q[0][c][i] - T[0][c][i];
+            slopeMul.a[c][i] <== lambda[c][i];
+            slopeMul.b[c][i] <== Q[0][c][i] - T[0][c][i];
+        }
+    }
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < 2 * k - 1; i++) {
+            if (c == 0 && i == 0) {
+                invCheck.in[c][i] <== dxInvMul.out[c][i] - 1;
+            } else {
+                invCheck.in[c][i] <== dxInvMul.out[c][i];
+            }
+            if (i < k) {
+                slopeCheck.in[c][i] <== slopeMul.out[c][i] - Q[1][c][i] + T[1][c][i];
+            } else {
+                slopeCheck.in[c][i] <== slopeMul.out[c][i];
+            }
+        }
+    }
+
+    component step = G2SlopeStep(CURVE);
+    step.lambda <== lambda;
+    step.T <== T;
+    step.xOther <== Q[0];
+    out <== step.out;
+}
+
+// T -> T + Q e linea per T e Q valutata in P
+template G2AddStep(CURVE) {
+    var k = curve_k(CURVE);
+
+    signal input T[2][2][k];
+    signal input Q[2][2][k];
+    signal input P[2][k];
+    signal output out[2][2][k];
+    signal output line[3][2][k];
+
+    component add = G2Add(CURVE);
+    add.T <== T;
+    add.Q <== Q;
+
+    component lineCoeffs = LineCoefficients(CURVE);
+    lineCoeffs.lambda <== add.lambda;
+    lineCoeffs.T <== T;
+    lineCoeffs.P <== P;
+    out <== add.out;
+    line <== lineCoeffs.out;
+}
+
+// Frobenius sul twist: π(Q) per j = 1, -π^2(Q) per j = 2 (le due linee
+// finali dell'ate ottimale su BN)
+template G2FrobeniusStep(CURVE, j) {
+    var k = curve_k(CURVE);
+    var consts[2][2][2][20] = curve_twist_frobenius(CURVE);
+    var sign = 1;
+    if (j % 2 == 1) {
+        sign = -1;
+    }
+
+    signal input in[2][2][k];
+    signal output out[2][2][k];
+
+    component reduce[2];
+    for (var a = 0; a < 2; a++) {
+        // -π^2(Q): si nega la y
+        var neg = 1;
+        if (j == 2 && a == 1) {
+            neg = -1;
+        }
+        reduce[a] = Fp2Reduce(CURVE);
+        for (var l = 0; l < 2 * k - 1; l++) {
+            var re = 0;
+            var im = 0;
+            for (var s = 0; s < k; s++) {
+                if (l - s >= 0 && l - s < k) {
+                    re += consts[j - 1][a][0][s] * in[a][0][l - s] - sign * consts[j - 1][a][1][s] * in[a][1][l - s];
+                    im += sign * consts[j - 1][a][0][s] * in[a][1][l - s] + consts[j - 1][a][1][s] * in[a][0][l - s];
+                }
+            }
+            reduce[a].in[0][l] <== neg * re;
+            reduce[a].in[1][l] <== neg * im;
+        }
+        out[a] <== reduce[a].out;
+    }
+}
+
+// Appartenenza a G2 sulle curve BLS12 (x < 0): Q ∈ G2 se e solo se ψ(Q) = [x] Q,
+// cioè [|x|] Q + ψ(Q) = O (Scott, "A note on group membership tests for G1, G2
+// and GT on BLS pairing-friendly curves"). ψ è il Frobenius sul twist
+// (G2FrobeniusStep con j = 1). [|x|] Q con double-and-add sui bit di
+// curve_loop_bits: per Q di ordine r i punti intermedi non valgono mai ±Q,
+// quindi i vincoli di G2Add sono soddisfacibili; per Q fuori da G2 il
+// controllo finale (o un passo intermedio) fallisce.
+template G2SubgroupCheck(CURVE) {
+    var k = curve_k(CURVE);
+    var L = curve_loop_length(CURVE);
+    var bits[70] = curve_loop_bits(CURVE);
+    assert(curve_is_bn(CURVE) == 0);
+
+    signal input in[2][2][k];
+
+    signal acc[L][2][2][k];
+    component dbl[L - 1];
+    component add[L - 1];
+    acc[0] <== in;
+    for (var i = 1; i < L; i++) {
+        dbl[i - 1] = G2Double(CURVE);
+        dbl[i - 1].T <== acc[i - 1];
+        if (bits[i] == 1) {
+            add[i - 1] = G2Add(CURVE);
+            add[i - 1].T <== dbl[i - 1].out;
+            add[i - 1].Q <== in;
+            acc[i] <== add[i - 1].out;
+        } else {
+            acc[i] <== dbl[i - 1].out;
+        }
+    }
+
+    component psi = G2FrobeniusStep(CURVE, 1);
+    psi.in <== in;
+
+    // x uguali e y opposte modulo p
+    component xCheck = Fp2CheckZero(CURVE);
+    component yCheck = Fp2CheckZero(CURVE);
+    for (var c = 0; c < 2; c++) {
+        for (var i = 0; i < 2 * k - 1; i++) {
+            if (i < k) {
+                xCheck.in[c][i] <== acc[L - 1][0][c][i] - psi.out[0][c][i];
+                yCheck.in[c][i] <== acc[L - 1][1][c][i] + psi.out[1][c][i];
+            } else {
+                xCheck.in[c][i] <== 0;
+                yCheck.in[c][i] <== 0;
+            }
+        }
+    }
+}
+
+// Un bit del Miller loop su N coppie (P_j, Q_j) con accumulatore condiviso:
+// f = f^2, poi le tangenti in T_j e, se il bit vale 1, le linee per T_j e Q_j.
+template MillerStep(CURVE, N, addBit) {
+    var k = curve_k(CURVE);
+
+    signal input f[6][2][k];
+    signal input T[N][2][2][k];
+    signal input P[N][2][k];
+    signal input Q[N][2][2][k];
+    signal output fOut[6][2][k];
+    signal output TOut[N][2][2][k];
+
+    var muls = N + addBit * N;
+    component square;
+    component dbl[N];
+    component add[N];
+    component mulLine[muls];
+
+    square = Fp12Mul(CURVE);
+    square.a <== f;
+    square.b <== f;
+    for (var j = 0; j < N; j++) {
+        dbl[j] = G2DoubleStep(CURVE);
+        dbl[j].T <== T[j];
+        dbl[j].P <== P[j];
+
+        mulLine[j] = Fp12MulByLine(CURVE);
+        if (j > 0) {
+            mulLine[j].a <== mulLine[j - 1].out;
+        } else {
+            mulLine[j].a <== square.out;
+        }
+        mulLine[j].line <== dbl[j].line;
+    }
+    for (var j = 0; j < N * addBit; j++) {
+        add[j] = G2AddStep(CURVE);
+        add[j].T <== dbl[j].out;
+        add[j].Q <== Q[j];
+        add[j].P <== P[j];
+
+        mulLine[N + j] = Fp12MulByLine(CURVE);
+        mulLine[N + j].a <== mulLine[N + j - 1].out;
+        mulLine[N + j].line <== add[j].line;
+    }
+
+    fOut <== mulLine[muls - 1].out;
+    for (var j = 0; j < N; j++) {
+        if (addBit == 1) {
+            TOut[j] <== add[j].out;
+        } else {
+            TOut[j] <== dbl[j].out;
+        }
+    }
+}
+
+// Linee finali dell'ate ottimale su BN: T + π(Q) e poi T + π(Q) - π^2(Q)
+template MillerFrobeniusStep(CURVE, N) {
+    var k = curve_k(CURVE);
+
+    signal input f[6][2][k];
+    signal input T[N][2][2][k];
+    signal input P[N][2][k];
+    signal input Q[N][2][2][k];
+    signal output fOut[6][2][k];
+
+    component q1[N];
+    component q2[N];
+    component add1[N];
+    component add2[N];
+    component mulLine[2 * N];
+    for (var j = 0; j < N; j++) {
+        q1[j] = G2FrobeniusStep(CURVE, 1);
+        q1[j].in <== Q[j];
+        q2[j] = G2FrobeniusStep(CURVE, 2);
+        q2[j].in <== Q[j];
+
+        add1[j] = G2AddStep(CURVE);
+        add1[j].T <== T[j];
+        add1[j].Q <== q1[j].out;
+        add1[j].P <== P[j];
+        add2[j] = G2AddStep(CURVE);
+        add2[j].T <== add1[j].out;
+        add2[j].Q <== q2[j].out;
+        add2[j].P <== P[j];
+
+        mulLine[2 * j] = Fp12MulByLine(CURVE);
+        if (j == 0) {
+            mulLine[2 * j].a <== f;
+        } else {
+            mulLine[2 * j].a <== mulLine[2 * j - 1].out;
+        }
+        mulLine[2 * j].line <== add1[j].line;
+        mulLine[2 * j + 1] = Fp12MulByLine(CURVE);
+        mulLine[2 * j + 1].a <== mulLine[2 * j].out;
+        mulLine[2 * j + 1].line <== add2[j].line;
+    }
+    fOut <== mulLine[2 * N - 1].out;
+}
+
+// Π_j f(P_j, Q_j), prodotto dei Miller loop delle N coppie.
+// P_j su E(Fp) e Q_j su E'(Fp2) in affini, limb con |limb| < 2^n; le y di P
+// possono avere limb negativi (per -P basta negarli).
+template MillerLoop(CURVE, N) {
+    var k = curve_k(CURVE);
+    var L = curve_loop_length(CURVE);
+    var bits[70] = curve_loop_bits(CURVE);
+
+    signal input P[N][2][k];
+    signal input Q[N][2][2][k];
+    signal output out[6][2][k];
+
+    // Il primo bit (1) è T = Q, f = 1; al primo passo f^2 = 1 costa un prodotto
+    component steps[L - 1];
+    component frobenius;
+    for (var i = 1; i < L; i++) {
+        steps[i - 1] = MillerStep(CURVE, N, bits[i]);
+        if (i == 1) {
+            for (var w = 0; w < 6; w++) {
+                for (var c = 0; c < 2; c++) {
+                    for (var l = 0; l < k; l++) {
+                        if (w == 0 && c == 0 && l == 0) {
+                            steps[0].f[w][c][l] <== 1;
+                        } else {
+                            steps[0].f[w][c][l] <== 0;
+                        }
+                    }
+                }
+            }
+            steps[0].T <== Q;
+        } else {
+            steps[i - 1].f <== steps[i - 2].fOut;
+            steps[i - 1].T <== steps[i - 2].TOut;
+        }
+        steps[i - 1].P <== P;
+        steps[i - 1].Q <== Q;
+    }
+
+    if (curve_is_bn(CURVE) == 1) {
+        frobenius = MillerFrobeniusStep(CURVE, N);
+        frobenius.f <== steps[L - 2].fOut;
+        frobenius.T <== steps[L - 2].TOut;
+        frobenius.P <== P;
+        frobenius.Q <== Q;
+        out <== frobenius.fOut;
+    } else {
+        out <== steps[L - 2].fOut;
+    }
+}
+
+// Vincola f^((p^12 - 1) / r) = 1.
+// (p^12 - 1) / r = (p^6 - 1)(p^2 + 1) e con e = (p^4 - p^2 + 1) / r;
+// f^((p^6 - 1) M) = 1 equivale a f^M ∈ Fp6 (coniugato uguale a se stesso),
+// cioè coefficienti dispari di w nulli: niente inverso in Fp12.
+// f != 0 perché ogni linea ha il coefficiente yP != 0 (G1 non ha punti di ordine 2).
+// h = f^(p^2 + 1), poi h^e come multi-esponenziazione sulle cifre di e in base p:
+// h^e = Π (h^(p^i))^(d_i), con una tabella dei 15 prodotti non vuoti di h^(p^i).
+template FinalExpCheck(CURVE) {
+    var n = curve_n(CURVE);
+    var k = curve_k(CURVE);
+    var d[4][20] = curve_final_exp_digits(CURVE);
+    var NB = curve_final_exp_bits(CURVE);
+
+    signal input in[6][2][k];
+
+    component frob2 = Fp12Frobenius(CURVE, 2);
+    frob2.in <== in;
+    component h = Fp12Mul(CURVE);
+    h.a <== frob2.out;
+    h.b <== in;
+
+    component frob[3];
+    for (var j = 0; j < 3; j++) {
+        frob[j] = Fp12Frobenius(CURVE, j + 1);
+        frob[j].in <== h.out;
+    }
+
+    // table[m] = Π_{i: bit i di m} h^(p^i)
+    signal table[16][6][2][k];
+    component tableMul[16];
+    for (var m = 1; m < 16; m++) {
+        var low = 0;
+        while (((m >> low) & 1) == 0) {
+            low++;
+        }
+        var rest = m - (1 << low);
+        if (rest == 0) {
+            if (low == 0) {
+                table[m] <== h.out;
+            } else {
+                table[m] <== frob[low - 1].out;
+            }
+        } else {
+            tableMul[m] = Fp12Mul(CURVE);
+            tableMul[m].a <== table[rest];
+            if (low == 0) {
+                tableMul[m].b <== h.out;
+            } else {
+                tableMul[m].b <== frob[low - 1].out;
+            }
+            table[m] <== tableMul[m].out;
+        }
+    }
+    for (var w = 0; w < 6; w++) {
+        for (var c = 0; c < 2; c++) {
+            for (var l = 0; l < k; l++) {
+                table[0][w][c][l] <== 0;
+            }
+        }
+    }
+
+    // Dal bit più alto: acc = acc^2 · table[mask]
+    signal acc[NB][6][2][k];
+    component square[NB];
+    component mul[NB];
+    for (var b = NB - 1; b >= 0; b--) {
+        var mask = 0;
+        for (var i = 0; i < 4; i++) {
+            mask += ((d[i][b \ n] >> (b % n)) & 1) << i;
+        }
+        if (b == NB - 1) {
+            acc[b] <== table[mask];
+        } else {
+            square[b] = Fp12Mul(CURVE);
+            square[b].a <== acc[b + 1];
+            square[b].b <== acc[b + 1];
+            if (mask == 0) {
+                acc[b] <== square[b].out;
+            } else {
+                mul[b] = Fp12Mul(CURVE);
+                mul[b].a <== square[b].out;
+                mul[b].b <== table[mask];
+                acc[b] <== mul[b].out;
+            }
+        }
+    }
+
+    // Coefficienti di w, w^3, w^5 nulli modulo p
+    component check[3];
+    for (var i = 0; i < 3; i++) {
+        check[i] = Fp2CheckZero(CURVE);
+        for (var c = 0; c < 2; c++) {
+            for (var l = 0; l < 2 * k - 1; l++) {
+                if (l < k) {
+                    check[i].in[c][l] <== acc[0][2 * i + 1][c][l];
+                } else {
+                    check[i].in[c][l] <== 0;
+                }
+            }
+        }
+    }
+}
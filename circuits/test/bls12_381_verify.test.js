@@ -0,0 +1,136 @@
+const assert = require("assert");
+const path = require("path");
+const { wasm: wasmTester } = require("circom_tester");
+const { buildBls12381 } = require("ffjavascript");
+
+/**
+ * Test di bls12_381_verify.circom (chiavi pubbliche in G1) e di
+ * G2SubgroupCheck (circuits/test/g2_subgroup_check.circom)
+ *
+ * Come in bls_verify.test.js: H(m) = h · g2, pk = sk · g1, σ = sk · H(m),
+ * calcolati con ffjavascript. La compilazione richiede circom nel PATH.
+ */
+
+const NODE_MODULES = path.join(__dirname, "..", "..", "node_modules");
+
+// Punto del twist con x = 2: sulla curva ma fuori da G2 (cofattore ≠ 1),
+// calcolato con ark-bls12-381
+const NON_G2_POINT = [
+    ["2", "0"],
+    [
+        "188995492400578496451910581292546059920654572609832469388872107051048741028892423057992033888655218419282460458611",
+        "434381874456081807472298918693162486998243066160460423017297172308631992219110538691921044767658182807847155297615",
+    ],
+];
+
+// Elemento di Fp in `count` cifre da `bits` bit, little endian
+function digits(value, count, bits) {
+    const mask = (1n << BigInt(bits)) - 1n;
+    return Array.from({ length: count }, (_, i) =>
+        ((BigInt(value) >> BigInt(bits * i)) & mask).toString()
+    );
+}
+
+function g2Coordinates(curve, point) {
+    const [x, y] = curve.G2.toObject(curve.G2.toAffine(point));
+    return [x, y];
+}
+
+describe("bls12_381_verify.circom", function () {
+    this.timeout(0);
+
+    const W = 3;
+    let curve;
+    let circuit;
+    let valid;
+
+    const words = (value) => digits(value, W, 128);
+
+    function signatureInput(h, sk, signer = sk) {
+        const messageHash = curve.G2.timesFr(curve.G2.g, curve.Fr.e(h));
+        const [pkX, pkY] = curve.G1.toObject(
+            curve.G1.toAffine(curve.G1.timesFr(curve.G1.g, curve.Fr.e(sk)))
+        );
+        const signature = curve.G2.timesFr(messageHash, curve.Fr.e(signer));
+        return {
+            publicKey: [words(pkX), words(pkY)],
+            messageHash: g2Coordinates(curve, messageHash).map((c) => c.map(words)),
+            signature: g2Coordinates(curve, signature).map((c) => c.map(words)),
+        };
+    }
+
+    before(async function () {
+        curve = await buildBls12381();
+        circuit = await wasmTester(path.join(__dirname, "..", "bls12_381_verify.circom"), {
+            include: NODE_MODULES,
+        });
+        valid = signatureInput(123456789n, 987654321n);
+    });
+
+    after(async function () {
+        await curve.terminate();
+    });
+
+    it("Dovrebbe accettare una firma valida", async function () {
+        const witness = await circuit.calculateWitness(valid, true);
+        await circuit.checkConstraints(witness);
+    });
+
+    it("Dovrebbe rifiutare una firma manomessa", async function () {
+        const wrongSigner = signatureInput(123456789n, 987654321n, 987654322n);
+        await assert.rejects(
+            circuit.calculateWitness({ ...valid, signature: wrongSigner.signature }, true)
+        );
+
+        // σ sulla curva ma fuori da G2
+        const outsideG2 = NON_G2_POINT.map((c) => c.map(words));
+        await assert.rejects(circuit.calculateWitness({ ...valid, signature: outsideG2 }, true));
+    });
+
+    it("Dovrebbe rifiutare una chiave pubblica o un H(m) diversi", async function () {
+        const otherKey = signatureInput(123456789n, 987654322n);
+        await assert.rejects(
+            circuit.calculateWitness({ ...valid, publicKey: otherKey.publicKey }, true)
+        );
+
+        const otherMessage = signatureInput(123456790n, 987654321n);
+        await assert.rejects(
+            circuit.calculateWitness({ ...valid, messageHash: otherMessage.messageHash }, true)
+        );
+    });
+});
+
+describe("G2SubgroupCheck (BLS12-381)", function () {
+    this.timeout(0);
+
+    const K = 6;
+    let curve;
+    let circuit;
+
+    const limbs = (value) => digits(value, K, 64);
+
+    before(async function () {
+        curve = await buildBls12381();
+        circuit = await wasmTester(path.join(__dirname, "g2_subgroup_check.circom"), {
+            include: NODE_MODULES,
+        });
+    });
+
+    after(async function () {
+        await curve.terminate();
+    });
+
+    it("Dovrebbe accettare punti di G2", async function () {
+        for (const scalar of [1n, 987654321n]) {
+            const point = curve.G2.timesFr(curve.G2.g, curve.Fr.e(scalar));
+            const input = { in: g2Coordinates(curve, point).map((c) => c.map(limbs)) };
+            const witness = await circuit.calculateWitness(input, true);
+            await circuit.checkConstraints(witness);
+        }
+    });
+
+    it("Dovrebbe rifiutare un punto del twist fuori da G2", async function () {
+        const input = { in: NON_G2_POINT.map((c) => c.map(limbs)) };
+        await assert.rejects(circuit.calculateWitness(input, true));
+    });
+});
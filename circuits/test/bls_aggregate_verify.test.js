@@ -0,0 +1,124 @@
+const assert = require("assert");
+const path = require("path");
+const { wasm: wasmTester } = require("circom_tester");
+const { buildBn128 } = require("ffjavascript");
+const { buildPoseidon } = require("circomlibjs");
+
+/**
+ * Test di bls_aggregate_verify.circom (comitato da N = 16 chiavi in G2)
+ *
+ * pk_i = sk_i · g2, H(m) = h · g1 e σ = (Σ sk_i dei firmatari) · H(m), con
+ * ffjavascript. committeeRoot è la catena di Poseidon di Committee::root,
+ * calcolata con circomlibjs. La compilazione richiede circom nel PATH.
+ */
+
+const N = 16;
+const W = 2;
+
+// Coordinata in Fp → parole da 128 bit little endian, come negli input
+function words(value) {
+    return Array.from({ length: W }, (_, i) =>
+        ((BigInt(value) >> BigInt(128 * i)) & ((1n << 128n) - 1n)).toString()
+    );
+}
+
+// Bitmap in parole da 128 bit: il partecipante i è il bit i % 128 della parola i / 128
+function bitmapWords(signers) {
+    const bitmap = Array.from({ length: Math.ceil(N / 128) }, () => 0n);
+    for (const i of signers) {
+        bitmap[Math.floor(i / 128)] |= 1n << BigInt(i % 128);
+    }
+    return bitmap.map((word) => word.toString());
+}
+
+describe("bls_aggregate_verify.circom", function () {
+    this.timeout(0);
+
+    const H = 123456789n;
+    const SIGNERS = [0, 2, 3, 7, 15];
+    let curve;
+    let poseidon;
+    let circuit;
+    let secretKeys;
+    let valid;
+
+    function g1Words(point) {
+        const [x, y] = curve.G1.toObject(curve.G1.toAffine(point));
+        return [words(x), words(y)];
+    }
+
+    // pk nell'ordine x.c0, x.c1, y.c0, y.c1
+    function g2Words(point) {
+        const [x, y] = curve.G2.toObject(curve.G2.toAffine(point));
+        return [
+            [words(x[0]), words(x[1])],
+            [words(y[0]), words(y[1])],
+        ];
+    }
+
+    // root = Poseidon(root, v) su tutte le parole delle chiavi, partendo da 0
+    function committeeRoot(publicKeys) {
+        let root = 0n;
+        for (const word of publicKeys.flat(3)) {
+            root = poseidon.F.toObject(poseidon([root, BigInt(word)]));
+        }
+        return root.toString();
+    }
+
+    function aggregateInput(keys, signers) {
+        const publicKeys = keys.map((sk) => g2Words(curve.G2.timesFr(curve.G2.g, curve.Fr.e(sk))));
+        const messageHash = curve.G1.timesFr(curve.G1.g, curve.Fr.e(H));
+        const aggregateKey = signers.reduce((acc, i) => acc + keys[i], 0n);
+        return {
+            committeeRoot: committeeRoot(publicKeys),
+            messageHash: g1Words(messageHash),
+            bitmap: bitmapWords(signers),
+            publicKeys,
+            signature: g1Words(curve.G1.timesFr(messageHash, curve.Fr.e(aggregateKey))),
+        };
+    }
+
+    before(async function () {
+        curve = await buildBn128();
+        poseidon = await buildPoseidon();
+        circuit = await wasmTester(path.join(__dirname, "..", "bls_aggregate_verify.circom"), {
+            include: path.join(__dirname, "..", "..", "node_modules"),
+        });
+        secretKeys = Array.from({ length: N }, (_, i) => 987654321n + 1000n * BigInt(i));
+        valid = aggregateInput(secretKeys, SIGNERS);
+    });
+
+    after(async function () {
+        await curve.terminate();
+    });
+
+    it("Dovrebbe accettare una firma aggregata con bitmap parziale", async function () {
+        const witness = await circuit.calculateWitness(valid, true);
+        await circuit.checkConstraints(witness);
+    });
+
+    it("Dovrebbe rifiutare una bitmap con un firmatario in più", async function () {
+        const bitmap = bitmapWords([...SIGNERS, 1]);
+        await assert.rejects(circuit.calculateWitness({ ...valid, bitmap }, true));
+    });
+
+    it("Dovrebbe rifiutare chiavi diverse da quelle di committeeRoot", async function () {
+        // Chiave di un non firmatario sostituita: la firma resta valida, la root no
+        const otherKeys = [...secretKeys];
+        otherKeys[5] += 1n;
+        const other = aggregateInput(otherKeys, SIGNERS);
+        await assert.rejects(
+            circuit.calculateWitness({ ...valid, publicKeys: other.publicKeys }, true)
+        );
+
+        // Root di un altro comitato con le chiavi registrate
+        await assert.rejects(
+            circuit.calculateWitness({ ...valid, committeeRoot: other.committeeRoot }, true)
+        );
+    });
+
+    it("Dovrebbe rifiutare bit della bitmap oltre N", async function () {
+        const bitmap = [(BigInt(valid.bitmap[0]) | (1n << BigInt(N))).toString()];
+        await assert.rejects(circuit.calculateWitness({ ...valid, bitmap }, true));
+    });
+});
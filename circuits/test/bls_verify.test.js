@@ -0,0 +1,94 @@
+const assert = require("assert");
+const path = require("path");
+const { wasm: wasmTester } = require("circom_tester");
+const { buildBn128 } = require("ffjavascript");
+
+/**
+ * Test del circuito bls_verify.circom (BN254, chiavi pubbliche in G2)
+ *
+ * Gli input sono calcolati con le operazioni su curva di ffjavascript:
+ * H(m) = h · g1, pk = sk · g2, σ = sk · H(m). Il circuito non calcola
+ * l'hash to curve, quindi un multiplo noto di g1 basta come H(m).
+ * La compilazione del circuito richiede circom nel PATH e qualche minuto.
+ */
+
+// Coordinata in Fp → parole da 128 bit little endian, come negli input
+function words(value, count) {
+    return Array.from({ length: count }, (_, i) =>
+        ((BigInt(value) >> BigInt(128 * i)) & ((1n << 128n) - 1n)).toString()
+    );
+}
+
+describe("bls_verify.circom", function () {
+    this.timeout(0);
+
+    const W = 2;
+    let curve;
+    let circuit;
+    let valid;
+
+    function g1Words(point) {
+        const [x, y] = curve.G1.toObject(curve.G1.toAffine(point));
+        return [words(x, W), words(y, W)];
+    }
+
+    // pk nell'ordine x.c0, x.c1, y.c0, y.c1
+    function g2Words(point) {
+        const [x, y] = curve.G2.toObject(curve.G2.toAffine(point));
+        return [
+            [words(x[0], W), words(x[1], W)],
+            [words(y[0], W), words(y[1], W)],
+        ];
+    }
+
+    function signatureInput(h, sk, signer = sk) {
+        const messageHash = curve.G1.timesFr(curve.G1.g, curve.Fr.e(h));
+        return {
+            messageHash: g1Words(messageHash),
+            publicKey: g2Words(curve.G2.timesFr(curve.G2.g, curve.Fr.e(sk))),
+            signature: g1Words(curve.G1.timesFr(messageHash, curve.Fr.e(signer))),
+        };
+    }
+
+    before(async function () {
+        curve = await buildBn128();
+        circuit = await wasmTester(path.join(__dirname, "..", "bls_verify.circom"), {
+            include: path.join(__dirname, "..", "..", "node_modules"),
+        });
+        valid = signatureInput(123456789n, 987654321n);
+    });
+
+    after(async function () {
+        await curve.terminate();
+    });
+
+    it("Dovrebbe accettare una firma valida", async function () {
+        const witness = await circuit.calculateWitness(valid, true);
+        await circuit.checkConstraints(witness);
+    });
+
+    it("Dovrebbe rifiutare una firma manomessa", async function () {
+        // σ firmata con un'altra chiave: punto sulla curva, pairing sbagliato
+        const wrongSigner = signatureInput(123456789n, 987654321n, 987654322n);
+        await assert.rejects(
+            circuit.calculateWitness({ ...valid, signature: wrongSigner.signature }, true)
+        );
+
+        // σ fuori dalla curva
+        const [x, [y0, y1]] = valid.signature;
+        const offCurve = [x, [(BigInt(y0) + 1n).toString(), y1]];
+        await assert.rejects(circuit.calculateWitness({ ...valid, signature: offCurve }, true));
+    });
+
+    it("Dovrebbe rifiutare una chiave pubblica o un H(m) diversi", async function () {
+        const otherKey = signatureInput(123456789n, 987654322n);
+        await assert.rejects(
+            circuit.calculateWitness({ ...valid, publicKey: otherKey.publicKey }, true)
+        );
+
+        const otherMessage = signatureInput(123456790n, 987654321n);
+        await assert.rejects(
+            circuit.calculateWitness({ ...valid, messageHash: otherMessage.messageHash }, true)
+        );
+    });
+});
@@ -0,0 +1,6 @@
+pragma circom 2.1.0;
+
+// G2SubgroupCheck da solo, per il test in bls12_381_verify.test.js
+include "../lib/pairing.circom";
+
+component main = G2SubgroupCheck(381);
@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+/**
+ * @title BN254HashToG1
+ * @notice Hash-to-curve su G1 di BN254, suite BN254G1_XMD:SHA-256_SVDW_RO_ (RFC 9380)
+ * @dev Stesso calcolo di hash_to_g1 in src/hash_to_curve.rs: expand_message_xmd
+ *      con il precompile sha256, due elementi di campo da 48 byte, mappa di
+ *      Shallue-van de Woestijne con Z = 1 e somma dei due punti con ecAdd.
+ *      Inversi e radici quadrate con il precompile modexp (p ≡ 3 mod 4).
+ *      Non è a tempo costante: i messaggi firmati sono pubblici.
+ */
+library BN254HashToG1 {
+    uint256 internal constant P =
+        21888242871839275222246405745257275088696311157297823662689037894645226208583;
+
+    /// 2^256 mod p, per ridurre gli elementi da 48 byte
+    uint256 private constant TWO_256 =
+        6350874878119819312338956282401532409788428879151445726012394534686998597021;
+    /// (p + 1) / 4: esponente della radice quadrata
+    uint256 private constant SQRT_EXP =
+        5472060717959818805561601436314318772174077789324455915672259473661306552146;
+
+    // Costanti della mappa SVDW per y² = x³ + 3, Z = 1: g(Z) = 4,
+    // c2 = -Z/2, c3 = sqrt(-g(Z)·3Z²) con sgn0(c3) = 0, c4 = -4·g(Z) / 3Z²
+    uint256 private constant C2 =
+        10944121435919637611123202872628637544348155578648911831344518947322613104291;
+    uint256 private constant C3 = 8815841940592487685674414971303048083897117035520822607866;
+    uint256 private constant C4 =
+        7296080957279758407415468581752425029565437052432607887563012631548408736189;
+
+    uint256 private constant MASK_128 = (1 << 128) - 1;
+
+    error InvalidDST();
+    error PrecompileFailed();
+
+    /**
+     * @notice H(m) come punto affine (x, y)
+     */
+    function hashToG1(bytes memory message, bytes memory dst)
+        internal
+        view
+        returns (uint256 x, uint256 y)
+    {
+        (uint256 u0, uint256 u1) = hashToField(message, dst);
+        (uint256 x0, uint256 y0) = mapToCurve(u0);
+        (uint256 x1, uint256 y1) = mapToCurve(u1);
+        return _ecAdd(x0, y0, x1, y1);
+    }
+
+    /**
+     * @notice H(m) nelle parole da 128 bit little endian dei circuiti: x, poi y
+     */
+    function hashToG1Words(bytes memory message, bytes memory dst)
+        internal
+        view
+        returns (uint256[4] memory words)
+    {
+        (uint256 x, uint256 y) = hashToG1(message, dst);
+        words[0] = x & MASK_128;
+        words[1] = x >> 128;
+        words[2] = y & MASK_128;
+        words[3] = y >> 128;
+    }
+
+    /**
+     * @notice hash_to_field con due elementi: 96 byte di expand_message_xmd,
+     *         48 byte big endian per elemento ridotti modulo p
+     */
+    function hashToField(bytes memory message, bytes memory dst)
+        internal
+        pure
+        returns (uint256 u0, uint256 u1)
+    {
+        (uint256 b1, uint256 b2, uint256 b3) = expandMessageXmd(message, dst);
+        u0 = addmod(mulmod(b1 >> 128, TWO_256, P), ((b1 & MASK_128) << 128) | (b2 >> 128), P);
+        u1 = addmod(mulmod(b2 & MASK_128, TWO_256, P), b3, P);
+    }
+
+    /**
+     * @notice expand_message_xmd con SHA-256 per 96 byte, restituiti come tre parole
+     * @dev DST fino a 255 byte: i DST più lunghi vanno accorciati dal chiamante
+     */
+    function expandMessageXmd(bytes memory message, bytes memory dst)
+        internal
+        pure
+        returns (uint256, uint256, uint256)
+    {
+        if (dst.length == 0 || dst.length > 255) revert InvalidDST();
+        bytes memory dstPrime = abi.encodePacked(dst, uint8(dst.length));
+
+        bytes32 b0 = sha256(abi.encodePacked(new bytes(64), message, uint16(96), uint8(0), dstPrime));
+        bytes32 b1 = sha256(abi.encodePacked(b0, uint8(1), dstPrime));
+        bytes32 b2 = sha256(abi.encodePacked(b0 ^ b1, uint8(2), dstPrime));
+        bytes32 b3 = sha256(abi.encodePacked(b0 ^ b2, uint8(3), dstPrime));
+        return (uint256(b1), uint256(b2), uint256(b3));
+    }
+
+    /**
+     * @notice Mappa di Shallue-van de Woestijne (RFC 9380, 6.6.1)
+     */
+    function mapToCurve(uint256 u) internal view returns (uint256 x, uint256 y) {
+        uint256 tv1 = mulmod(mulmod(u, u, P), 4, P);
+        uint256 tv2 = addmod(1, tv1, P);
+        tv1 = addmod(1, P - tv1, P);
+        // inv0: l'inverso di zero è zero
+        uint256 tv3 = _modexp(mulmod(tv1, tv2, P), P - 2);
+        uint256 tv5 = mulmod(mulmod(mulmod(u, tv1, P), tv3, P), C3, P);
+
+        bool found;
+        x = addmod(C2, P - tv5, P);
+        (y, found) = _sqrt(_g(x));
+        if (!found) {
+            x = addmod(C2, tv5, P);
+            (y, found) = _sqrt(_g(x));
+        }
+        if (!found) {
+            uint256 t = mulmod(mulmod(tv2, tv2, P), tv3, P);
+            x = addmod(1, mulmod(C4, mulmod(t, t, P), P), P);
+            (y, found) = _sqrt(_g(x));
+        }
+        // g(x3) è sempre un quadrato
+        assert(found);
+
+        if ((u & 1) != (y & 1)) {
+            y = P - y;
+        }
+    }
+
+    /// x³ + 3
+    function _g(uint256 x) private pure returns (uint256) {
+        return addmod(mulmod(mulmod(x, x, P), x, P), 3, P);
+    }
+
+    function _sqrt(uint256 a) private view returns (uint256 root, bool found) {
+        root = _modexp(a, SQRT_EXP);
+        found = mulmod(root, root, P) == a;
+    }
+
+    function _modexp(uint256 base, uint256 exponent) private view returns (uint256) {
+        (bool ok, bytes memory result) =
+            address(0x05).staticcall(abi.encode(32, 32, 32, base, exponent, P));
+        if (!ok) revert PrecompileFailed();
+        return abi.decode(result, (uint256));
+    }
+
+    function _ecAdd(uint256 x0, uint256 y0, uint256 x1, uint256 y1)
+        private
+        view
+        returns (uint256, uint256)
+    {
+        (bool ok, bytes memory result) = address(0x06).staticcall(abi.encode(x0, y0, x1, y1));
+        if (!ok) revert PrecompileFailed();
+        return abi.decode(result, (uint256, uint256));
+    }
+}
@@ -1,6 +1,8 @@
-/ SPDX-License-Identifier: MIT
+// SPDX-License-Identifier: MIT
 pragma solidity ^0.8.20;
 
+import "./BN254HashToG1.sol";
+
 /**
  * @title ZKRollupBLS
  * @notice ZK Rollup per verifica batch di firme BLS usando Groth16
@@ -23,10 +25,10 @@ bool verified;
 uint64 l2BlockNumber;
 }
 
+/// @dev H(m) e pk sono impegnati con keccak256 delle loro parole (4 e 8)
 struct SignatureSubmission {
 bytes32 messageHash;
-uint256 publicKeyX;
-uint256 publicKeyY;
+bytes32 publicKeyHash;
 bool included;
 }
 
@@ -42,6 +44,18 @@ uint256[2] c;
 address public immutable sequencer;
 address public verifier;
 
+/// @notice Registra il comitato: ruolo distinto dal sequencer, che non può
+///         scegliersi le chiavi con cui firmare i propri batch
+address public immutable governance;
+
+/// @notice Impegno Poseidon sulle chiavi del comitato e verifier di bls_aggregate_verify
+bytes32 public committeeRoot;
+address public committeeVerifier;
+
+/// @notice DST delle firme del comitato (schema proof of possession),
+///         BN254_G1_POP_SCHEME_DST lato prover
+bytes public constant COMMITTEE_DST = "BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_POP_";
+
 uint256 public batchCount;
 uint256 public submissionCount;
 uint256 public l2BlockNumber;
@@ -64,8 +78,7 @@ uint64 l2BlockNumber
 event SignatureSubmitted(
 uint256 indexed submissionId,
 bytes32 messageHash,
-uint256 publicKeyX,
-uint256 publicKeyY
+bytes32 publicKeyHash
 );
 
 event StateUpdated(
@@ -78,14 +91,23 @@ address oldVerifier,
 address newVerifier
 );
 
+event CommitteeUpdated(
+bytes32 committeeRoot,
+address committeeVerifier
+);
+
 // ============ ERRORS ============
 
 error OnlySequencer();
+error OnlyGovernance();
+error InvalidGovernance();
 error InvalidVerifier();
 error EmptyBatch();
 error LengthMismatch();
 error InvalidZKProof();
 error VerificationFailed();
+error CommitteeNotSet();
+error EmptyBitmap();
 
 // ============ MODIFIERS ============
 
@@ -94,14 +116,21 @@ if (msg.sender != sequencer) revert OnlySequencer();
 _;
 }
 
+modifier onlyGovernance() {
+if (msg.sender != governance) revert OnlyGovernance();
+_;
+}
+
 // ============ CONSTRUCTOR ============
 
-constructor(address _sequencer, address _verifier) {
+constructor(address _sequencer, address _verifier, address _governance) {
 if (_sequencer == address(0)) revert InvalidVerifier();
 if (_verifier == address(0)) revert InvalidVerifier();
+if (_governance == address(0) || _governance == _sequencer) revert InvalidGovernance();
 
 sequencer = _sequencer;
 verifier = _verifier;
+governance = _governance;
 currentStateRoot = bytes32(0);
 l2BlockNumber = 0;
 }
@@ -111,35 +140,26 @@ l2BlockNumber = 0;
 /**
  * @notice Sottomette un batch con prova ZK in formato snarkjs
      * @param _stateRoot Nuovo state root L2
-     * @param _messageHashes Array di hash dei messaggi
-     * @param _publicKeysX Array di coordinate X delle chiavi pubbliche
-     * @param _publicKeysY Array di coordinate Y delle chiavi pubbliche
+     * @param _messageHashes H(m) in G1 per firma: x e y in parole da 128 bit little endian
+     * @param _publicKeys pk in G2 per firma: x.c0, x.c1, y.c0, y.c1 in parole da 128 bit
      * @param _proof Prova Groth16 (a, b, c)
      */
 function submitBatchWithProof(
 bytes32 _stateRoot,
-bytes32[] calldata _messageHashes,
-uint256[] calldata _publicKeysX,
-uint256[] calldata _publicKeysY,
+uint256[4][] calldata _messageHashes,
+uint256[8][] calldata _publicKeys,
 Groth16Proof calldata _proof
 ) external onlySequencer {
 if (_messageHashes.length == 0) revert EmptyBatch();
-if (_messageHashes.length != _publicKeysX.length ||
-_publicKeysX.length != _publicKeysY.length) {
-revert LengthMismatch();
-}
+if (_messageHashes.length != _publicKeys.length) revert LengthMismatch();
 
 uint256 batchId = batchCount;
 
 // Prepara gli input pubblici per la verifica
-uint256[] memory publicInputs = _preparePublicInputs(
-_messageHashes,
-_publicKeysX,
-_publicKeysY
-);
+uint256[] memory publicInputs = _preparePublicInputs(_messageHashes, _publicKeys);
 
 // Verifica la prova chiamando il Verifier snarkjs
-bool isValid = _verifyGroth16Proof(_proof, publicInputs);
+bool isValid = _verifyGroth16Proof(verifier, _proof, publicInputs);
 if (!isValid) revert InvalidZKProof();
 
 // Crea e memorizza il batch
@@ -155,22 +175,18 @@ l2BlockNumber: uint64(l2BlockNumber + 1)
 // Memorizza le submission individuali
 for (uint256 i = 0; i < _messageHashes.length; i++) {
 uint256 submissionId = submissionCount++;
+bytes32 messageHash = keccak256(abi.encode(_messageHashes[i]));
+bytes32 publicKeyHash = keccak256(abi.encode(_publicKeys[i]));
 
 submissions[submissionId] = SignatureSubmission({
-messageHash: _messageHashes[i],
-publicKeyX: _publicKeysX[i],
-publicKeyY: _publicKeysY[i],
+messageHash: messageHash,
+publicKeyHash: publicKeyHash,
 included: true
 });
 
 batchSubmissions[batchId].push(submissionId);
 
-emit SignatureSubmitted(
-submissionId,
-_messageHashes[i],
-_publicKeysX[i],
-_publicKeysY[i]
-);
+emit SignatureSubmitted(submissionId, messageHash, publicKeyHash);
 }
 
 // Aggiorna stato L2
@@ -195,29 +211,21 @@ emit StateUpdated(_stateRoot, uint64(l2BlockNumber));
      */
 function submitBatchWithProofBytes(
 bytes32 _stateRoot,
-bytes32[] calldata _messageHashes,
-uint256[] calldata _publicKeysX,
-uint256[] calldata _publicKeysY,
+uint256[4][] calldata _messageHashes,
+uint256[8][] calldata _publicKeys,
 bytes calldata _proofBytes
 ) external onlySequencer {
 if (_messageHashes.length == 0) revert EmptyBatch();
-if (_messageHashes.length != _publicKeysX.length ||
-_publicKeysX.length != _publicKeysY.length) {
-revert LengthMismatch();
-}
+if (_messageHashes.length != _publicKeys.length) revert LengthMismatch();
 
 // Decodifica prova da bytes (formato: a[0], a[1], b[0][0], b[0][1], b[1][0], b[1][1], c[0], c[1])
 Groth16Proof memory proof = _decodeProof(_proofBytes);
 
 uint256 batchId = batchCount;
 
-uint256[] memory publicInputs = _preparePublicInputs(
-_messageHashes,
-_publicKeysX,
-_publicKeysY
-);
+uint256[] memory publicInputs = _preparePublicInputs(_messageHashes, _publicKeys);
 
-bool isValid = _verifyGroth16Proof(proof, publicInputs);
+bool isValid = _verifyGroth16Proof(verifier, proof, publicInputs);
 if (!isValid) revert InvalidZKProof();
 
 // Stesso codice di submitBatchWithProof...
@@ -232,22 +240,18 @@ l2BlockNumber: uint64(l2BlockNumber + 1)
 
 for (uint256 i = 0; i < _messageHashes.length; i++) {
 uint256 submissionId = submissionCount++;
+bytes32 messageHash = keccak256(abi.encode(_messageHashes[i]));
+bytes32 publicKeyHash = keccak256(abi.encode(_publicKeys[i]));
 
 submissions[submissionId] = SignatureSubmission({
-messageHash: _messageHashes[i],
-publicKeyX: _publicKeysX[i],
-publicKeyY: _publicKeysY[i],
+messageHash: messageHash,
+publicKeyHash: publicKeyHash,
 included: true
 });
 
 batchSubmissions[batchId].push(submissionId);
 
-emit SignatureSubmitted(
-submissionId,
-_messageHashes[i],
-_publicKeysX[i],
-_publicKeysY[i]
-);
+emit SignatureSubmitted(submissionId, messageHash, publicKeyHash);
 }
 
 currentStateRoot = _stateRoot;
@@ -265,29 +269,92 @@ uint64(l2BlockNumber)
 emit StateUpdated(_stateRoot, uint64(l2BlockNumber));
 }
 
+/**
+ * @notice Sottomette un batch firmato dal comitato con una firma aggregata
+     * @dev Una sola prova di bls_aggregate_verify per tutto il comitato: gli input
+     *      pubblici sono committeeRoot, H(m) e la bitmap (5 + N/128), non 3N.
+     *      Le chiavi restano off-chain, impegnate in committeeRoot.
+     *      Il comitato firma committeeMessage(_stateRoot) (chain id, contratto,
+     *      batchId e state root): H(m) si calcola qui con l'hash-to-curve, quindi
+     *      la prova vale solo per questo state root e per questo batch.
+     * @param _stateRoot Nuovo state root L2
+     * @param _bitmap Partecipanti: il firmatario i è il bit i % 128 della parola i / 128
+     * @param _proof Prova Groth16 (a, b, c)
+     */
+function submitCommitteeBatchWithProof(
+bytes32 _stateRoot,
+uint256[] calldata _bitmap,
+Groth16Proof calldata _proof
+) external onlySequencer {
+if (committeeVerifier == address(0)) revert CommitteeNotSet();
+
+uint32 numSigners = 0;
+for (uint256 i = 0; i < _bitmap.length; i++) {
+for (uint256 word = _bitmap[i]; word != 0; word &= word - 1) {
+numSigners++;
+}
+}
+if (numSigners == 0) revert EmptyBitmap();
+
+uint256[4] memory messageHash = committeeMessageHash(_stateRoot);
+
+uint256[] memory publicInputs = new uint256[](5 + _bitmap.length);
+publicInputs[0] = uint256(committeeRoot);
+for (uint256 i = 0; i < 4; i++) {
+publicInputs[1 + i] = messageHash[i];
+}
+for (uint256 i = 0; i < _bitmap.length; i++) {
+publicInputs[5 + i] = _bitmap[i];
+}
+
+bool isValid = _verifyGroth16Proof(committeeVerifier, _proof, publicInputs);
+if (!isValid) revert InvalidZKProof();
+
+uint256 batchId = batchCount;
+
+batches[batchId] = Batch({
+stateRoot: _stateRoot,
+numSignatures: numSigners,
+timestamp: uint64(block.timestamp),
+proposer: msg.sender,
+verified: true,
+l2BlockNumber: uint64(l2BlockNumber + 1)
+});
+
+currentStateRoot = _stateRoot;
+l2BlockNumber++;
+batchCount++;
+
+emit BatchSubmitted(
+batchId,
+_stateRoot,
+numSigners,
+msg.sender,
+uint64(l2BlockNumber)
+);
+
+emit StateUpdated(_stateRoot, uint64(l2BlockNumber));
+}
+
 /**
  * @notice Sottomette una singola firma per inclusione futura
+     * @dev Stesse parole degli input pubblici di bls_verify (4 per H(m), 8 per pk)
      */
 function submitSignature(
-bytes32 _messageHash,
-uint256 _publicKeyX,
-uint256 _publicKeyY
+uint256[4] calldata _messageHash,
+uint256[8] calldata _publicKey
 ) external {
 uint256 submissionId = submissionCount++;
+bytes32 messageHash = keccak256(abi.encode(_messageHash));
+bytes32 publicKeyHash = keccak256(abi.encode(_publicKey));
 
 submissions[submissionId] = SignatureSubmission({
-messageHash: _messageHash,
-publicKeyX: _publicKeyX,
-publicKeyY: _publicKeyY,
+messageHash: messageHash,
+publicKeyHash: publicKeyHash,
 included: false
 });
 
-emit SignatureSubmitted(
-submissionId,
-_messageHash,
-_publicKeyX,
-_publicKeyY
-);
+emit SignatureSubmitted(submissionId, messageHash, publicKeyHash);
 }
 
 // ============ VIEW FUNCTIONS ============
@@ -311,6 +378,23 @@ batch.l2BlockNumber
 );
 }
 
+/**
+ * @notice Messaggio che il comitato firma per il prossimo batch con _stateRoot
+     * @dev abi.encode(chainid, contratto, batchId, stateRoot), come
+     *      committee_batch_message lato prover
+     */
+function committeeMessage(bytes32 _stateRoot) public view returns (bytes memory) {
+return abi.encode(block.chainid, address(this), batchCount, _stateRoot);
+}
+
+/**
+ * @notice H(committeeMessage(_stateRoot)) in G1, nelle parole da 128 bit dell'input
+     *         pubblico messageHash di bls_aggregate_verify
+     */
+function committeeMessageHash(bytes32 _stateRoot) public view returns (uint256[4] memory) {
+return BN254HashToG1.hashToG1Words(committeeMessage(_stateRoot), COMMITTEE_DST);
+}
+
 function getBatchSubmissions(uint256 _batchId) external view returns (uint256[] memory) {
 return batchSubmissions[_batchId];
 }
@@ -333,6 +417,7 @@ return submissions[_submissionId].included;
  * @notice Verifica prova Groth16 chiamando il Verifier snarkjs
      */
 function _verifyGroth16Proof(
+address _verifier,
 Groth16Proof memory _proof,
 uint256[] memory _publicInputs
 ) internal view returns (bool) {
@@ -348,7 +433,7 @@ _proof.c,
 _publicInputs
 );
 
-(bool success, bytes memory result) = verifier.staticcall(callData);
+(bool success, bytes memory result) = _verifier.staticcall(callData);
 
 if (!success || result.length == 0) {
 return false;
@@ -359,19 +444,24 @@ return abi.decode(result, (bool));
 
 /**
  * @notice Prepara gli input pubblici per il verifier
+     * @dev Ordine dei segnali pubblici di bls_verify.circom: le 4 parole di
+     *      messageHash[2][2] e poi le 8 di publicKey[2][2][2], 12 per firma.
+     *      bls_verify verifica una firma per prova: il verifier rifiuta
+     *      batch di lunghezza diversa da quella del circuito compilato.
      */
 function _preparePublicInputs(
-bytes32[] calldata _messageHashes,
-uint256[] calldata _publicKeysX,
-uint256[] calldata _publicKeysY
+uint256[4][] calldata _messageHashes,
+uint256[8][] calldata _publicKeys
 ) internal pure returns (uint256[] memory) {
-// Per un batch di N firme, abbiamo 3N input pubblici
-uint256[] memory publicInputs = new uint256[](_messageHashes.length * 3);
+uint256[] memory publicInputs = new uint256[](_messageHashes.length * 12);
 
 for (uint256 i = 0; i < _messageHashes.length; i++) {
-publicInputs[i * 3] = uint256(_messageHashes[i]);
-publicInputs[i * 3 + 1] = _publicKeysX[i];
-publicInputs[i * 3 + 2] = _publicKeysY[i];
+for (uint256 j = 0; j < 4; j++) {
+publicInputs[i * 12 + j] = _messageHashes[i][j];
+}
+for (uint256 j = 0; j < 8; j++) {
+publicInputs[i * 12 + 4 + j] = _publicKeys[i][j];
+}
 }
 
 return publicInputs;
@@ -420,4 +510,22 @@ verifier = _newVerifier;
 
 emit VerifierUpdated(oldVerifier, _newVerifier);
 }
+
+/**
+ * @notice Registra il comitato (solo governance, mai il sequencer)
+     * @dev Il contratto vede solo l'impegno Poseidon sulle chiavi, non le chiavi:
+     *      i controlli avvengono off-chain quando si calcola _committeeRoot.
+     *      PublicKey::from_point (src/bls.rs) rifiuta le chiavi fuori dal
+     *      sottogruppo di G2 e Committee::new (src/committee.rs) verifica la proof
+     *      of possession di ogni chiave, contro gli attacchi rogue key.
+     *      La governance registra solo root calcolate da Committee::root.
+     */
+function setCommittee(bytes32 _committeeRoot, address _committeeVerifier) external onlyGovernance {
+if (_committeeVerifier == address(0)) revert InvalidVerifier();
+
+committeeRoot = _committeeRoot;
+committeeVerifier = _committeeVerifier;
+
+emit CommitteeUpdated(_committeeRoot, _committeeVerifier);
+}
 }
\ No newline at end of file
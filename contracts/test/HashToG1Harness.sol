@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import "../BN254HashToG1.sol";
+
+/**
+ * @title HashToG1Harness
+ * @notice Espone BN254HashToG1 ai test
+ */
+contract HashToG1Harness {
+    function hashToG1(bytes calldata message, bytes calldata dst)
+        external
+        view
+        returns (uint256 x, uint256 y)
+    {
+        return BN254HashToG1.hashToG1(message, dst);
+    }
+
+    function hashToG1Words(bytes calldata message, bytes calldata dst)
+        external
+        view
+        returns (uint256[4] memory)
+    {
+        return BN254HashToG1.hashToG1Words(message, dst);
+    }
+}
@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+/**
+ * @title MockInputsVerifier
+ * @notice Verifier di test: accetta la prova solo se gli input pubblici sono
+ *         quelli attesi, per controllare cosa il contratto passa al verifier
+ */
+contract MockInputsVerifier {
+    bytes32 public expectedInputsHash;
+
+    function setExpectedInputs(uint256[] calldata _inputs) external {
+        expectedInputsHash = keccak256(abi.encode(_inputs));
+    }
+
+    function verifyProof(
+        uint256[2] calldata,
+        uint256[2][2] calldata,
+        uint256[2] calldata,
+        uint256[] calldata _inputs
+    ) external view returns (bool) {
+        return keccak256(abi.encode(_inputs)) == expectedInputsHash;
+    }
+}
@@ -0,0 +1,205 @@
+// SPDX-License-Identifier: GPL-3.0
+// Generato da bls-zk-prover: non modificare a mano
+pragma solidity >=0.8.0 <0.9.0;
+
+contract Groth16BatchVerifier {
+    // Scalar field size
+    uint256 constant r = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+    // Base field size
+    uint256 constant q = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+
+    // Verification Key data
+    uint256 constant alphax  = 20491192805390485299153009773594534940189261866228447918068658471970481763042;
+    uint256 constant alphay  = 9383485363053290200918347156157836566562967994039712273449902621266178545958;
+    uint256 constant betax1  = 4252822878758300859123897981450591353533073413197771768651442665752259397132;
+    uint256 constant betax2  = 6375614351688725206403948262868962793625744043794305715222011528459656738731;
+    uint256 constant betay1  = 21847035105528745403288232691147584728191162732299865338377159692350059136679;
+    uint256 constant betay2  = 10505242626370262277552901082094356697409835680220590971873171140371331206856;
+    uint256 constant gammax1 = 11559732032986387107991004021392285783925812861821192530917403151452391805634;
+    uint256 constant gammax2 = 10857046999023057135944570762232829481370756359578518086990519993285655852781;
+    uint256 constant gammay1 = 4082367875863433681332203403145435568316851327593401208105741076214120093531;
+    uint256 constant gammay2 = 8495653923123431417604973247489272438418190587263600148770280649306958101930;
+    uint256 constant deltax1 = 11559732032986387107991004021392285783925812861821192530917403151452391805634;
+    uint256 constant deltax2 = 10857046999023057135944570762232829481370756359578518086990519993285655852781;
+    uint256 constant deltay1 = 4082367875863433681332203403145435568316851327593401208105741076214120093531;
+    uint256 constant deltay2 = 8495653923123431417604973247489272438418190587263600148770280649306958101930;
+
+    uint256 constant IC0x    = 6819801395408938350212900248749732364821477541620635511814266536599629892365;
+    uint256 constant IC0y    = 9092252330033992554755034971584864587974280972948086568597554018278609861372;
+
+    uint256 constant IC1x    = 17882351432929302592725330552407222299541667716607588771282887857165175611387;
+    uint256 constant IC1y    = 18907419617206324833977586007131055763810739835484972981819026406579664278293;
+
+
+    uint256 constant nPublic = 1;
+
+    /// Verifica n prove con un solo pairing di n + 3 coppie:
+    /// prod(e(-rho_i A_i, B_i)) e(sum(rho_i) alpha, beta) e(sum(rho_i vk_x_i), gamma)
+    /// e(sum(rho_i C_i), delta) == 1, con rho_i da keccak256 di tutte le prove
+    function verifyBatch(
+        uint256[2][] calldata _pA,
+        uint256[2][2][] calldata _pB,
+        uint256[2][] calldata _pC,
+        uint256[][] calldata _pubSignals
+    ) public view returns (bool) {
+        uint256 n = _pA.length;
+        if (n == 0 || _pB.length != n || _pC.length != n || _pubSignals.length != n) {
+            return false;
+        }
+        for (uint256 i = 0; i < n; i++) {
+            if (!_inRange(_pA[i], _pB[i], _pC[i], _pubSignals[i])) return false;
+        }
+
+        uint256[] memory rho = _challenges(keccak256(abi.encode(_pA, _pB, _pC, _pubSignals)), n);
+        uint256[] memory input = new uint256[]((n + 3) * 6);
+        return _abTerms(input, rho, _pA, _pB)
+            && _vkTerms(input, rho, _pubSignals)
+            && _cTerm(input, rho, _pC)
+            && _pairing(input);
+    }
+
+    function _inRange(
+        uint256[2] calldata _pA,
+        uint256[2][2] calldata _pB,
+        uint256[2] calldata _pC,
+        uint256[] calldata _pubSignals
+    ) internal pure returns (bool) {
+        if (_pubSignals.length != nPublic) return false;
+        for (uint256 i = 0; i < nPublic; i++) {
+            if (_pubSignals[i] >= r) return false;
+        }
+        if (_pA[0] >= q || _pA[1] >= q || _pC[0] >= q || _pC[1] >= q) return false;
+        return _pB[0][0] < q && _pB[0][1] < q && _pB[1][0] < q && _pB[1][1] < q;
+    }
+
+    function _challenges(bytes32 seed, uint256 n) internal pure returns (uint256[] memory rho) {
+        rho = new uint256[](n);
+        for (uint256 i = 0; i < n; i++) {
+            rho[i] = uint256(keccak256(abi.encode(seed, i))) % r;
+        }
+    }
+
+    // -rho_i A_i, B_i
+    function _abTerms(
+        uint256[] memory input,
+        uint256[] memory rho,
+        uint256[2][] calldata _pA,
+        uint256[2][2][] calldata _pB
+    ) internal view returns (bool) {
+        bool ok;
+        uint256[2] memory term;
+        for (uint256 i = 0; i < rho.length; i++) {
+            (ok, term) = _ecMul(_pA[i], r - rho[i]);
+            if (!ok) return false;
+            _store(input, i * 6, term, [_pB[i][0][0], _pB[i][0][1], _pB[i][1][0], _pB[i][1][1]]);
+        }
+        return true;
+    }
+
+    // sum(rho_i) alpha, beta e sum(rho_i vk_x_i), gamma
+    function _vkTerms(
+        uint256[] memory input,
+        uint256[] memory rho,
+        uint256[][] calldata _pubSignals
+    ) internal view returns (bool) {
+        uint256[] memory coeff = _icCoefficients(rho, _pubSignals);
+        uint256 o = rho.length * 6;
+        bool ok;
+        uint256[2] memory term;
+
+        (ok, term) = _ecMul([alphax, alphay], coeff[0]);
+        if (!ok) return false;
+        _store(input, o, term, [betax1, betax2, betay1, betay2]);
+
+        // sum(rho_i vk_x_i) = sum(coeff[j] IC[j])
+        uint256[2] memory vkX;
+        (ok, vkX) = _ecMul([IC0x, IC0y], coeff[0]);
+        if (!ok) return false;
+        (ok, term) = _ecMul([IC1x, IC1y], coeff[1]);
+        if (!ok) return false;
+        (ok, vkX) = _ecAdd(vkX, term);
+        if (!ok) return false;
+
+        _store(input, o + 6, vkX, [gammax1, gammax2, gammay1, gammay2]);
+        return true;
+    }
+
+    // Coefficienti di IC: sum(rho_i) per IC0, sum(rho_i input_i[j]) per IC[j + 1]
+    function _icCoefficients(uint256[] memory rho, uint256[][] calldata _pubSignals)
+        internal
+        pure
+        returns (uint256[] memory coeff)
+    {
+        coeff = new uint256[](nPublic + 1);
+        for (uint256 i = 0; i < rho.length; i++) {
+            coeff[0] = addmod(coeff[0], rho[i], r);
+            for (uint256 j = 0; j < nPublic; j++) {
+                coeff[j + 1] = addmod(coeff[j + 1], mulmod(rho[i], _pubSignals[i][j], r), r);
+            }
+        }
+    }
+
+    // sum(rho_i C_i), delta
+    function _cTerm(uint256[] memory input, uint256[] memory rho, uint256[2][] calldata _pC)
+        internal
+        view
+        returns (bool)
+    {
+        bool ok;
+        uint256[2] memory term;
+        uint256[2] memory acc;
+        for (uint256 i = 0; i < rho.length; i++) {
+            (ok, term) = _ecMul(_pC[i], rho[i]);
+            if (!ok) return false;
+            (ok, acc) = _ecAdd(acc, term);
+            if (!ok) return false;
+        }
+        _store(input, (rho.length + 2) * 6, acc, [deltax1, deltax2, deltay1, deltay2]);
+        return true;
+    }
+
+    function _store(
+        uint256[] memory input,
+        uint256 o,
+        uint256[2] memory p,
+        uint256[4] memory g2
+    ) internal pure {
+        input[o] = p[0];
+        input[o + 1] = p[1];
+        input[o + 2] = g2[0];
+        input[o + 3] = g2[1];
+        input[o + 4] = g2[2];
+        input[o + 5] = g2[3];
+    }
+
+    function _ecAdd(uint256[2] memory p1, uint256[2] memory p2)
+        internal
+        view
+        returns (bool ok, uint256[2] memory out)
+    {
+        uint256[4] memory input = [p1[0], p1[1], p2[0], p2[1]];
+        assembly {
+            ok := staticcall(sub(gas(), 2000), 0x06, input, 0x80, out, 0x40)
+        }
+    }
+
+    function _ecMul(uint256[2] memory p, uint256 s)
+        internal
+        view
+        returns (bool ok, uint256[2] memory out)
+    {
+        uint256[3] memory input = [p[0], p[1], s];
+        assembly {
+            ok := staticcall(sub(gas(), 2000), 0x07, input, 0x60, out, 0x40)
+        }
+    }
+
+    function _pairing(uint256[] memory input) internal view returns (bool) {
+        bool ok;
+        uint256[1] memory out;
+        assembly {
+            ok := staticcall(sub(gas(), 2000), 0x08, add(input, 0x20), mul(mload(input), 0x20), out, 0x20)
+        }
+        return ok && out[0] == 1;
+    }
+}
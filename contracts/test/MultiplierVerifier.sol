@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: GPL-3.0
+// Generato da bls-zk-prover: non modificare a mano
+pragma solidity >=0.8.0 <0.9.0;
+
+contract Groth16Verifier {
+    // Scalar field size
+    uint256 constant r = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+    // Base field size
+    uint256 constant q = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+
+    // Verification Key data
+    uint256 constant alphax  = 20491192805390485299153009773594534940189261866228447918068658471970481763042;
+    uint256 constant alphay  = 9383485363053290200918347156157836566562967994039712273449902621266178545958;
+    uint256 constant betax1  = 4252822878758300859123897981450591353533073413197771768651442665752259397132;
+    uint256 constant betax2  = 6375614351688725206403948262868962793625744043794305715222011528459656738731;
+    uint256 constant betay1  = 21847035105528745403288232691147584728191162732299865338377159692350059136679;
+    uint256 constant betay2  = 10505242626370262277552901082094356697409835680220590971873171140371331206856;
+    uint256 constant gammax1 = 11559732032986387107991004021392285783925812861821192530917403151452391805634;
+    uint256 constant gammax2 = 10857046999023057135944570762232829481370756359578518086990519993285655852781;
+    uint256 constant gammay1 = 4082367875863433681332203403145435568316851327593401208105741076214120093531;
+    uint256 constant gammay2 = 8495653923123431417604973247489272438418190587263600148770280649306958101930;
+    uint256 constant deltax1 = 11559732032986387107991004021392285783925812861821192530917403151452391805634;
+    uint256 constant deltax2 = 10857046999023057135944570762232829481370756359578518086990519993285655852781;
+    uint256 constant deltay1 = 4082367875863433681332203403145435568316851327593401208105741076214120093531;
+    uint256 constant deltay2 = 8495653923123431417604973247489272438418190587263600148770280649306958101930;
+
+    uint256 constant IC0x    = 6819801395408938350212900248749732364821477541620635511814266536599629892365;
+    uint256 constant IC0y    = 9092252330033992554755034971584864587974280972948086568597554018278609861372;
+
+    uint256 constant IC1x    = 17882351432929302592725330552407222299541667716607588771282887857165175611387;
+    uint256 constant IC1y    = 18907419617206324833977586007131055763810739835484972981819026406579664278293;
+
+
+    uint256 constant nPublic = 1;
+
+    function verifyProof(
+        uint256[2] calldata _pA,
+        uint256[2][2] calldata _pB,
+        uint256[2] calldata _pC,
+        uint256[] calldata _pubSignals
+    ) public view returns (bool) {
+        if (_pubSignals.length != nPublic) return false;
+        for (uint256 i = 0; i < nPublic; i++) {
+            if (_pubSignals[i] >= r) return false;
+        }
+        if (_pA[0] >= q || _pA[1] >= q || _pC[0] >= q || _pC[1] >= q) return false;
+        if (_pB[0][0] >= q || _pB[0][1] >= q || _pB[1][0] >= q || _pB[1][1] >= q) return false;
+
+        // vk_x = IC0 + sum(input[i] * IC[i + 1])
+        uint256[2] memory vkX = [IC0x, IC0y];
+        bool ok;
+        uint256[2] memory term;
+        (ok, term) = _ecMul([IC1x, IC1y], _pubSignals[0]);
+        if (!ok) return false;
+        (ok, vkX) = _ecAdd(vkX, term);
+        if (!ok) return false;
+
+        uint256[24] memory input = [
+            // -A, B
+            _pA[0], (q - (_pA[1] % q)) % q,
+            _pB[0][0], _pB[0][1], _pB[1][0], _pB[1][1],
+            // alpha, beta
+            alphax, alphay,
+            betax1, betax2, betay1, betay2,
+            // vk_x, gamma
+            vkX[0], vkX[1],
+            gammax1, gammax2, gammay1, gammay2,
+            // C, delta
+            _pC[0], _pC[1],
+            deltax1, deltax2, deltay1, deltay2
+        ];
+        return _pairing(input);
+    }
+
+    function _ecAdd(uint256[2] memory p1, uint256[2] memory p2)
+        internal
+        view
+        returns (bool ok, uint256[2] memory out)
+    {
+        uint256[4] memory input = [p1[0], p1[1], p2[0], p2[1]];
+        assembly {
+            ok := staticcall(sub(gas(), 2000), 0x06, input, 0x80, out, 0x40)
+        }
+    }
+
+    function _ecMul(uint256[2] memory p, uint256 s)
+        internal
+        view
+        returns (bool ok, uint256[2] memory out)
+    {
+        uint256[3] memory input = [p[0], p[1], s];
+        assembly {
+            ok := staticcall(sub(gas(), 2000), 0x07, input, 0x60, out, 0x40)
+        }
+    }
+
+    function _pairing(uint256[24] memory input) internal view returns (bool) {
+        bool ok;
+        uint256[1] memory out;
+        assembly {
+            ok := staticcall(sub(gas(), 2000), 0x08, input, 0x300, out, 0x20)
+        }
+        return ok && out[0] == 1;
+    }
+}
@@ -2,6 +2,13 @@
 
 # Script per compilare il circuito Circom BLS
 # Questo script usa circom e snarkjs per generare i file necessari
+#
+# Uso: compile.sh [nome_circuito]   (default: bls_verify)
+#   BUILD_DIR=... compile.sh bls12_381_verify   per la modalità BLS12-381
+# verification_key.json ha lo stesso nome per ogni circuito: usare una
+# BUILD_DIR diversa per ciascuno. Il file Powers of Tau si sceglie dai
+# vincoli dell'R1CS (snarkjs r1cs info): PTAU_POWER lo forza, ma deve
+# bastare per il circuito. I file hermez arrivano a 2^28.
 
 set -e
 
@@ -11,8 +18,10 @@ echo "======================================"
 echo ""
 
 CIRCUIT_DIR="$(cd "$(dirname "$0")/.." && pwd)"
-CIRCUIT_FILE="$CIRCUIT_DIR/bls_verify.circom"
-BUILD_DIR="$CIRCUIT_DIR/build"
+CIRCUIT_NAME="${1:-bls_verify}"
+CIRCUIT_FILE="$CIRCUIT_DIR/$CIRCUIT_NAME.circom"
+BUILD_DIR="${BUILD_DIR:-$CIRCUIT_DIR/build}"
+MAX_PTAU_POWER=28
 
 echo "Circuit dir: $CIRCUIT_DIR"
 echo "Build dir: $BUILD_DIR"
@@ -36,17 +45,42 @@ echo ""
 
 # Step 2: Info sul circuito
 echo "[2/6] Informazioni circuito:"
-snarkjs r1cs info "$BUILD_DIR/bls_verify.r1cs"
+R1CS_INFO="$(snarkjs r1cs info "$BUILD_DIR/$CIRCUIT_NAME.r1cs")"
+echo "$R1CS_INFO"
+
+r1cs_count() {
+    echo "$R1CS_INFO" | grep "# of $1:" | sed 's/.*: *//'
+}
+
+# Stesso dominio di snarkjs groth16 setup: vincoli + input pubblici + output + 1
+DOMAIN=$(( $(r1cs_count Constraints) + $(r1cs_count "Public Inputs") + $(r1cs_count Outputs) + 1 ))
+REQUIRED_POWER=8
+while [ $(( 1 << REQUIRED_POWER )) -lt "$DOMAIN" ]; do
+    REQUIRED_POWER=$(( REQUIRED_POWER + 1 ))
+done
+PTAU_POWER="${PTAU_POWER:-$REQUIRED_POWER}"
+
+if [ "$REQUIRED_POWER" -gt "$MAX_PTAU_POWER" ]; then
+    echo "Errore: $CIRCUIT_NAME richiede 2^$REQUIRED_POWER vincoli, oltre il massimo 2^$MAX_PTAU_POWER dei ptau hermez" >&2
+    exit 1
+fi
+if [ "$PTAU_POWER" -lt "$REQUIRED_POWER" ] || [ "$PTAU_POWER" -gt "$MAX_PTAU_POWER" ]; then
+    echo "Errore: PTAU_POWER=$PTAU_POWER non valido per $CIRCUIT_NAME (serve tra $REQUIRED_POWER e $MAX_PTAU_POWER)" >&2
+    exit 1
+fi
+echo "      Dominio: $DOMAIN, ptau 2^$PTAU_POWER"
 echo ""
 
 # Step 3: Trusted setup fase 1 (Powers of Tau)
 echo "[3/6] Trusted setup - Powers of Tau..."
-PTAU_FILE="$BUILD_DIR/powersOfTau28_hez_final_10.ptau"
+# I nomi hermez hanno la potenza su due cifre (..._08.ptau)
+PTAU_NAME="powersOfTau28_hez_final_$(printf '%02d' "$PTAU_POWER").ptau"
+PTAU_FILE="$BUILD_DIR/$PTAU_NAME"
 
 if [ ! -f "$PTAU_FILE" ]; then
     echo "      Download ptau file..."
     wget -O "$PTAU_FILE" \
-        https://hermez.s3-eu-west-1.amazonaws.com/powersOfTau28_hez_final_10.ptau
+        "https://hermez.s3-eu-west-1.amazonaws.com/$PTAU_NAME"
 else
     echo "      Ptau file già esistente"
 fi
@@ -55,9 +89,9 @@ echo ""
 # Step 4: Trusted setup fase 2 (Circuit-specific)
 echo "[4/6] Trusted setup - Circuit-specific..."
 snarkjs groth16 setup \
-    "$BUILD_DIR/bls_verify.r1cs" \
+    "$BUILD_DIR/$CIRCUIT_NAME.r1cs" \
     "$PTAU_FILE" \
-    "$BUILD_DIR/bls_verify_0000.zkey"
+    "$BUILD_DIR/${CIRCUIT_NAME}_0000.zkey"
 
 echo "      Zkey generata"
 echo ""
@@ -65,8 +99,8 @@ echo ""
 # Step 5: Contribuzione (per produzione si farebbe cerimonia multi-party)
 echo "[5/6] Contribuzione random beacon..."
 echo "random" | snarkjs zkey contribute \
-    "$BUILD_DIR/bls_verify_0000.zkey" \
-    "$BUILD_DIR/bls_verify_final.zkey" \
+    "$BUILD_DIR/${CIRCUIT_NAME}_0000.zkey" \
+    "$BUILD_DIR/${CIRCUIT_NAME}_final.zkey" \
     --name="Test contribution"
 
 echo "      Contribuzione applicata"
@@ -75,7 +109,7 @@ echo ""
 # Step 6: Esporta verifying key
 echo "[6/6] Export verifying key..."
 snarkjs zkey export verificationkey \
-    "$BUILD_DIR/bls_verify_final.zkey" \
+    "$BUILD_DIR/${CIRCUIT_NAME}_final.zkey" \
     "$BUILD_DIR/verification_key.json"
 
 echo "      Verification key esportata"
@@ -84,7 +118,7 @@ echo ""
 # Step 7: Genera Solidity verifier (opzionale)
 echo "[7/6] Generazione Solidity verifier..."
 snarkjs zkey export solidityverifier \
-    "$BUILD_DIR/bls_verify_final.zkey" \
+    "$BUILD_DIR/${CIRCUIT_NAME}_final.zkey" \
     "$BUILD_DIR/Verifier.sol"
 
 echo "      Solidity verifier generato"
@@ -95,9 +129,9 @@ echo "Compilazione completata con successo!"
 echo "======================================"
 echo ""
 echo "File generati:"
-echo "  - $BUILD_DIR/bls_verify.r1cs"
-echo "  - $BUILD_DIR/bls_verify_js/bls_verify.wasm"
-echo "  - $BUILD_DIR/bls_verify_final.zkey"
+echo "  - $BUILD_DIR/$CIRCUIT_NAME.r1cs"
+echo "  - $BUILD_DIR/${CIRCUIT_NAME}_js/$CIRCUIT_NAME.wasm"
+echo "  - $BUILD_DIR/${CIRCUIT_NAME}_final.zkey"
 echo "  - $BUILD_DIR/verification_key.json"
 echo "  - $BUILD_DIR/Verifier.sol"
 echo ""
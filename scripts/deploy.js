@@ -81,10 +81,18 @@ async function main() {
 
   // Deploy ZKRollupBLS
   console.log("\n[2/2] Deploying ZKRollupBLS...");
+  // La governance registra il comitato: deve essere diversa dal sequencer
+  const signers = await ethers.getSigners();
+  const governance = process.env.GOVERNANCE_ADDRESS || signers[1]?.address;
+  if (!governance) {
+    throw new Error("GOVERNANCE_ADDRESS not set and no second signer available");
+  }
+
   const ZKRollupBLS = await ethers.getContractFactory("ZKRollupBLS");
   const zkRollup = await ZKRollupBLS.deploy(
       deployer.address,  // sequencer
-      verifierAddress    // verifier
+      verifierAddress,   // verifier
+      governance         // governance
   );
   await zkRollup.waitForDeployment();
   const zkRollupAddress = await zkRollup.getAddress();
@@ -99,6 +107,7 @@ async function main() {
 
   console.log(`   Sequencer:   ${sequencer}`);
   console.log(`   Verifier:    ${verifierContract}`);
+  console.log(`   Governance:  ${await zkRollup.governance()}`);
   console.log(`   Batch Count: ${batchCount.toString()}`);
   console.log(`   State Root:  ${currentStateRoot}`);
 
@@ -112,6 +121,7 @@ async function main() {
     },
     verifierType: isRealVerifier ? "snarkjs-groth16" : "mock",
     deployer: deployer.address,
+    governance,
     timestamp: new Date().toISOString(),
     blockNumber: await ethers.provider.getBlockNumber()
   };
@@ -143,18 +153,20 @@ async function main() {
       c: [BigInt(7), BigInt(8)]
     };
 
+    // Input pubblici di bls_verify: H(m) in 4 parole, pk in 8
     const sampleMessageHashes = [
-      ethers.keccak256(ethers.toUtf8Bytes("test1")),
-      ethers.keccak256(ethers.toUtf8Bytes("test2"))
+      [BigInt(1), BigInt(2), BigInt(3), BigInt(4)],
+      [BigInt(5), BigInt(6), BigInt(7), BigInt(8)]
+    ];
+    const samplePublicKeys = [
+      Array.from({ length: 8 }, (_, i) => BigInt(100 + i)),
+      Array.from({ length: 8 }, (_, i) => BigInt(200 + i))
     ];
-    const samplePublicKeysX = [BigInt(123), BigInt(456)];
-    const samplePublicKeysY = [BigInt(789), BigInt(101112)];
 
     const estimatedGas = await zkRollup.submitBatchWithProof.estimateGas(
         ethers.keccak256(ethers.toUtf8Bytes("newState")),
         sampleMessageHashes,
-        samplePublicKeysX,
-        samplePublicKeysY,
+        samplePublicKeys,
         sampleProof
     );
 
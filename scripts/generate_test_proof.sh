@@ -17,13 +17,22 @@ INPUT_FILE="$CIRCUIT_DIR/test_input.json"
 # Crea input di test se non esiste
 if [ ! -f "$INPUT_FILE" ]; then
     echo "[1/4] Creazione input di test..."
+    # Chiave segreta 1 e H(m) = g1: pk = g2, σ = g1. Coordinate in parole da
+    # 128 bit little endian; pk nell'ordine x.c0, x.c1, y.c0, y.c1
     cat > "$INPUT_FILE" << EOF
 {
-  "messageHash": "12345678901234567890",
-  "publicKeyX": "98765432109876543210",
-  "publicKeyY": "11111111111111111111",
-  "signatureX": "22222222222222222222",
-  "signatureY": "33333333333333333333"
+  "messageHash": [["1", "0"], ["2", "0"]],
+  "publicKey": [
+    [
+      ["137259073930222615551684094724674877165", "31905993534909183259390360115767690361"],
+      ["321228122123261106059779968529748660930", "33970999254487337296367544696982691109"]
+    ],
+    [
+      ["302824638645981006498739523792345398698", "24966482982931995192542807552294600847"],
+      ["250285283385540557223862098795094513499", "11997000940139619428152682118654997397"]
+    ]
+  ],
+  "signature": [["1", "0"], ["2", "0"]]
 }
 EOF
     echo "      Input creato: $INPUT_FILE"
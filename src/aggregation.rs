@@ -0,0 +1,1051 @@
+// prover/src/aggregation.rs
+// Aggregazione di prove Groth16 in stile SnarkPack (Gailly, Maller, Nitulescu 2021)
+//
+// N prove con la stessa VK diventano una prova di dimensione O(log N): il
+// verifier controlla un solo prodotto di pairing randomizzato
+//   Π e(A_i, B_i)^{r^i} = e(α, β)^{Σr^i} · e(Σ r^i·vk_x_i, γ) · e(Σ r^i·C_i, δ)
+// più gli argomenti TIPP/MIPP (GIPA + aperture KZG delle chiavi finali) che
+// legano Π e(A_i, B_i^{r^i}) e Σ r^i·C_i agli impegni sulle prove.
+//
+// Il Fiat-Shamir è keccak256 sugli elementi codificati in parole da 32 byte.
+//
+// La verifica è off-chain (relayer, sequencer, servizi di attestazione): i
+// controlli GIPA confrontano ed elevano a potenza elementi di GT, che i
+// precompile EVM non espongono (0x08 restituisce solo se un prodotto di
+// pairing vale 1). On-chain un batch si verifica con il Groth16BatchVerifier
+// generato da solidity.rs: un solo multi-pairing di N + 3 coppie.
+
+use crate::{parse_public_inputs, ProofResult, ProverError, SnarkjsProof, ZkeyParser};
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::short_weierstrass::Affine;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{BigInteger, Field, One, PrimeField, UniformRand, Zero};
+use ark_groth16::{Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+use sha3::{Digest, Keccak256};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+
+type Gt = PairingOutput<Bn254>;
+
+/// Impegno su un vettore: coppia (T, U) con le chiavi in a e in b
+type Commitment = (Gt, Gt);
+
+const TRANSCRIPT_DOMAIN: &[u8] = b"bls-zk-prover/snarkpack/v1";
+
+// ============================================================================
+// SRS
+// ============================================================================
+
+/// SRS dell'aggregazione: potenze di due segreti a, b in G1 (2n) e G2 (n),
+/// con n il massimo numero di prove aggregabili.
+///
+/// In produzione va derivata da due cerimonie Powers of Tau indipendenti con
+/// [`AggregationSrs::from_ptau`] (come SnarkPack con Filecoin e Zcash): nessuno
+/// conosce a e b finché almeno un partecipante onesto per cerimonia ha
+/// distrutto il proprio contributo. [`AggregationSrs::setup`] conosce i
+/// segreti ed è adatta solo a test e devnet.
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregationSrs {
+    g_alpha_powers: Vec<G1Affine>,
+    g_beta_powers: Vec<G1Affine>,
+    h_alpha_powers: Vec<G2Affine>,
+    h_beta_powers: Vec<G2Affine>,
+}
+
+/// Parte della SRS che serve al verifier
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregationVerifierKey {
+    pub g: G1Affine,
+    pub h: G2Affine,
+    pub g_alpha: G1Affine,
+    pub g_beta: G1Affine,
+    pub h_alpha: G2Affine,
+    pub h_beta: G2Affine,
+}
+
+/// Potenze 1, s, s^2, ... del generatore di `G`
+fn generator_powers<G: CurveGroup<ScalarField = Fr>>(s: Fr, count: usize) -> Vec<G::Affine> {
+    let generator = G::generator();
+    let points: Vec<G> = scalar_powers(s, count)
+        .into_iter()
+        .map(|p| generator * p)
+        .collect();
+    G::normalize_batch(&points)
+}
+
+/// 1, s, s^2, ..., s^{count-1}
+fn scalar_powers(s: Fr, count: usize) -> Vec<Fr> {
+    std::iter::successors(Some(Fr::one()), |p| Some(*p * s))
+        .take(count)
+        .collect()
+}
+
+impl AggregationSrs {
+    /// SRS con segreti casuali per fino a `max_proofs` prove (arrotondato a potenza di 2).
+    /// Solo per test: chi esegue il setup può falsificare prove aggregate.
+    pub fn setup<R: Rng>(rng: &mut R, max_proofs: usize) -> Self {
+        let n = max_proofs.max(2).next_power_of_two();
+        let alpha = Fr::rand(rng);
+        let beta = Fr::rand(rng);
+
+        AggregationSrs {
+            g_alpha_powers: generator_powers::<G1Projective>(alpha, 2 * n),
+            g_beta_powers: generator_powers::<G1Projective>(beta, 2 * n),
+            h_alpha_powers: generator_powers::<G2Projective>(alpha, n),
+            h_beta_powers: generator_powers::<G2Projective>(beta, n),
+        }
+    }
+
+    /// SRS per fino a `max_proofs` prove dalle trascrizioni di due cerimonie
+    /// Powers of Tau (file .ptau di snarkjs, anche dopo la fase 2): le
+    /// potenze di a da `alpha_ptau`, quelle di b da `beta_ptau`.
+    ///
+    /// Le due cerimonie devono essere indipendenti; le potenze di ciascun file
+    /// sono controllate con un pairing randomizzato prima dell'uso.
+    pub fn from_ptau(
+        alpha_ptau: &str,
+        beta_ptau: &str,
+        max_proofs: usize,
+    ) -> Result<Self, ProverError> {
+        let n = max_proofs
+            .max(2)
+            .checked_next_power_of_two()
+            .ok_or_else(|| {
+                ProverError::Aggregation(format!("{} proofs: SRS too large", max_proofs))
+            })?;
+        let (g_alpha_powers, h_alpha_powers) = read_ptau_powers(alpha_ptau, n)?;
+        let (g_beta_powers, h_beta_powers) = read_ptau_powers(beta_ptau, n)?;
+        if g_alpha_powers[1] == g_beta_powers[1] {
+            return Err(ProverError::Aggregation(format!(
+                "{} and {} come from the same ceremony: the SRS needs two independent secrets",
+                alpha_ptau, beta_ptau
+            )));
+        }
+
+        Ok(AggregationSrs {
+            g_alpha_powers,
+            g_beta_powers,
+            h_alpha_powers,
+            h_beta_powers,
+        })
+    }
+
+    /// Carica una SRS serializzata con [`AggregationSrs::save`] (formato arkworks compresso)
+    pub fn load(path: &str) -> Result<Self, ProverError> {
+        let file = File::open(path).map_err(|_| ProverError::missing("aggregation SRS", path))?;
+        let srs = Self::deserialize_compressed(BufReader::new(file))
+            .map_err(|e| ProverError::Aggregation(format!("invalid SRS {}: {}", path, e)))?;
+        srs.validate_lengths()?;
+        Ok(srs)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), ProverError> {
+        let file = File::create(path)?;
+        self.serialize_compressed(BufWriter::new(file))
+            .map_err(|e| ProverError::Aggregation(format!("cannot write SRS {}: {}", path, e)))
+    }
+
+    fn validate_lengths(&self) -> Result<(), ProverError> {
+        let n = self.h_alpha_powers.len();
+        if n < 2
+            || !n.is_power_of_two()
+            || self.h_beta_powers.len() != n
+            || self.g_alpha_powers.len() != 2 * n
+            || self.g_beta_powers.len() != 2 * n
+        {
+            return Err(ProverError::Aggregation(
+                "inconsistent SRS: expected 2n G1 and n G2 powers, n a power of two".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Massimo numero di prove aggregabili (dopo il padding a potenza di 2)
+    pub fn max_proofs(&self) -> usize {
+        self.h_alpha_powers.len()
+    }
+
+    pub fn verifier_key(&self) -> AggregationVerifierKey {
+        AggregationVerifierKey {
+            g: self.g_alpha_powers[0],
+            h: self.h_alpha_powers[0],
+            g_alpha: self.g_alpha_powers[1],
+            g_beta: self.g_beta_powers[1],
+            h_alpha: self.h_alpha_powers[1],
+            h_beta: self.h_beta_powers[1],
+        }
+    }
+
+    /// Chiavi di impegno per n prove: v = (h^{a^i}, h^{b^i}), w = (g^{a^{n+i}}, g^{b^{n+i}})
+    fn commitment_keys(&self, n: usize) -> (CommitmentKey<G2Affine>, CommitmentKey<G1Affine>) {
+        let v = CommitmentKey {
+            a: self.h_alpha_powers[..n].to_vec(),
+            b: self.h_beta_powers[..n].to_vec(),
+        };
+        let w = CommitmentKey {
+            a: self.g_alpha_powers[n..2 * n].to_vec(),
+            b: self.g_beta_powers[n..2 * n].to_vec(),
+        };
+        (v, w)
+    }
+}
+
+/// Magic "ptau" dei file Powers of Tau di snarkjs
+const PTAU_MAGIC: u32 = 0x75617470;
+const PTAU_SECTION_HEADER: u32 = 1;
+const PTAU_SECTION_TAU_G1: u32 = 2;
+const PTAU_SECTION_TAU_G2: u32 = 3;
+
+/// Le prime 2n potenze di tau in G1 e n in G2 di un file .ptau.
+/// Una cerimonia di potenza p contiene 2^{p+1} - 1 punti G1 e 2^p punti G2.
+fn read_ptau_powers(path: &str, n: usize) -> Result<(Vec<G1Affine>, Vec<G2Affine>), ProverError> {
+    let invalid =
+        |e: ProverError| ProverError::Aggregation(format!("invalid ptau {}: {}", path, e));
+    let file = File::open(path).map_err(|_| ProverError::missing("ptau file", path))?;
+    let mut ptau = ZkeyParser::binfile(BufReader::new(file), PTAU_MAGIC).map_err(invalid)?;
+
+    // Header: n8, q, potenza della cerimonia
+    ptau.seek_section(PTAU_SECTION_HEADER, 4 + 32 + 4)
+        .map_err(invalid)?;
+    let n8 = ptau.read_u32().map_err(invalid)?;
+    let q = ptau.read_array::<32>().map_err(invalid)?;
+    if n8 != 32 || q.to_vec() != Fq::MODULUS.to_bytes_le() {
+        return Err(ProverError::Aggregation(format!(
+            "ptau {} is not a BN254 ceremony",
+            path
+        )));
+    }
+    // 2^{p+1} - 1 deve stare in usize: una potenza più grande è un header corrotto
+    let power = ptau.read_u32().map_err(invalid)?;
+    if power >= usize::BITS - 1 {
+        return Err(ProverError::Aggregation(format!(
+            "invalid ptau {}: power {} out of range",
+            path, power
+        )));
+    }
+    // 2n punti G1 su 2^{p+1} - 1, cioè n < 2^p
+    if n >= 1usize << power {
+        return Err(ProverError::Aggregation(format!(
+            "ptau {} has power {}: too small for {} proofs",
+            path, power, n
+        )));
+    }
+
+    let g = ptau
+        .read_g1_section(PTAU_SECTION_TAU_G1, 2 * n)
+        .map_err(invalid)?;
+    let h = ptau
+        .read_g2_section(PTAU_SECTION_TAU_G2, n)
+        .map_err(invalid)?;
+    if g[0] != G1Affine::generator() || h[0] != G2Affine::generator() || !powers_consistent(&g, &h)
+    {
+        return Err(ProverError::Aggregation(format!(
+            "ptau {} does not contain consecutive powers of a single tau",
+            path
+        )));
+    }
+    Ok((g, h))
+}
+
+/// Controllo randomizzato che g e h siano potenze consecutive dello stesso tau:
+/// con ρ casuale, e(Σ ρ^i·g_{i+1}, h) = e(Σ ρ^i·g_i, h^tau) e
+/// e(g, Σ ρ^i·h_{i+1}) = e(g^tau, Σ ρ^i·h_i)
+fn powers_consistent(g: &[G1Affine], h: &[G2Affine]) -> bool {
+    let rho = Fr::rand(&mut ark_std::rand::thread_rng());
+    let g_rho = scalar_powers(rho, g.len() - 1);
+    let h_rho = scalar_powers(rho, h.len() - 1);
+    let g_shifted = G1Projective::msm_unchecked(&g[1..], &g_rho);
+    let g_base = G1Projective::msm_unchecked(&g[..g.len() - 1], &g_rho);
+    let h_shifted = G2Projective::msm_unchecked(&h[1..], &h_rho);
+    let h_base = G2Projective::msm_unchecked(&h[..h.len() - 1], &h_rho);
+
+    Bn254::pairing(g_shifted, h[0]) == Bn254::pairing(g_base, h[1])
+        && Bn254::pairing(g[0], h_shifted) == Bn254::pairing(g[1], h_base)
+}
+
+/// Coppia di vettori di chiavi (potenze di a, potenze di b)
+#[derive(Clone)]
+struct CommitmentKey<A> {
+    a: Vec<A>,
+    b: Vec<A>,
+}
+
+impl<A: AffineRepr<ScalarField = Fr>> CommitmentKey<A> {
+    fn split(&self) -> (Self, Self) {
+        let (a_left, a_right) = self.a.split_at(self.a.len() / 2);
+        let (b_left, b_right) = self.b.split_at(self.b.len() / 2);
+        (
+            CommitmentKey {
+                a: a_left.to_vec(),
+                b: b_left.to_vec(),
+            },
+            CommitmentKey {
+                a: a_right.to_vec(),
+                b: b_right.to_vec(),
+            },
+        )
+    }
+
+    /// key_i · s_i
+    fn scale(&self, scalars: &[Fr]) -> Self {
+        let scale = |points: &[A]| {
+            let scaled: Vec<A::Group> = points.iter().zip(scalars).map(|(p, s)| *p * s).collect();
+            A::Group::normalize_batch(&scaled)
+        };
+        CommitmentKey {
+            a: scale(&self.a),
+            b: scale(&self.b),
+        }
+    }
+
+    fn fold(&self, x: Fr) -> Self {
+        CommitmentKey {
+            a: fold(&self.a, x),
+            b: fold(&self.b, x),
+        }
+    }
+
+    fn first(&self) -> (A, A) {
+        (self.a[0], self.b[0])
+    }
+}
+
+/// left_i + right_i · x, con left/right le due metà di `v`
+fn fold<A: AffineRepr<ScalarField = Fr>>(v: &[A], x: Fr) -> Vec<A> {
+    let (left, right) = v.split_at(v.len() / 2);
+    let folded: Vec<A::Group> = left.iter().zip(right).map(|(l, r)| *r * x + l).collect();
+    A::Group::normalize_batch(&folded)
+}
+
+/// T = Π e(A_i, v.a_i) · e(w.a_i, B_i), U = lo stesso con le chiavi in b
+fn commit_pair(
+    v: &CommitmentKey<G2Affine>,
+    w: &CommitmentKey<G1Affine>,
+    a: &[G1Affine],
+    b: &[G2Affine],
+) -> Commitment {
+    let half = |va: &[G2Affine], wa: &[G1Affine]| {
+        Bn254::multi_pairing(a.iter().chain(wa), va.iter().chain(b))
+    };
+    (half(&v.a, &w.a), half(&v.b, &w.b))
+}
+
+/// T = Π e(C_i, v.a_i), U = Π e(C_i, v.b_i)
+fn commit_single(v: &CommitmentKey<G2Affine>, c: &[G1Affine]) -> Commitment {
+    (Bn254::multi_pairing(c, &v.a), Bn254::multi_pairing(c, &v.b))
+}
+
+/// Π_j (1 + c_j · z^{2^{k-1-j}}): valutazione del polinomio delle chiavi
+/// ripiegate con le sfide `challenges` (la prima sfida piega il bit alto)
+fn eval_folded(challenges: &[Fr], z: Fr) -> Fr {
+    let mut power = z;
+    let mut acc = Fr::one();
+    for c in challenges.iter().rev() {
+        acc *= Fr::one() + *c * power;
+        power.square_in_place();
+    }
+    acc
+}
+
+/// Coefficienti di [`eval_folded`]: coeff_i = Π c_j sui bit di i
+fn folded_coefficients(challenges: &[Fr]) -> Vec<Fr> {
+    let mut coeffs = vec![Fr::one()];
+    for c in challenges.iter().rev() {
+        let upper: Vec<Fr> = coeffs.iter().map(|x| *x * c).collect();
+        coeffs.extend(upper);
+    }
+    coeffs
+}
+
+/// Quoziente (f(X) - f(z)) / (X - z)
+fn kzg_quotient(coeffs: &[Fr], z: Fr) -> Vec<Fr> {
+    let mut quotient = vec![Fr::zero(); coeffs.len() - 1];
+    let mut acc = Fr::zero();
+    for i in (1..coeffs.len()).rev() {
+        acc = acc * z + coeffs[i];
+        quotient[i - 1] = acc;
+    }
+    quotient
+}
+
+// ============================================================================
+// TRANSCRIPT
+// ============================================================================
+
+/// Encoding in parole big-endian da 32 byte per il transcript.
+/// G1 = (x, y), infinito = (0, 0); G2 = (x.c1, x.c0, y.c1, y.c0) come EIP-197;
+/// GT = 12 coefficienti Fq nell'ordine della torre Fq2 → Fq6 → Fq12.
+trait EvmWords {
+    fn write_words(&self, out: &mut Vec<u8>);
+}
+
+impl EvmWords for Fq {
+    fn write_words(&self, out: &mut Vec<u8>) {
+        out.extend(self.into_bigint().to_bytes_be());
+    }
+}
+
+impl EvmWords for Fr {
+    fn write_words(&self, out: &mut Vec<u8>) {
+        out.extend(self.into_bigint().to_bytes_be());
+    }
+}
+
+// Tipi concreti: con gli alias G1Affine/G2Affine le due impl risultano in conflitto
+impl EvmWords for Affine<ark_bn254::g1::Config> {
+    fn write_words(&self, out: &mut Vec<u8>) {
+        let (x, y) = self.xy().unwrap_or((Fq::zero(), Fq::zero()));
+        x.write_words(out);
+        y.write_words(out);
+    }
+}
+
+impl EvmWords for Affine<ark_bn254::g2::Config> {
+    fn write_words(&self, out: &mut Vec<u8>) {
+        let (x, y) = self.xy().unwrap_or_default();
+        for coeff in [x.c1, x.c0, y.c1, y.c0] {
+            coeff.write_words(out);
+        }
+    }
+}
+
+impl EvmWords for Gt {
+    fn write_words(&self, out: &mut Vec<u8>) {
+        for coeff in self.0.to_base_prime_field_elements() {
+            coeff.write_words(out);
+        }
+    }
+}
+
+impl EvmWords for [Fr] {
+    fn write_words(&self, out: &mut Vec<u8>) {
+        for x in self {
+            x.write_words(out);
+        }
+    }
+}
+
+impl<A: EvmWords, B: EvmWords> EvmWords for (A, B) {
+    fn write_words(&self, out: &mut Vec<u8>) {
+        self.0.write_words(out);
+        self.1.write_words(out);
+    }
+}
+
+/// Fiat-Shamir: state = keccak256(state || parole), sfida = keccak256(state) mod r
+struct Transcript {
+    state: [u8; 32],
+}
+
+impl Transcript {
+    fn new(public_inputs: &[Vec<Fr>]) -> Self {
+        let mut transcript = Transcript {
+            state: Keccak256::digest(TRANSCRIPT_DOMAIN).into(),
+        };
+        for inputs in public_inputs {
+            transcript.append(inputs.as_slice());
+        }
+        transcript
+    }
+
+    fn append<T: EvmWords + ?Sized>(&mut self, value: &T) {
+        let mut data = self.state.to_vec();
+        value.write_words(&mut data);
+        self.state = Keccak256::digest(&data).into();
+    }
+
+    /// Sfida non nulla (serve anche il suo inverso)
+    fn challenge(&mut self) -> Fr {
+        loop {
+            self.state = Keccak256::digest(self.state).into();
+            let c = Fr::from_be_bytes_mod_order(&self.state);
+            if !c.is_zero() {
+                return c;
+            }
+        }
+    }
+}
+
+// ============================================================================
+// PROVA AGGREGATA
+// ============================================================================
+
+/// Messaggi di un round GIPA (parte sinistra, parte destra)
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct GipaRound {
+    /// Impegni incrociati sulla coppia (A, B^r)
+    pub com_ab: (Commitment, Commitment),
+    /// Impegni incrociati su C
+    pub com_c: (Commitment, Commitment),
+    /// Prodotti di pairing incrociati di (A, B^r)
+    pub z_ab: (Gt, Gt),
+    /// Multi-esponenziazioni incrociate di (C, r)
+    pub z_c: (G1Affine, G1Affine),
+}
+
+impl EvmWords for GipaRound {
+    fn write_words(&self, out: &mut Vec<u8>) {
+        self.com_ab.write_words(out);
+        self.com_c.write_words(out);
+        self.z_ab.write_words(out);
+        self.z_c.write_words(out);
+    }
+}
+
+/// Prova aggregata di n prove Groth16 (n potenza di 2, log2(n) round GIPA).
+/// Si trasporta con CanonicalSerialize e si verifica con [`verify_aggregate_proof`].
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregateProof {
+    /// Impegno su (A, B)
+    pub com_ab: Commitment,
+    /// Impegno su C
+    pub com_c: Commitment,
+    /// Π e(A_i, B_i)^{r^i}
+    pub ip_ab: Gt,
+    /// Σ r^i · C_i
+    pub agg_c: G1Affine,
+    pub rounds: Vec<GipaRound>,
+    pub final_a: G1Affine,
+    pub final_b: G2Affine,
+    pub final_c: G1Affine,
+    /// Chiavi ripiegate e relative aperture KZG
+    pub final_vkey: (G2Affine, G2Affine),
+    pub final_wkey: (G1Affine, G1Affine),
+    pub vkey_opening: (G2Affine, G2Affine),
+    pub wkey_opening: (G1Affine, G1Affine),
+}
+
+impl AggregateProof {
+    /// Numero di prove aggregate dopo il padding
+    pub fn num_proofs(&self) -> usize {
+        1 << self.rounds.len()
+    }
+}
+
+/// Porta prove e input a una potenza di 2 (minimo 2) ripetendo l'ultimo elemento
+fn pad_to_power_of_two<T: Clone>(items: &[T]) -> Vec<T> {
+    let n = items.len().max(2).next_power_of_two();
+    let mut padded = items.to_vec();
+    padded.resize(n, items[items.len() - 1].clone());
+    padded
+}
+
+/// Aggrega `proofs` (tutte con la stessa VK) con i rispettivi input pubblici.
+/// Il numero di prove viene portato a una potenza di 2 ripetendo l'ultima:
+/// il verifier applica lo stesso padding agli input.
+pub fn aggregate_proofs(
+    srs: &AggregationSrs,
+    proofs: &[Proof<Bn254>],
+    public_inputs: &[Vec<Fr>],
+) -> Result<AggregateProof, ProverError> {
+    if proofs.is_empty() {
+        return Err(ProverError::Aggregation(
+            "no proofs to aggregate".to_string(),
+        ));
+    }
+    if proofs.len() != public_inputs.len() {
+        return Err(ProverError::Aggregation(format!(
+            "{} proofs but {} public input vectors",
+            proofs.len(),
+            public_inputs.len()
+        )));
+    }
+    let proofs = pad_to_power_of_two(proofs);
+    let public_inputs = pad_to_power_of_two(public_inputs);
+    let n = proofs.len();
+    if n > srs.max_proofs() {
+        return Err(ProverError::Aggregation(format!(
+            "{} proofs exceed the SRS size {}",
+            n,
+            srs.max_proofs()
+        )));
+    }
+
+    let a: Vec<G1Affine> = proofs.iter().map(|p| p.a).collect();
+    let b: Vec<G2Affine> = proofs.iter().map(|p| p.b).collect();
+    let c: Vec<G1Affine> = proofs.iter().map(|p| p.c).collect();
+    let (vkey, wkey) = srs.commitment_keys(n);
+
+    let com_ab = commit_pair(&vkey, &wkey, &a, &b);
+    let com_c = commit_single(&vkey, &c);
+
+    let mut transcript = Transcript::new(&public_inputs);
+    transcript.append(&com_ab);
+    transcript.append(&com_c);
+    let r = transcript.challenge();
+    let r_vec = scalar_powers(r, n);
+    let r_inv_vec = scalar_powers(r.inverse().expect("challenge is non-zero"), n);
+
+    // B^{r^i} e w^{r^{-i}}: l'impegno su (A, B^r) con w riscalata resta com_ab
+    let b_r = G2Projective::normalize_batch(
+        &b.iter()
+            .zip(&r_vec)
+            .map(|(b, r)| *b * r)
+            .collect::<Vec<_>>(),
+    );
+    let wkey = wkey.scale(&r_inv_vec);
+    let ip_ab = Bn254::multi_pairing(&a, &b_r);
+    let agg_c = G1Projective::msm_unchecked(&c, &r_vec).into_affine();
+    transcript.append(&ip_ab);
+    transcript.append(&agg_c);
+
+    // GIPA: TIPP su (A, B^r) e MIPP su (C, r) con le stesse sfide
+    let (mut a, mut b, mut c, mut r_vec) = (a, b_r, c, r_vec);
+    let (mut vkey, mut wkey) = (vkey, wkey);
+    let mut rounds = Vec::new();
+    let mut challenges = Vec::new();
+    while a.len() > 1 {
+        let half = a.len() / 2;
+        let (a_left, a_right) = a.split_at(half);
+        let (b_left, b_right) = b.split_at(half);
+        let (c_left, c_right) = c.split_at(half);
+        let (r_left, r_right) = r_vec.split_at(half);
+        let (v_left, v_right) = vkey.split();
+        let (w_left, w_right) = wkey.split();
+
+        let round = GipaRound {
+            com_ab: (
+                commit_pair(&v_left, &w_right, a_right, b_left),
+                commit_pair(&v_right, &w_left, a_left, b_right),
+            ),
+            com_c: (
+                commit_single(&v_left, c_right),
+                commit_single(&v_right, c_left),
+            ),
+            z_ab: (
+                Bn254::multi_pairing(a_right, b_left),
+                Bn254::multi_pairing(a_left, b_right),
+            ),
+            z_c: (
+                G1Projective::msm_unchecked(c_right, r_left).into_affine(),
+                G1Projective::msm_unchecked(c_left, r_right).into_affine(),
+            ),
+        };
+        transcript.append(&round);
+        let x = transcript.challenge();
+        let x_inv = x.inverse().expect("challenge is non-zero");
+
+        // A, C, w si piegano con x; B, r, v con x^-1
+        a = fold(&a, x);
+        c = fold(&c, x);
+        b = fold(&b, x_inv);
+        r_vec = r_left
+            .iter()
+            .zip(r_right)
+            .map(|(l, r)| *l + *r * x_inv)
+            .collect();
+        vkey = vkey.fold(x_inv);
+        wkey = wkey.fold(x);
+
+        rounds.push(round);
+        challenges.push(x);
+    }
+
+    let final_vkey = vkey.first();
+    let final_wkey = wkey.first();
+    transcript.append(&final_vkey);
+    transcript.append(&final_wkey);
+    let z = transcript.challenge();
+
+    // v finale = h^{f_v(a)}, f_v(X) = Π (1 + x_j^-1 X^{2^{k-1-j}})
+    let challenges_inv: Vec<Fr> = challenges
+        .iter()
+        .map(|x| x.inverse().expect("challenge is non-zero"))
+        .collect();
+    let v_quotient = kzg_quotient(&folded_coefficients(&challenges_inv), z);
+    let vkey_opening = (
+        G2Projective::msm_unchecked(&srs.h_alpha_powers[..v_quotient.len()], &v_quotient)
+            .into_affine(),
+        G2Projective::msm_unchecked(&srs.h_beta_powers[..v_quotient.len()], &v_quotient)
+            .into_affine(),
+    );
+
+    // w finale = g^{f_w(a)}, f_w(X) = X^n Π (1 + x_j (X/r)^{2^{k-1-j}})
+    let mut w_coeffs = vec![Fr::zero(); n];
+    w_coeffs.extend(
+        folded_coefficients(&challenges)
+            .into_iter()
+            .zip(&r_inv_vec)
+            .map(|(c, r_inv)| c * r_inv),
+    );
+    let w_quotient = kzg_quotient(&w_coeffs, z);
+    let wkey_opening = (
+        G1Projective::msm_unchecked(&srs.g_alpha_powers[..w_quotient.len()], &w_quotient)
+            .into_affine(),
+        G1Projective::msm_unchecked(&srs.g_beta_powers[..w_quotient.len()], &w_quotient)
+            .into_affine(),
+    );
+
+    Ok(AggregateProof {
+        com_ab,
+        com_c,
+        ip_ab,
+        agg_c,
+        rounds,
+        final_a: a[0],
+        final_b: b[0],
+        final_c: c[0],
+        final_vkey,
+        final_wkey,
+        vkey_opening,
+        wkey_opening,
+    })
+}
+
+/// Prova Groth16 e input pubblici di un [`ProofResult`] (JSON snarkjs)
+pub(crate) fn decode_proof_result(
+    result: &ProofResult,
+) -> Result<(Proof<Bn254>, Vec<Fr>), ProverError> {
+    let json = std::str::from_utf8(&result.proof)
+        .map_err(|e| ProverError::InvalidInput(format!("proof is not UTF-8 JSON: {}", e)))?;
+    let proof = SnarkjsProof::from_json(json)?.to_arkworks_proof()?;
+    Ok((proof, parse_public_inputs(&result.public_inputs)?))
+}
+
+/// Vero se Π e(p_i, q_i) = 1
+fn pairing_product_is_one(g1: &[G1Affine], g2: &[G2Affine]) -> bool {
+    Bn254::multi_pairing(g1, g2).is_zero()
+}
+
+/// Verifica una prova aggregata contro la VK delle prove e i loro input
+/// pubblici (nell'ordine usato in aggregazione, senza padding).
+/// Ok(false) se un controllo fallisce, errore se prova o input sono malformati.
+pub fn verify_aggregate_proof(
+    ip_vk: &AggregationVerifierKey,
+    vk: &VerifyingKey<Bn254>,
+    public_inputs: &[Vec<Fr>],
+    proof: &AggregateProof,
+) -> Result<bool, ProverError> {
+    if public_inputs.is_empty() {
+        return Err(ProverError::Aggregation("no public inputs".to_string()));
+    }
+    let public_inputs = pad_to_power_of_two(public_inputs);
+    let n = public_inputs.len();
+    if proof.rounds.len() >= usize::BITS as usize || proof.num_proofs() != n {
+        return Err(ProverError::Aggregation(format!(
+            "aggregate proof has {} rounds, expected {} for {} proofs",
+            proof.rounds.len(),
+            n.trailing_zeros(),
+            n
+        )));
+    }
+    if let Some(inputs) = public_inputs
+        .iter()
+        .find(|inputs| inputs.len() + 1 != vk.gamma_abc_g1.len())
+    {
+        return Err(ProverError::InvalidInput(format!(
+            "wrong number of public inputs: expected {}, got {}",
+            vk.gamma_abc_g1.len() - 1,
+            inputs.len()
+        )));
+    }
+
+    let mut transcript = Transcript::new(&public_inputs);
+    transcript.append(&proof.com_ab);
+    transcript.append(&proof.com_c);
+    let r = transcript.challenge();
+    let r_vec = scalar_powers(r, n);
+
+    // Equazione Groth16 randomizzata:
+    // ip_ab = e(α, β)^{Σr^i} · e(Σ r^i·vk_x_i, γ) · e(agg_c, δ)
+    let r_sum: Fr = r_vec.iter().sum();
+    let mut input_scalars = vec![r_sum];
+    for j in 0..vk.gamma_abc_g1.len() - 1 {
+        input_scalars.push(
+            public_inputs
+                .iter()
+                .zip(&r_vec)
+                .map(|(inputs, r)| inputs[j] * r)
+                .sum(),
+        );
+    }
+    let vk_x = G1Projective::msm_unchecked(&vk.gamma_abc_g1, &input_scalars).into_affine();
+    let expected = Bn254::pairing(vk.alpha_g1, vk.beta_g2) * r_sum
+        + Bn254::multi_pairing([vk_x, proof.agg_c], [vk.gamma_g2, vk.delta_g2]);
+    if expected != proof.ip_ab {
+        return Ok(false);
+    }
+
+    // GIPA: ricostruisce impegni e valori finali con le sfide del transcript
+    transcript.append(&proof.ip_ab);
+    transcript.append(&proof.agg_c);
+    let (mut com_ab, mut com_c) = (proof.com_ab, proof.com_c);
+    let mut z_ab = proof.ip_ab;
+    let mut z_c = G1Projective::from(proof.agg_c);
+    let mut challenges = Vec::with_capacity(proof.rounds.len());
+    let mut challenges_inv = Vec::with_capacity(proof.rounds.len());
+    for round in &proof.rounds {
+        transcript.append(round);
+        let x = transcript.challenge();
+        let x_inv = x.inverse().expect("challenge is non-zero");
+
+        let ((l_ab, r_ab), (l_c, r_c)) = (round.com_ab, round.com_c);
+        com_ab.0 += l_ab.0 * x + r_ab.0 * x_inv;
+        com_ab.1 += l_ab.1 * x + r_ab.1 * x_inv;
+        com_c.0 += l_c.0 * x + r_c.0 * x_inv;
+        com_c.1 += l_c.1 * x + r_c.1 * x_inv;
+        z_ab += round.z_ab.0 * x + round.z_ab.1 * x_inv;
+        z_c += round.z_c.0 * x + round.z_c.1 * x_inv;
+
+        challenges.push(x);
+        challenges_inv.push(x_inv);
+    }
+
+    let (v_a, v_b) = proof.final_vkey;
+    let (w_a, w_b) = proof.final_wkey;
+    let (final_a, final_b, final_c) = (proof.final_a, proof.final_b, proof.final_c);
+    let final_r = eval_folded(&challenges_inv, r);
+
+    let gipa_ok = Bn254::pairing(final_a, final_b) == z_ab
+        && Bn254::multi_pairing([final_a, w_a], [v_a, final_b]) == com_ab.0
+        && Bn254::multi_pairing([final_a, w_b], [v_b, final_b]) == com_ab.1
+        && final_c * final_r == z_c
+        && Bn254::pairing(final_c, v_a) == com_c.0
+        && Bn254::pairing(final_c, v_b) == com_c.1;
+    if !gipa_ok {
+        return Ok(false);
+    }
+
+    // KZG: le chiavi finali sono i polinomi delle sfide valutati in a e b
+    transcript.append(&proof.final_vkey);
+    transcript.append(&proof.final_wkey);
+    let z = transcript.challenge();
+    let r_inv = r.inverse().expect("challenge is non-zero");
+    let f_v = eval_folded(&challenges_inv, z);
+    let f_w = z.pow([n as u64]) * eval_folded(&challenges, z * r_inv);
+
+    let g = ip_vk.g.into_group();
+    let h = ip_vk.h.into_group();
+    let g_z = g * z;
+    let h_z = h * z;
+    let (v_open_a, v_open_b) = proof.vkey_opening;
+    let (w_open_a, w_open_b) = proof.wkey_opening;
+    let v_check = |g_secret: G1Affine, v: G2Affine, opening: G2Affine| {
+        // e(g^{s-z}, π) = e(g, v - h^{f_v(z)})
+        pairing_product_is_one(
+            &[
+                (g_secret.into_group() - g_z).into_affine(),
+                (-g).into_affine(),
+            ],
+            &[opening, (v.into_group() - h * f_v).into_affine()],
+        )
+    };
+    let w_check = |h_secret: G2Affine, w: G1Affine, opening: G1Affine| {
+        // e(π, h^{s-z}) = e(w - g^{f_w(z)}, h)
+        pairing_product_is_one(
+            &[opening, (g * f_w - w).into_affine()],
+            &[(h_secret.into_group() - h_z).into_affine(), h.into_affine()],
+        )
+    };
+
+    Ok(v_check(ip_vk.g_alpha, v_a, v_open_a)
+        && v_check(ip_vk.g_beta, v_b, v_open_b)
+        && w_check(ip_vk.h_alpha, w_a, w_open_a)
+        && w_check(ip_vk.h_beta, w_b, w_open_b))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use ark_groth16::{Groth16, ProvingKey};
+    use ark_relations::lc;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+    /// x · y = z con z pubblico
+    #[derive(Clone)]
+    struct MulCircuit {
+        x: Fr,
+        y: Fr,
+    }
+
+    impl ConstraintSynthesizer<Fr> for MulCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let z = cs.new_input_variable(|| Ok(self.x * self.y))?;
+            let x = cs.new_witness_variable(|| Ok(self.x))?;
+            let y = cs.new_witness_variable(|| Ok(self.y))?;
+            cs.enforce_constraint(lc!() + x, lc!() + y, lc!() + z)?;
+            Ok(())
+        }
+    }
+
+    /// `count` prove Groth16 reali di un circuito minimo, con i loro input pubblici
+    pub(crate) fn sample_proofs(
+        rng: &mut StdRng,
+        count: u64,
+    ) -> (ProvingKey<Bn254>, Vec<Proof<Bn254>>, Vec<Vec<Fr>>) {
+        let circuit = MulCircuit {
+            x: Fr::zero(),
+            y: Fr::zero(),
+        };
+        let pk = Groth16::<Bn254>::generate_random_parameters_with_reduction(circuit, rng).unwrap();
+
+        let (mut proofs, mut inputs) = (Vec::new(), Vec::new());
+        for i in 1..=count {
+            let circuit = MulCircuit {
+                x: Fr::from(i),
+                y: Fr::from(i + 10),
+            };
+            proofs.push(
+                Groth16::<Bn254>::create_random_proof_with_reduction(circuit, &pk, rng).unwrap(),
+            );
+            inputs.push(vec![Fr::from(i * (i + 10))]);
+        }
+        (pk, proofs, inputs)
+    }
+
+    #[test]
+    fn test_aggregate_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let (pk, proofs, inputs) = sample_proofs(&mut rng, 3);
+
+        let srs = AggregationSrs::setup(&mut rng, 4);
+        let ip_vk = srs.verifier_key();
+        let aggregate = aggregate_proofs(&srs, &proofs, &inputs).unwrap();
+        assert_eq!(aggregate.num_proofs(), 4);
+        assert!(verify_aggregate_proof(&ip_vk, &pk.vk, &inputs, &aggregate).unwrap());
+
+        // Input pubblici diversi da quelli provati
+        let mut wrong = inputs.clone();
+        wrong[1][0] += Fr::one();
+        assert!(!verify_aggregate_proof(&ip_vk, &pk.vk, &wrong, &aggregate).unwrap());
+
+        // Prova aggregata manomessa
+        let mut tampered = aggregate.clone();
+        tampered.final_c = (tampered.final_c + G1Affine::generator()).into_affine();
+        assert!(!verify_aggregate_proof(&ip_vk, &pk.vk, &inputs, &tampered).unwrap());
+    }
+
+    #[test]
+    fn test_aggregate_tampered_input_proof() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let (pk, mut proofs, inputs) = sample_proofs(&mut rng, 4);
+        let srs = AggregationSrs::setup(&mut rng, 4);
+
+        // Una delle prove aggregate non è valida per il suo input
+        proofs[2].c = (proofs[2].c + G1Affine::generator()).into_affine();
+        let aggregate = aggregate_proofs(&srs, &proofs, &inputs).unwrap();
+        assert!(!verify_aggregate_proof(&srs.verifier_key(), &pk.vk, &inputs, &aggregate).unwrap());
+
+        // Prove valide scambiate tra loro rispetto agli input
+        let (_, mut proofs, _) = sample_proofs(&mut StdRng::seed_from_u64(11), 4);
+        proofs.swap(0, 1);
+        let aggregate = aggregate_proofs(&srs, &proofs, &inputs).unwrap();
+        assert!(!verify_aggregate_proof(&srs.verifier_key(), &pk.vk, &inputs, &aggregate).unwrap());
+    }
+
+    #[test]
+    fn test_aggregate_non_power_of_two() {
+        let mut rng = StdRng::seed_from_u64(13);
+        let srs = AggregationSrs::setup(&mut rng, 8);
+        let ip_vk = srs.verifier_key();
+
+        for (count, padded) in [(1u64, 2), (5, 8), (7, 8)] {
+            let last = count as usize - 1;
+            let (pk, proofs, inputs) = sample_proofs(&mut rng, count);
+            let aggregate = aggregate_proofs(&srs, &proofs, &inputs).unwrap();
+            assert_eq!(aggregate.num_proofs(), padded);
+            assert!(verify_aggregate_proof(&ip_vk, &pk.vk, &inputs, &aggregate).unwrap());
+
+            // Gli input del padding non si possono omettere né aggiungere
+            let mut wrong = inputs.clone();
+            wrong[last][0] += Fr::one();
+            assert!(!verify_aggregate_proof(&ip_vk, &pk.vk, &wrong, &aggregate).unwrap());
+            assert!(
+                verify_aggregate_proof(&ip_vk, &pk.vk, &inputs[..last], &aggregate)
+                    .map_or(true, |ok| !ok)
+            );
+        }
+    }
+
+    /// Punto G1 in Montgomery little endian, come nei binfile di snarkjs
+    fn write_g1(out: &mut Vec<u8>, p: &G1Affine) {
+        out.extend(p.x.0.to_bytes_le());
+        out.extend(p.y.0.to_bytes_le());
+    }
+
+    fn write_g2(out: &mut Vec<u8>, p: &G2Affine) {
+        for c in [p.x.c0, p.x.c1, p.y.c0, p.y.c1] {
+            out.extend(c.0.to_bytes_le());
+        }
+    }
+
+    /// File .ptau di potenza `power` con tau noto; `corrupt` sostituisce tau^2·g
+    fn write_ptau(path: &std::path::Path, tau: Fr, power: u32, corrupt: bool) {
+        let mut g = generator_powers::<G1Projective>(tau, (1 << (power + 1)) - 1);
+        let h = generator_powers::<G2Projective>(tau, 1 << power);
+        if corrupt {
+            g[2] = (g[2] + G1Affine::generator()).into_affine();
+        }
+
+        let mut header = Vec::new();
+        header.extend(32u32.to_le_bytes());
+        header.extend(Fq::MODULUS.to_bytes_le());
+        header.extend(power.to_le_bytes());
+        header.extend(power.to_le_bytes());
+        let mut tau_g1 = Vec::new();
+        g.iter().for_each(|p| write_g1(&mut tau_g1, p));
+        let mut tau_g2 = Vec::new();
+        h.iter().for_each(|p| write_g2(&mut tau_g2, p));
+
+        let mut file = Vec::new();
+        file.extend(PTAU_MAGIC.to_le_bytes());
+        file.extend(1u32.to_le_bytes());
+        file.extend(3u32.to_le_bytes());
+        for (section, data) in [
+            (PTAU_SECTION_HEADER, header),
+            (PTAU_SECTION_TAU_G1, tau_g1),
+            (PTAU_SECTION_TAU_G2, tau_g2),
+        ] {
+            file.extend(section.to_le_bytes());
+            file.extend((data.len() as u64).to_le_bytes());
+            file.extend(data);
+        }
+        std::fs::write(path, file).unwrap();
+    }
+
+    #[test]
+    fn test_srs_from_ptau() {
+        let mut rng = StdRng::seed_from_u64(17);
+        let dir = tempfile::tempdir().unwrap();
+        let path = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+        let (alpha, beta) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        write_ptau(dir.path().join("alpha.ptau").as_path(), alpha, 3, false);
+        write_ptau(dir.path().join("beta.ptau").as_path(), beta, 3, false);
+        write_ptau(dir.path().join("corrupt.ptau").as_path(), beta, 3, true);
+
+        // Potenza 3: 15 punti G1 bastano per 2n = 8, non per 2n = 16
+        let srs = AggregationSrs::from_ptau(&path("alpha.ptau"), &path("beta.ptau"), 3).unwrap();
+        assert_eq!(srs.max_proofs(), 4);
+        let ip_vk = srs.verifier_key();
+        assert_eq!(ip_vk.g_alpha, (G1Affine::generator() * alpha).into_affine());
+        assert_eq!(ip_vk.h_beta, (G2Affine::generator() * beta).into_affine());
+
+        let (pk, proofs, inputs) = sample_proofs(&mut rng, 3);
+        let aggregate = aggregate_proofs(&srs, &proofs, &inputs).unwrap();
+        assert!(verify_aggregate_proof(&ip_vk, &pk.vk, &inputs, &aggregate).unwrap());
+
+        let err = |alpha: &str, beta: &str, max| {
+            AggregationSrs::from_ptau(&path(alpha), &path(beta), max)
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(err("alpha.ptau", "beta.ptau", 5).contains("too small"));
+        assert!(err("alpha.ptau", "alpha.ptau", 4).contains("same ceremony"));
+        assert!(err("alpha.ptau", "corrupt.ptau", 4).contains("consecutive powers"));
+        assert!(err("alpha.ptau", "missing.ptau", 4).contains("missing.ptau"));
+        assert!(err("alpha.ptau", "beta.ptau", usize::MAX).contains("too large"));
+
+        // Header con una potenza fuori scala: errore, non overflow
+        let mut huge = std::fs::read(path("beta.ptau")).unwrap();
+        for power in [usize::BITS - 1, u32::MAX] {
+            // magic, versione, sezioni, id e lunghezza della sezione, n8, q
+            huge[60..64].copy_from_slice(&power.to_le_bytes());
+            std::fs::write(path("huge.ptau"), &huge).unwrap();
+            assert!(err("alpha.ptau", "huge.ptau", 4).contains("out of range"));
+        }
+    }
+}
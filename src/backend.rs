@@ -0,0 +1,231 @@
+// prover/src/backend.rs
+// Astrazione sui backend di proving
+//
+// BLSProver e BatchProver lavorano su un Box<dyn ProofBackend>: il backend
+// snarkjs (processi esterni) e quello nativo (arkworks in-process) sono
+// intercambiabili per ambiente, e nei test si può usare un mock.
+
+use crate::{
+    CancelToken, NativeProver, ProofInputs, ProofResult, ProofStats, ProverError, SnarkjsProver,
+    VkExportFormat, VkExporter, ZkeyParser,
+};
+use ark_bn254::Bn254;
+use ark_groth16::VerifyingKey;
+use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Future restituiti dai metodi async di [`ProofBackend`]
+pub type BackendFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, ProverError>> + Send + 'a>>;
+
+/// Contatori cumulativi di un backend
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BackendStats {
+    pub proofs_generated: u64,
+    pub proofs_failed: u64,
+    pub total_proving_time_ms: u64,
+}
+
+/// Un backend caricato è condiviso tra i thread di BatchProver e i task
+/// async: deve contenere solo dati Send + Sync (percorsi, Arc ai parametri)
+/// e creare per ogni prova lo stato non condivisibile
+pub trait ProofBackend: Send + Sync {
+    /// Nome per log e metriche
+    fn name(&self) -> &'static str;
+
+    fn setup(&mut self) -> Result<(), ProverError>;
+
+    fn prove(&self, inputs: ProofInputs) -> Result<(ProofResult, ProofStats), ProverError>;
+
+    /// Default: controlla `cancel` solo prima di iniziare
+    fn prove_cancellable(
+        &self,
+        inputs: ProofInputs,
+        cancel: &CancelToken,
+    ) -> Result<(ProofResult, ProofStats), ProverError> {
+        if cancel.is_cancelled() {
+            return Err(ProverError::Cancelled {
+                command: self.name().to_string(),
+            });
+        }
+        self.prove(inputs)
+    }
+
+    /// Default: esegue `prove` in linea (adatto solo a backend non bloccanti, es. mock)
+    fn prove_async(&self, inputs: ProofInputs) -> BackendFuture<'_, (ProofResult, ProofStats)> {
+        Box::pin(async move { self.prove(inputs) })
+    }
+
+    /// Verifica una prova nel formato JSON di snarkjs
+    fn verify(&self, proof_json: &str, public_inputs: &[String]) -> Result<bool, ProverError>;
+
+    fn verify_async<'a>(
+        &'a self,
+        proof_json: &'a str,
+        public_inputs: &'a [String],
+    ) -> BackendFuture<'a, bool> {
+        Box::pin(async move { self.verify(proof_json, public_inputs) })
+    }
+
+    /// VK caricata in setup()
+    fn verifying_key(&self) -> Result<&VerifyingKey<Bn254>, ProverError>;
+
+    /// Default: [`VkExporter`] senza i punti G1 della zkey (il formato gnark fallisce)
+    fn export_vk(&self, format: VkExportFormat) -> Result<Vec<u8>, ProverError> {
+        VkExporter::new(self.verifying_key()?).export(format)
+    }
+
+    fn stats(&self) -> BackendStats;
+}
+
+/// Contatori thread-safe condivisi dalle implementazioni
+#[derive(Debug, Default)]
+pub(crate) struct StatsRecorder {
+    generated: AtomicU64,
+    failed: AtomicU64,
+    proving_time_ms: AtomicU64,
+}
+
+impl StatsRecorder {
+    pub(crate) fn record<T>(
+        &self,
+        result: Result<(T, ProofStats), ProverError>,
+    ) -> Result<(T, ProofStats), ProverError> {
+        match &result {
+            Ok((_, stats)) => {
+                self.generated.fetch_add(1, Ordering::Relaxed);
+                self.proving_time_ms
+                    .fetch_add(stats.proving_time_ms as u64, Ordering::Relaxed);
+            }
+            Err(_) => {
+                self.failed.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        result
+    }
+
+    pub(crate) fn snapshot(&self) -> BackendStats {
+        BackendStats {
+            proofs_generated: self.generated.load(Ordering::Relaxed),
+            proofs_failed: self.failed.load(Ordering::Relaxed),
+            total_proving_time_ms: self.proving_time_ms.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Export con beta/delta G1 letti dall'header della zkey quando servono (gnark)
+fn export_with_zkey(
+    vk: &VerifyingKey<Bn254>,
+    zkey_path: &str,
+    format: VkExportFormat,
+) -> Result<Vec<u8>, ProverError> {
+    let mut exporter = VkExporter::new(vk);
+    if format == VkExportFormat::Gnark {
+        let header = ZkeyParser::new(zkey_path)?.header()?;
+        exporter = exporter.with_g1_points(header.beta_g1, header.delta_g1);
+    }
+    exporter.export(format)
+}
+
+impl ProofBackend for SnarkjsProver {
+    fn name(&self) -> &'static str {
+        "snarkjs"
+    }
+
+    fn setup(&mut self) -> Result<(), ProverError> {
+        SnarkjsProver::setup(self)
+    }
+
+    fn prove(&self, inputs: ProofInputs) -> Result<(ProofResult, ProofStats), ProverError> {
+        self.stats.record(self.generate_proof(inputs))
+    }
+
+    fn prove_cancellable(
+        &self,
+        inputs: ProofInputs,
+        cancel: &CancelToken,
+    ) -> Result<(ProofResult, ProofStats), ProverError> {
+        self.stats
+            .record(self.generate_proof_cancellable(inputs, cancel))
+    }
+
+    fn prove_async(&self, inputs: ProofInputs) -> BackendFuture<'_, (ProofResult, ProofStats)> {
+        Box::pin(async move { self.stats.record(self.generate_proof_async(inputs).await) })
+    }
+
+    fn verify(&self, proof_json: &str, public_inputs: &[String]) -> Result<bool, ProverError> {
+        self.verify_proof(proof_json, public_inputs)
+    }
+
+    fn verify_async<'a>(
+        &'a self,
+        proof_json: &'a str,
+        public_inputs: &'a [String],
+    ) -> BackendFuture<'a, bool> {
+        Box::pin(self.verify_proof_async(proof_json, public_inputs))
+    }
+
+    fn verifying_key(&self) -> Result<&VerifyingKey<Bn254>, ProverError> {
+        self.verifying_key
+            .as_ref()
+            .ok_or(ProverError::NotInitialized)
+    }
+
+    /// Il JSON snarkjs è il verification_key.json originale, byte per byte
+    fn export_vk(&self, format: VkExportFormat) -> Result<Vec<u8>, ProverError> {
+        match format {
+            VkExportFormat::SnarkjsJson => Ok(std::fs::read(self.config.vk_path())?),
+            _ => export_with_zkey(self.verifying_key()?, &self.config.zkey_path(), format),
+        }
+    }
+
+    fn stats(&self) -> BackendStats {
+        self.stats.snapshot()
+    }
+}
+
+impl ProofBackend for NativeProver {
+    fn name(&self) -> &'static str {
+        "native"
+    }
+
+    fn setup(&mut self) -> Result<(), ProverError> {
+        NativeProver::setup(self)
+    }
+
+    fn prove(&self, inputs: ProofInputs) -> Result<(ProofResult, ProofStats), ProverError> {
+        self.stats.record(self.generate_proof(inputs))
+    }
+
+    fn prove_async(&self, inputs: ProofInputs) -> BackendFuture<'_, (ProofResult, ProofStats)> {
+        Box::pin(async move { self.stats.record(self.generate_proof_async(inputs).await) })
+    }
+
+    fn verify(&self, proof_json: &str, public_inputs: &[String]) -> Result<bool, ProverError> {
+        self.verify_proof(proof_json, public_inputs)
+    }
+
+    fn verify_async<'a>(
+        &'a self,
+        proof_json: &'a str,
+        public_inputs: &'a [String],
+    ) -> BackendFuture<'a, bool> {
+        Box::pin(self.verify_proof_async(proof_json, public_inputs))
+    }
+
+    fn verifying_key(&self) -> Result<&VerifyingKey<Bn254>, ProverError> {
+        self.proving_key
+            .as_ref()
+            .map(|pk| &pk.vk)
+            .ok_or(ProverError::NotInitialized)
+    }
+
+    fn export_vk(&self, format: VkExportFormat) -> Result<Vec<u8>, ProverError> {
+        export_with_zkey(self.verifying_key()?, &self.config.zkey_path(), format)
+    }
+
+    fn stats(&self) -> BackendStats {
+        self.stats.snapshot()
+    }
+}
@@ -0,0 +1,131 @@
+// prover/src/batch_verify.rs
+// Verifica di molte prove Groth16 con la stessa VK in un solo multi-pairing
+//
+// Con scalari casuali ρ_i, scelti dal verifier dopo aver ricevuto le prove,
+// le N equazioni e(A_i, B_i) = e(α, β) · e(vk_x_i, γ) · e(C_i, δ) diventano
+//   Π e(ρ_i·A_i, B_i) · e(Σ ρ_i·vk_x_i, -γ) · e(Σ ρ_i·C_i, -δ) = e(α, β)^{Σρ_i}
+// cioè N + 2 Miller loop e una sola esponenziazione finale. Un batch con una
+// prova non valida passa solo con probabilità ~1/r.
+
+use crate::{parse_public_inputs, ProverError, SnarkjsProof};
+use ark_bn254::{Bn254, Fr, G1Projective};
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_groth16::{PreparedVerifyingKey, Proof};
+use ark_std::{rand::thread_rng, UniformRand};
+
+/// Verifica `proofs[i]` con `public_inputs[i]` contro `pvk`.
+/// Ok(true) solo se tutte le prove sono valide; se il batch fallisce non dice
+/// quale prova è errata (verificarle singolarmente per trovarla).
+pub fn verify_batch(
+    pvk: &PreparedVerifyingKey<Bn254>,
+    proofs: &[Proof<Bn254>],
+    public_inputs: &[Vec<Fr>],
+) -> Result<bool, ProverError> {
+    if proofs.len() != public_inputs.len() {
+        return Err(ProverError::InvalidInput(format!(
+            "{} proofs but {} public input vectors",
+            proofs.len(),
+            public_inputs.len()
+        )));
+    }
+    let n_public = pvk.vk.gamma_abc_g1.len() - 1;
+    if let Some(inputs) = public_inputs.iter().find(|i| i.len() != n_public) {
+        return Err(ProverError::InvalidInput(format!(
+            "wrong number of public inputs: expected {}, got {}",
+            n_public,
+            inputs.len()
+        )));
+    }
+    if proofs.is_empty() {
+        return Ok(true);
+    }
+
+    let mut rng = thread_rng();
+    let rho: Vec<Fr> = proofs.iter().map(|_| Fr::rand(&mut rng)).collect();
+    let rho_sum: Fr = rho.iter().sum();
+
+    // Σ ρ_i·vk_x_i = IC_0·Σρ_i + Σ_j IC_{j+1}·(Σ_i ρ_i·x_ij)
+    let mut ic_scalars = vec![rho_sum];
+    for j in 0..n_public {
+        ic_scalars.push(
+            public_inputs
+                .iter()
+                .zip(&rho)
+                .map(|(inputs, r)| inputs[j] * r)
+                .sum(),
+        );
+    }
+    let vk_x = G1Projective::msm_unchecked(&pvk.vk.gamma_abc_g1, &ic_scalars);
+    let c: Vec<_> = proofs.iter().map(|p| p.c).collect();
+    let c_sum = G1Projective::msm_unchecked(&c, &rho);
+    let a_scaled = G1Projective::normalize_batch(
+        &proofs
+            .iter()
+            .zip(&rho)
+            .map(|(p, r)| p.a * r)
+            .collect::<Vec<_>>(),
+    );
+
+    let mut g1: Vec<<Bn254 as Pairing>::G1Prepared> =
+        a_scaled.into_iter().map(Into::into).collect();
+    g1.push(vk_x.into());
+    g1.push(c_sum.into());
+    let mut g2: Vec<<Bn254 as Pairing>::G2Prepared> = proofs.iter().map(|p| p.b.into()).collect();
+    g2.push(pvk.gamma_g2_neg_pc.clone());
+    g2.push(pvk.delta_g2_neg_pc.clone());
+
+    let Some(lhs) = Bn254::final_exponentiation(Bn254::multi_miller_loop(g1, g2)) else {
+        return Ok(false);
+    };
+    Ok(lhs == PairingOutput(pvk.alpha_g1_beta_g2) * rho_sum)
+}
+
+/// [`verify_batch`] su coppie (proof.json, public.json) nel formato di snarkjs
+pub fn verify_snarkjs_batch(
+    pvk: &PreparedVerifyingKey<Bn254>,
+    proofs: &[(String, Vec<String>)],
+) -> Result<bool, ProverError> {
+    let mut parsed = Vec::with_capacity(proofs.len());
+    let mut public_inputs = Vec::with_capacity(proofs.len());
+    for (proof_json, inputs) in proofs {
+        parsed.push(SnarkjsProof::from_json(proof_json)?.to_arkworks_proof()?);
+        public_inputs.push(parse_public_inputs(inputs)?);
+    }
+    verify_batch(pvk, &parsed, &public_inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregation::tests::sample_proofs;
+    use ark_ff::One;
+    use ark_groth16::prepare_verifying_key;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_verify_batch() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let (pk, proofs, inputs) = sample_proofs(&mut rng, 5);
+        let pvk = prepare_verifying_key(&pk.vk);
+
+        assert!(verify_batch(&pvk, &proofs, &inputs).unwrap());
+        assert!(verify_batch(&pvk, &[], &[]).unwrap());
+
+        let mut wrong = inputs.clone();
+        wrong[3][0] += Fr::one();
+        assert!(!verify_batch(&pvk, &proofs, &wrong).unwrap());
+
+        let json: Vec<_> = proofs
+            .iter()
+            .zip(&inputs)
+            .map(|(proof, inputs)| {
+                (
+                    crate::proof_to_snarkjs_json(proof).to_string(),
+                    inputs.iter().map(crate::field_to_decimal).collect(),
+                )
+            })
+            .collect();
+        assert!(verify_snarkjs_batch(&pvk, &json).unwrap());
+    }
+}
@@ -0,0 +1,407 @@
+// prover/src/bls.rs
+// Firme BLS native su BN254: chiavi, firma e verifica
+//
+// Lo schema è quello di bls_verify.circom: chiave pubblica pk = sk · g2 in G2,
+// firma σ = sk · H(m) in G1, verifica e(σ, g2) = e(H(m), pk). Serve a produrre
+// input validi per il prover (test, devnet, benchmark) e a scartare firme
+// non valide prima di spendere tempo di proving.
+//
+// H(m) è l'hash-to-curve di RFC 9380 (hash_to_curve.rs). Senza DST esplicito
+// si usa BN254_G1_DST; le varianti _with_dst servono per altri domini.
+//
+// Per le firme aggregate sullo stesso messaggio (committee.rs) ogni chiave
+// porta una proof of possession (PopProve/PopVerify del draft IETF,
+// sezione 3.3): una firma della chiave stessa con BN254_G1_POP_DST.
+
+use crate::{
+    hash_to_g1, BLSPrivateInputs, BLSProofInputs, BLSPublicInputs, ProverError, BN254_G1_DST,
+    BN254_G1_POP_DST,
+};
+use ark_bn254::{Bn254, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::Pairing;
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField, UniformRand, Zero};
+use ark_std::rand::Rng;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Salt iniziale di KeyGen (draft-irtf-cfrg-bls-signature, sezione 2.3)
+const KEYGEN_SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
+/// KeyGen richiede almeno 32 byte di materiale segreto
+const KEYGEN_MIN_SEED_BYTES: usize = 32;
+
+/// Chiave segreta: scalare non nullo di Fr
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretKey(Fr);
+
+/// Chiave pubblica in G2, validata (nel sottogruppo e diversa dall'infinito)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicKey(G2Affine);
+
+/// Firma in G1, validata (diversa dall'infinito)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature(G1Affine);
+
+impl SecretKey {
+    /// Chiave casuale
+    pub fn generate<R: Rng>(rng: &mut R) -> Self {
+        loop {
+            let sk = Fr::rand(rng);
+            if !sk.is_zero() {
+                return SecretKey(sk);
+            }
+        }
+    }
+
+    /// Derivazione deterministica da un seed di almeno 32 byte (KeyGen del
+    /// draft IETF: HKDF-SHA256 ridotto modulo r, key_info vuoto)
+    pub fn from_seed(seed: &[u8]) -> Result<Self, ProverError> {
+        if seed.len() < KEYGEN_MIN_SEED_BYTES {
+            return Err(ProverError::InvalidInput(format!(
+                "key seed must be at least {} bytes, got {}",
+                KEYGEN_MIN_SEED_BYTES,
+                seed.len()
+            )));
+        }
+        Ok(SecretKey(hkdf_mod_r(seed)))
+    }
+
+    /// Scalare in 32 byte big endian, rifiutando zero e valori >= r
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProverError> {
+        let invalid = |reason: &str| ProverError::InvalidFieldElement {
+            field: "secretKey".to_string(),
+            reason: reason.to_string(),
+        };
+        if bytes.len() != 32 {
+            return Err(invalid("expected 32 bytes"));
+        }
+        let sk = Fr::from_be_bytes_mod_order(bytes);
+        if sk.into_bigint().to_bytes_be() != bytes {
+            return Err(invalid("not smaller than the group order"));
+        }
+        if sk.is_zero() {
+            return Err(invalid("zero secret key"));
+        }
+        Ok(SecretKey(sk))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&self.0.into_bigint().to_bytes_be());
+        bytes
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey((G2Affine::generator() * self.0).into_affine())
+    }
+
+    /// σ = sk · H(m), con il DST predefinito
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        self.sign_with_dst(message, BN254_G1_DST)
+            .expect("default DST is valid")
+    }
+
+    pub fn sign_with_dst(&self, message: &[u8], dst: &[u8]) -> Result<Signature, ProverError> {
+        Ok(self.sign_hashed(&hash_to_g1(message, dst)?))
+    }
+
+    /// σ = sk · H(m) con H(m) già mappato su G1
+    pub fn sign_hashed(&self, message_hash: &G1Affine) -> Signature {
+        Signature((*message_hash * self.0).into_affine())
+    }
+
+    /// PopProve: firma della propria chiave pubblica con BN254_G1_POP_DST
+    pub fn pop_prove(&self) -> Signature {
+        self.sign_with_dst(&self.public_key().to_bytes(), BN254_G1_POP_DST)
+            .expect("PoP DST is valid")
+    }
+}
+
+/// Non stampa lo scalare
+impl std::fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretKey(..)")
+    }
+}
+
+impl PublicKey {
+    pub fn from_point(point: G2Affine) -> Result<Self, ProverError> {
+        checked_nonzero(point, "publicKey").map(PublicKey)
+    }
+
+    pub fn point(&self) -> G2Affine {
+        self.0
+    }
+
+    /// Encoding di 128 byte big endian x.c1, x.c0, y.c1, y.c0 (ordine di
+    /// EIP-197): è il messaggio firmato dalla proof of possession
+    pub fn to_bytes(&self) -> [u8; 128] {
+        let mut bytes = [0u8; 128];
+        let coords = [self.0.x.c1, self.0.x.c0, self.0.y.c1, self.0.y.c0];
+        for (chunk, coord) in bytes.chunks_mut(32).zip(coords) {
+            chunk.copy_from_slice(&coord.into_bigint().to_bytes_be());
+        }
+        bytes
+    }
+
+    /// PopVerify: `proof` è la firma di questa chiave su sé stessa
+    pub fn pop_verify(&self, proof: &Signature) -> bool {
+        self.verify_with_dst(&self.to_bytes(), BN254_G1_POP_DST, proof)
+            .expect("PoP DST is valid")
+    }
+
+    /// Verifica con il DST predefinito
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> bool {
+        self.verify_with_dst(message, BN254_G1_DST, signature)
+            .expect("default DST is valid")
+    }
+
+    pub fn verify_with_dst(
+        &self,
+        message: &[u8],
+        dst: &[u8],
+        signature: &Signature,
+    ) -> Result<bool, ProverError> {
+        Ok(verify_hashed(
+            &self.0,
+            &hash_to_g1(message, dst)?,
+            &signature.0,
+        ))
+    }
+}
+
+impl Signature {
+    pub fn from_point(point: G1Affine) -> Result<Self, ProverError> {
+        checked_nonzero(point, "signature").map(Signature)
+    }
+
+    pub fn point(&self) -> G1Affine {
+        self.0
+    }
+}
+
+/// Verifica con H(m) già mappato: e(σ, g2) · e(-H(m), pk) = 1.
+/// I punti devono essere già validati; chiave e firma all'infinito sono rifiutate.
+pub fn verify_hashed(public_key: &G2Affine, message_hash: &G1Affine, signature: &G1Affine) -> bool {
+    if public_key.is_zero() || signature.is_zero() {
+        return false;
+    }
+    Bn254::multi_pairing(
+        [*signature, -*message_hash],
+        [G2Affine::generator(), *public_key],
+    )
+    .is_zero()
+}
+
+/// Chiave aggregata Σ pk_i, per firme aggregate sullo stesso messaggio.
+/// Sicura solo con chiavi accompagnate da proof of possession (rogue key).
+pub fn aggregate_public_keys(public_keys: &[PublicKey]) -> Result<PublicKey, ProverError> {
+    if public_keys.is_empty() {
+        return Err(ProverError::InvalidInput(
+            "no public keys to aggregate".into(),
+        ));
+    }
+    let sum: G2Projective = public_keys.iter().map(|pk| pk.0.into_group()).sum();
+    PublicKey::from_point(sum.into_affine())
+}
+
+/// Firma aggregata Σ σ_i; si verifica con [`aggregate_public_keys`] dei firmatari
+pub fn aggregate_signatures(signatures: &[Signature]) -> Result<Signature, ProverError> {
+    if signatures.is_empty() {
+        return Err(ProverError::InvalidInput(
+            "no signatures to aggregate".into(),
+        ));
+    }
+    let sum: G1Projective = signatures.iter().map(|sig| sig.0.into_group()).sum();
+    Signature::from_point(sum.into_affine())
+}
+
+impl BLSProofInputs {
+    /// Input della prova per una firma su `message`, con il DST predefinito
+    pub fn from_signature(public_key: &PublicKey, message: &[u8], signature: &Signature) -> Self {
+        Self::from_signature_with_dst(public_key, message, BN254_G1_DST, signature)
+            .expect("default DST is valid")
+    }
+
+    /// Input della prova con H(m) calcolato come la firma: stesso messaggio e
+    /// stesso DST usati dal firmatario
+    pub fn from_signature_with_dst(
+        public_key: &PublicKey,
+        message: &[u8],
+        dst: &[u8],
+        signature: &Signature,
+    ) -> Result<Self, ProverError> {
+        Ok(BLSProofInputs {
+            public_inputs: BLSPublicInputs {
+                message_hash: hash_to_g1(message, dst)?.into(),
+                public_key: public_key.0.into(),
+            },
+            private_inputs: BLSPrivateInputs {
+                signature: signature.0.into(),
+            },
+        })
+    }
+
+    /// Verifica nativa della firma, da fare prima del proving: una firma non
+    /// valida produce un witness che non soddisfa il circuito
+    pub fn check_signature(&self) -> Result<(), ProverError> {
+        let message_hash = self.public_inputs.message_hash.to_affine("messageHash")?;
+        let public_key = self.public_inputs.public_key.to_affine("publicKey")?;
+        let signature = self.private_inputs.signature.to_affine("signature")?;
+        if verify_hashed(&public_key, &message_hash, &signature) {
+            Ok(())
+        } else {
+            Err(ProverError::InvalidSignature)
+        }
+    }
+}
+
+/// Punto sulla curva, nel sottogruppo di ordine r e diverso dall'infinito
+fn checked_nonzero<C: SWCurveConfig>(
+    point: Affine<C>,
+    field: &str,
+) -> Result<Affine<C>, ProverError> {
+    let invalid = |reason: &str| ProverError::InvalidPoint {
+        field: field.to_string(),
+        reason: reason.to_string(),
+    };
+    if point.is_zero() {
+        return Err(invalid("point at infinity"));
+    }
+    if !point.is_on_curve() {
+        return Err(invalid("not on the BN254 curve"));
+    }
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(invalid("not in the prime-order subgroup"));
+    }
+    Ok(point)
+}
+
+/// HKDF_mod_r: salt = SHA256(salt), OKM = HKDF-SHA256(salt, seed || 0, L)
+/// con L = ⌈3·⌈log2 r⌉ / 16⌉, ripetendo finché lo scalare è nullo
+fn hkdf_mod_r<F: PrimeField>(seed: &[u8]) -> F {
+    let okm_len = (3 * F::MODULUS_BIT_SIZE as usize).div_ceil(16);
+    let mut salt: [u8; 32] = Sha256::digest(KEYGEN_SALT).into();
+    loop {
+        // HKDF-Extract(salt, IKM || I2OSP(0, 1))
+        let mut extract = HmacSha256::new_from_slice(&salt).expect("HMAC takes keys of any size");
+        extract.update(seed);
+        extract.update(&[0]);
+        let prk = extract.finalize().into_bytes();
+
+        // HKDF-Expand(PRK, I2OSP(L, 2), L)
+        let mut okm = Vec::with_capacity(okm_len + 32);
+        let mut block = Vec::new();
+        for i in 1..=okm_len.div_ceil(32) as u8 {
+            let mut expand = HmacSha256::new_from_slice(&prk).expect("HMAC takes keys of any size");
+            expand.update(&block);
+            expand.update(&(okm_len as u16).to_be_bytes());
+            expand.update(&[i]);
+            block = expand.finalize().into_bytes().to_vec();
+            okm.extend_from_slice(&block);
+        }
+
+        let sk = F::from_be_bytes_mod_order(&okm[..okm_len]);
+        if !sk.is_zero() {
+            return sk;
+        }
+        salt = Sha256::digest(salt).into();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_sign_and_verify() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let sk = SecretKey::generate(&mut rng);
+        let pk = sk.public_key();
+        let signature = sk.sign(b"batch 42");
+
+        assert!(pk.verify(b"batch 42", &signature));
+        assert!(!pk.verify(b"batch 43", &signature));
+        let other = SecretKey::generate(&mut rng).public_key();
+        assert!(!other.verify(b"batch 42", &signature));
+
+        // Il DST fa parte di ciò che viene firmato
+        let dst = b"ROLLUP-V1_BN254G1_XMD:SHA-256_SVDW_RO_";
+        let tagged = sk.sign_with_dst(b"batch 42", dst).unwrap();
+        assert!(pk.verify_with_dst(b"batch 42", dst, &tagged).unwrap());
+        assert!(!pk.verify(b"batch 42", &tagged));
+        assert!(PublicKey::from_point(G2Affine::zero()).is_err());
+    }
+
+    #[test]
+    fn test_proof_of_possession() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let sk = SecretKey::generate(&mut rng);
+        let pk = sk.public_key();
+        let pop = sk.pop_prove();
+        assert!(pk.pop_verify(&pop));
+
+        // La PoP di un'altra chiave non vale
+        let other = SecretKey::generate(&mut rng);
+        assert!(!other.public_key().pop_verify(&pop));
+        assert!(!pk.pop_verify(&other.pop_prove()));
+
+        // DST separati: una firma sui byte della chiave non è una PoP, e viceversa
+        let signature = sk.sign(&pk.to_bytes());
+        assert!(!pk.pop_verify(&signature));
+        assert!(!pk.verify(&pk.to_bytes(), &pop));
+
+        // Rogue key pk' = -pk: senza sk non c'è PoP, nemmeno negando quella di pk
+        let rogue = PublicKey::from_point(-pk.point()).unwrap();
+        assert!(!rogue.pop_verify(&pop));
+        assert!(!rogue.pop_verify(&Signature::from_point(-pop.point()).unwrap()));
+    }
+
+    #[test]
+    fn test_key_derivation_from_seed() {
+        // Vettore 0 di EIP-2333: stesso HKDF_mod_r, con l'ordine r di BLS12-381
+        let seed = hex::decode("c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04").unwrap();
+        let master: ark_bls12_381::Fr = hkdf_mod_r(&seed);
+        assert_eq!(
+            master.to_string(),
+            "6083874454709270928345386274498605044986640685124978867557563392430687146096"
+        );
+
+        let sk = SecretKey::from_seed(&seed).unwrap();
+        assert_eq!(sk, SecretKey::from_seed(&seed).unwrap());
+        assert_ne!(sk, SecretKey::from_seed(&seed[1..]).unwrap());
+        assert_eq!(SecretKey::from_bytes(&sk.to_bytes()).unwrap(), sk);
+
+        assert!(matches!(
+            SecretKey::from_seed(&[0u8; 31]),
+            Err(ProverError::InvalidInput(_))
+        ));
+        assert!(SecretKey::from_bytes(&[0u8; 32]).is_err());
+        assert!(SecretKey::from_bytes(&[0xff; 32]).is_err());
+    }
+
+    #[test]
+    fn test_proof_inputs_from_signature() {
+        let sk = SecretKey::from_seed(&[1u8; 32]).unwrap();
+        let pk = sk.public_key();
+        let signature = sk.sign(b"hello");
+
+        let inputs = BLSProofInputs::from_signature(&pk, b"hello", &signature);
+        inputs.check_signature().unwrap();
+        assert_eq!(
+            inputs.public_inputs.message_hash,
+            hash_to_g1(b"hello", BN254_G1_DST).unwrap().into()
+        );
+        assert_eq!(inputs.circuit_inputs().unwrap().len(), 3);
+
+        let wrong = BLSProofInputs::from_signature(&pk, b"goodbye", &signature);
+        assert!(matches!(
+            wrong.check_signature(),
+            Err(ProverError::InvalidSignature)
+        ));
+    }
+}
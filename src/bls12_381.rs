@@ -0,0 +1,338 @@
+// prover/src/bls12_381.rs
+// Modalità BLS12-381: firme di Ethereum consensus
+//
+// Lo schema è quello delle specifiche consensus: chiave pubblica in G1
+// (48 byte compressi), firma e H(m) in G2 (96 byte compressi), formato di
+// serializzazione ZCash. Il circuito bls12_381_verify.circom riceve ogni
+// coordinata di Fq (381 bit) in tre parole da 128 bit little endian.
+
+use crate::{
+    field_from_biguint, field_to_decimal, fq_words, hash_to_bls12_g2, parse_decimal, ProverError,
+};
+use ark_bls12_381::{Bls12_381, Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::pairing::Pairing;
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ec::AffineRepr;
+use ark_ff::Zero;
+use serde::{Deserialize, Serialize};
+
+/// Chiave pubblica compressa (G1)
+const PUBLIC_KEY_BYTES: usize = 48;
+/// Firma compressa (G2)
+const SIGNATURE_BYTES: usize = 96;
+
+/// Punto di G1 di BLS12-381 in coordinate affini, coordinate decimali in Fq
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bls12G1PointInput {
+    pub x: String,
+    pub y: String,
+}
+
+/// Punto di G2 di BLS12-381 sul twist, coordinate in Fq2 come [c0, c1]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bls12G2PointInput {
+    pub x: [String; 2],
+    pub y: [String; 2],
+}
+
+impl Bls12G1PointInput {
+    /// Punto validato: sulla curva e nel sottogruppo di ordine r
+    pub fn to_affine(&self, field: &str) -> Result<G1Affine, ProverError> {
+        let point = G1Affine::new_unchecked(parse_fq(&self.x, field)?, parse_fq(&self.y, field)?);
+        checked(point, "G1", field)
+    }
+}
+
+impl Bls12G2PointInput {
+    /// Punto validato: sul twist e nel sottogruppo di ordine r
+    pub fn to_affine(&self, field: &str) -> Result<G2Affine, ProverError> {
+        let x = Fq2::new(parse_fq(&self.x[0], field)?, parse_fq(&self.x[1], field)?);
+        let y = Fq2::new(parse_fq(&self.y[0], field)?, parse_fq(&self.y[1], field)?);
+        checked(G2Affine::new_unchecked(x, y), "G2", field)
+    }
+}
+
+impl From<G1Affine> for Bls12G1PointInput {
+    fn from(point: G1Affine) -> Self {
+        Bls12G1PointInput {
+            x: field_to_decimal(&point.x),
+            y: field_to_decimal(&point.y),
+        }
+    }
+}
+
+impl From<G2Affine> for Bls12G2PointInput {
+    fn from(point: G2Affine) -> Self {
+        Bls12G2PointInput {
+            x: [field_to_decimal(&point.x.c0), field_to_decimal(&point.x.c1)],
+            y: [field_to_decimal(&point.y.c0), field_to_decimal(&point.y.c1)],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bls12PublicInputs {
+    pub public_key: Bls12G1PointInput,
+    /// H(m) già mappato su G2
+    pub message_hash: Bls12G2PointInput,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bls12PrivateInputs {
+    pub signature: Bls12G2PointInput,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bls12ProofInputs {
+    pub public_inputs: Bls12PublicInputs,
+    pub private_inputs: Bls12PrivateInputs,
+}
+
+impl Bls12ProofInputs {
+    /// Input da chiave pubblica (48 byte) e firma (96 byte) compresse, come
+    /// nei messaggi del beacon chain, e da H(m) già mappato su G2
+    pub fn from_compressed(
+        public_key: &[u8],
+        signature: &[u8],
+        message_hash: G2Affine,
+    ) -> Result<Self, ProverError> {
+        Ok(Bls12ProofInputs {
+            public_inputs: Bls12PublicInputs {
+                public_key: decompress_bls12_public_key(public_key)?.into(),
+                message_hash: message_hash.into(),
+            },
+            private_inputs: Bls12PrivateInputs {
+                signature: decompress_bls12_signature(signature)?.into(),
+            },
+        })
+    }
+
+    /// Input dal messaggio firmato invece che da H(m): H(m) è l'hash-to-curve
+    /// con il DST dato ([`crate::BLS12_381_G2_DST`] per le firme consensus)
+    pub fn from_message(
+        public_key: &[u8],
+        signature: &[u8],
+        message: &[u8],
+        dst: &[u8],
+    ) -> Result<Self, ProverError> {
+        Self::from_compressed(public_key, signature, hash_to_bls12_g2(message, dst)?)
+    }
+
+    /// Segnali di input di bls12_381_verify.circom (nome circom, valori decimali
+    /// appiattiti). Tutti e tre i punti vengono validati, sottogruppo compreso.
+    pub fn circuit_inputs(&self) -> Result<Vec<(&'static str, Vec<String>)>, ProverError> {
+        let public_key = self.public_inputs.public_key.to_affine("publicKey")?;
+        let message_hash = self.public_inputs.message_hash.to_affine("messageHash")?;
+        let signature = self.private_inputs.signature.to_affine("signature")?;
+        Ok(vec![
+            ("publicKey", g1_words(&public_key)),
+            ("messageHash", g2_words(&message_hash)),
+            ("signature", g2_words(&signature)),
+        ])
+    }
+
+    /// Segnali pubblici attesi nella prova (come public.json di snarkjs)
+    pub fn public_signals(&self) -> Result<Vec<String>, ProverError> {
+        let public_key = self.public_inputs.public_key.to_affine("publicKey")?;
+        let message_hash = self.public_inputs.message_hash.to_affine("messageHash")?;
+        Ok([g1_words(&public_key), g2_words(&message_hash)].concat())
+    }
+
+    /// Verifica nativa e(pk, H(m)) · e(-g1, σ) = 1, da fare prima del proving
+    pub fn check_signature(&self) -> Result<(), ProverError> {
+        let public_key = self.public_inputs.public_key.to_affine("publicKey")?;
+        let message_hash = self.public_inputs.message_hash.to_affine("messageHash")?;
+        let signature = self.private_inputs.signature.to_affine("signature")?;
+        let valid = Bls12_381::multi_pairing(
+            [public_key, -G1Affine::generator()],
+            [message_hash, signature],
+        )
+        .is_zero();
+        if valid {
+            Ok(())
+        } else {
+            Err(ProverError::InvalidSignature)
+        }
+    }
+}
+
+/// Chiave pubblica compressa (48 byte, formato ZCash) → punto di G1.
+/// Rifiuta punti fuori dalla curva, fuori dal sottogruppo e il punto all'infinito.
+pub fn decompress_bls12_public_key(bytes: &[u8]) -> Result<G1Affine, ProverError> {
+    decompress(bytes, PUBLIC_KEY_BYTES, "publicKey")
+}
+
+/// Firma compressa (96 byte, formato ZCash) → punto di G2, con gli stessi controlli
+pub fn decompress_bls12_signature(bytes: &[u8]) -> Result<G2Affine, ProverError> {
+    decompress(bytes, SIGNATURE_BYTES, "signature")
+}
+
+fn decompress<P: AffineRepr>(bytes: &[u8], len: usize, field: &str) -> Result<P, ProverError> {
+    let invalid = |reason: String| ProverError::InvalidPoint {
+        field: field.to_string(),
+        reason,
+    };
+    if bytes.len() != len {
+        return Err(invalid(format!(
+            "expected {} bytes, got {}",
+            len,
+            bytes.len()
+        )));
+    }
+    // deserialize_compressed controlla curva e sottogruppo
+    let point = P::deserialize_compressed(bytes).map_err(|e| invalid(e.to_string()))?;
+    if point.is_zero() {
+        return Err(invalid("point at infinity".to_string()));
+    }
+    Ok(point)
+}
+
+fn parse_fq(value: &str, field: &str) -> Result<Fq, ProverError> {
+    field_from_biguint(parse_decimal(value, field)?, field)
+}
+
+/// Costruisce un punto verificando che sia sulla curva e nel sottogruppo
+fn checked<C: SWCurveConfig>(
+    point: Affine<C>,
+    group: &str,
+    field: &str,
+) -> Result<Affine<C>, ProverError> {
+    if !point.is_on_curve() {
+        return Err(ProverError::InvalidPoint {
+            field: field.to_string(),
+            reason: format!("not on the BLS12-381 {} curve", group),
+        });
+    }
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(ProverError::InvalidPoint {
+            field: field.to_string(),
+            reason: "not in the prime-order subgroup".to_string(),
+        });
+    }
+    Ok(point)
+}
+
+fn g1_words(point: &G1Affine) -> Vec<String> {
+    fq_words(&[point.x, point.y])
+}
+
+fn g2_words(point: &G2Affine) -> Vec<String> {
+    fq_words(&[point.x.c0, point.x.c1, point.y.c0, point.y.c1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Fr;
+    use ark_ec::CurveGroup;
+    use ark_serialize::CanonicalSerialize;
+    use num_bigint::BigUint;
+
+    const G1_GENERATOR: &str = "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb";
+    const G2_GENERATOR: &str = "93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8";
+
+    fn compressed<P: CanonicalSerialize>(point: &P) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        point.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_decompress_generators() {
+        let g1 = decompress_bls12_public_key(&hex::decode(G1_GENERATOR).unwrap()).unwrap();
+        assert_eq!(g1, G1Affine::generator());
+        let g2 = decompress_bls12_signature(&hex::decode(G2_GENERATOR).unwrap()).unwrap();
+        assert_eq!(g2, G2Affine::generator());
+    }
+
+    #[test]
+    fn test_decompress_rejects_invalid_encodings() {
+        // Lunghezza sbagliata
+        let err = decompress_bls12_public_key(&[0u8; 47]);
+        assert!(
+            matches!(err, Err(ProverError::InvalidPoint { reason, .. }) if reason.contains("48"))
+        );
+
+        // Punto all'infinito (flag di compressione e infinito)
+        let mut infinity = [0u8; 48];
+        infinity[0] = 0xc0;
+        let err = decompress_bls12_public_key(&infinity);
+        assert!(
+            matches!(err, Err(ProverError::InvalidPoint { reason, .. }) if reason.contains("infinity"))
+        );
+
+        // Senza flag di compressione
+        let mut bytes = hex::decode(G2_GENERATOR).unwrap();
+        bytes[0] &= 0x7f;
+        assert!(decompress_bls12_signature(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_bls12_inputs_from_message() {
+        let sk = Fr::from(42u64);
+        let message = b"beacon block root";
+        let message_hash = hash_to_bls12_g2(message, crate::BLS12_381_G2_DST).unwrap();
+        let public_key = (G1Affine::generator() * sk).into_affine();
+        let signature = (message_hash * sk).into_affine();
+
+        let inputs = Bls12ProofInputs::from_message(
+            &compressed(&public_key),
+            &compressed(&signature),
+            message,
+            crate::BLS12_381_G2_DST,
+        )
+        .unwrap();
+        assert_eq!(inputs.public_inputs.message_hash, message_hash.into());
+        inputs.check_signature().unwrap();
+    }
+
+    #[test]
+    fn test_bls12_circuit_inputs() {
+        let sk = Fr::from(123456789u64);
+        let message_hash = (G2Affine::generator() * Fr::from(987654321u64)).into_affine();
+        let public_key = (G1Affine::generator() * sk).into_affine();
+        let signature = (message_hash * sk).into_affine();
+
+        let inputs = Bls12ProofInputs::from_compressed(
+            &compressed(&public_key),
+            &compressed(&signature),
+            message_hash,
+        )
+        .unwrap();
+        assert_eq!(inputs.public_inputs.public_key, public_key.into());
+        assert_eq!(inputs.private_inputs.signature, signature.into());
+        inputs.check_signature().unwrap();
+
+        // Ogni coordinata di Fq in tre parole da 128 bit: 2 + 4 + 4 coordinate
+        let signals = inputs.circuit_inputs().unwrap();
+        let names: Vec<_> = signals.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["publicKey", "messageHash", "signature"]);
+        assert_eq!(
+            signals.iter().map(|(_, v)| v.len()).collect::<Vec<_>>(),
+            [6, 12, 12]
+        );
+        let x: BigUint = public_key.x.into();
+        let words: Vec<BigUint> = signals[0].1[..3]
+            .iter()
+            .map(|w| w.parse().unwrap())
+            .collect();
+        assert_eq!(&words[0] + (&words[1] << 128) + (&words[2] << 256), x);
+
+        let public = inputs.public_signals().unwrap();
+        assert_eq!(
+            public,
+            [signals[0].1.clone(), signals[1].1.clone()].concat()
+        );
+
+        let forged = Bls12ProofInputs::from_compressed(
+            &compressed(&public_key),
+            &compressed(&message_hash),
+            message_hash,
+        )
+        .unwrap();
+        assert!(matches!(
+            forged.check_signature(),
+            Err(ProverError::InvalidSignature)
+        ));
+    }
+}
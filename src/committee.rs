@@ -0,0 +1,537 @@
+// prover/src/committee.rs
+// Firme BLS aggregate di un comitato: N chiavi firmano lo stesso messaggio
+//
+// Al posto di N prove bls_verify (e di 3N input pubblici on-chain) il
+// comitato produce una firma aggregata σ = Σ σ_i, verificata in
+// bls_aggregate_verify.circom con la chiave aggregata dei partecipanti
+// indicati dalla bitmap. Le chiavi sono witness: on-chain restano l'impegno
+// committeeRoot (catena di Poseidon sulle parole delle chiavi), H(m) e la
+// bitmap.
+//
+// Le chiavi entrano nel comitato solo con la loro proof of possession
+// (Committee::new la verifica): senza, una chiave costruita come pk' - Σ pk_i
+// permette di falsificare l'aggregato (rogue key). Di conseguenza i membri
+// firmano con il DST dello schema PoP, BN254_G1_POP_SCHEME_DST.
+
+use crate::poseidon::poseidon2;
+use crate::{
+    aggregate_public_keys, expand_message_xmd, field_from_biguint, field_to_decimal, g1_words,
+    g2_words, hash_to_g1, parse_decimal, verify_hashed, G1PointInput, G2PointInput, ProverError,
+    PublicKey, Signature, BN254_G1_POP_SCHEME_DST, CIRCUIT_WORD_BITS,
+};
+use ark_bn254::{Fq, Fq2, Fr, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{PrimeField, Zero};
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Seed del punto R di curve_g2_offset (partenza della somma delle chiavi)
+const OFFSET_SEED: &[u8] = b"bls-zk-prover/aggregate/offset";
+/// Seed del punto P di curve_g2_padding (operando dei non partecipanti)
+const PADDING_SEED: &[u8] = b"bls-zk-prover/aggregate/padding";
+/// DST del try-and-increment che deriva R e P
+const POINT_DST: &[u8] = b"BLS-ZK-PROVER-V01-BN254G2_XMD:SHA-256_TAI_";
+
+/// Chiavi del comitato nell'ordine della bitmap e dell'impegno
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Committee {
+    public_keys: Vec<PublicKey>,
+}
+
+impl Committee {
+    /// Comitato dalle chiavi dei membri, ciascuna con la sua proof of
+    /// possession ([`crate::SecretKey::pop_prove`]). Le chiavi devono essere
+    /// distinte anche a meno del segno.
+    pub fn new(members: Vec<(PublicKey, Signature)>) -> Result<Self, ProverError> {
+        if members.is_empty() {
+            return Err(ProverError::InvalidInput("empty committee".into()));
+        }
+        for (i, (pk, pop)) in members.iter().enumerate() {
+            if !pk.pop_verify(pop) {
+                return Err(ProverError::InvalidInput(format!(
+                    "invalid proof of possession for committee key {}",
+                    i
+                )));
+            }
+            if members[..i]
+                .iter()
+                .any(|(other, _)| other.point().x == pk.point().x)
+            {
+                return Err(ProverError::InvalidInput(format!(
+                    "committee key {} is repeated (or negated)",
+                    i
+                )));
+            }
+        }
+        Ok(Committee {
+            public_keys: members.into_iter().map(|(pk, _)| pk).collect(),
+        })
+    }
+
+    pub fn public_keys(&self) -> &[PublicKey] {
+        &self.public_keys
+    }
+
+    /// committeeRoot del circuito: root = Poseidon(root, parola) su tutte le
+    /// parole da 128 bit delle chiavi, partendo da zero
+    pub fn root(&self) -> Fr {
+        let points: Vec<G2Affine> = self.public_keys.iter().map(PublicKey::point).collect();
+        committee_root(&points)
+    }
+
+    /// Chiave aggregata dei partecipanti indicati dalla bitmap
+    pub fn aggregate_public_key(&self, bitmap: &[bool]) -> Result<PublicKey, ProverError> {
+        check_bitmap(bitmap, self.public_keys.len())?;
+        let signers: Vec<PublicKey> = self
+            .public_keys
+            .iter()
+            .zip(bitmap)
+            .filter(|(_, &signed)| signed)
+            .map(|(pk, _)| *pk)
+            .collect();
+        aggregate_public_keys(&signers)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitteePublicInputs {
+    /// Impegno sulle chiavi del comitato, decimale in Fr
+    pub committee_root: String,
+    /// H(m) già mappato su G1
+    pub message_hash: G1PointInput,
+    /// Partecipanti, un bit per chiave nell'ordine del comitato
+    pub bitmap: Vec<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitteePrivateInputs {
+    /// Tutte le chiavi del comitato, partecipanti e non
+    pub public_keys: Vec<G2PointInput>,
+    /// Firma aggregata dei partecipanti
+    pub signature: G1PointInput,
+}
+
+#[derive(Debug, Clone)]
+pub struct CommitteeProofInputs {
+    pub public_inputs: CommitteePublicInputs,
+    pub private_inputs: CommitteePrivateInputs,
+}
+
+impl CommitteeProofInputs {
+    /// Input della prova per la firma aggregata dei partecipanti su `message`,
+    /// con il DST dello schema PoP
+    pub fn from_signature(
+        committee: &Committee,
+        bitmap: Vec<bool>,
+        message: &[u8],
+        signature: &Signature,
+    ) -> Result<Self, ProverError> {
+        Self::from_signature_with_dst(
+            committee,
+            bitmap,
+            message,
+            BN254_G1_POP_SCHEME_DST,
+            signature,
+        )
+    }
+
+    pub fn from_signature_with_dst(
+        committee: &Committee,
+        bitmap: Vec<bool>,
+        message: &[u8],
+        dst: &[u8],
+        signature: &Signature,
+    ) -> Result<Self, ProverError> {
+        check_bitmap(&bitmap, committee.public_keys.len())?;
+        Ok(CommitteeProofInputs {
+            public_inputs: CommitteePublicInputs {
+                committee_root: field_to_decimal(&committee.root()),
+                message_hash: hash_to_g1(message, dst)?.into(),
+                bitmap,
+            },
+            private_inputs: CommitteePrivateInputs {
+                public_keys: committee
+                    .public_keys
+                    .iter()
+                    .map(|pk| pk.point().into())
+                    .collect(),
+                signature: signature.point().into(),
+            },
+        })
+    }
+
+    /// Segnali di input di bls_aggregate_verify.circom (nome circom, valori
+    /// decimali appiattiti). Le chiavi vengono validate, sottogruppo compreso,
+    /// e devono corrispondere a committeeRoot.
+    pub fn circuit_inputs(&self) -> Result<Vec<(&'static str, Vec<String>)>, ProverError> {
+        let (root, public_keys) = self.committee()?;
+        let message_hash = self.public_inputs.message_hash.to_affine("messageHash")?;
+        let signature = self.private_inputs.signature.to_affine("signature")?;
+        Ok(vec![
+            ("committeeRoot", vec![field_to_decimal(&root)]),
+            ("messageHash", g1_words(&message_hash)),
+            ("bitmap", bitmap_words(&self.public_inputs.bitmap)),
+            (
+                "publicKeys",
+                public_keys.iter().flat_map(g2_words).collect(),
+            ),
+            ("signature", g1_words(&signature)),
+        ])
+    }
+
+    /// Segnali pubblici attesi nella prova (come public.json di snarkjs)
+    pub fn public_signals(&self) -> Result<Vec<String>, ProverError> {
+        let root = parse_root(&self.public_inputs.committee_root)?;
+        let message_hash = self.public_inputs.message_hash.to_affine("messageHash")?;
+        Ok([
+            vec![field_to_decimal(&root)],
+            g1_words(&message_hash),
+            bitmap_words(&self.public_inputs.bitmap),
+        ]
+        .concat())
+    }
+
+    /// Verifica nativa della firma aggregata, da fare prima del proving:
+    /// oltre al pairing controlla che la somma delle chiavi sia calcolabile
+    /// dal circuito
+    pub fn check_signature(&self) -> Result<(), ProverError> {
+        let (_, public_keys) = self.committee()?;
+        let message_hash = self.public_inputs.message_hash.to_affine("messageHash")?;
+        let signature = self.private_inputs.signature.to_affine("signature")?;
+        let aggregate = circuit_key_sum(&public_keys, &self.public_inputs.bitmap)?;
+        if verify_hashed(&aggregate, &message_hash, &signature) {
+            Ok(())
+        } else {
+            Err(ProverError::InvalidSignature)
+        }
+    }
+
+    /// Chiavi validate, coerenti con committeeRoot e con la bitmap
+    fn committee(&self) -> Result<(Fr, Vec<G2Affine>), ProverError> {
+        let root = parse_root(&self.public_inputs.committee_root)?;
+        let public_keys = self
+            .private_inputs
+            .public_keys
+            .iter()
+            .map(|pk| pk.to_affine("publicKeys"))
+            .collect::<Result<Vec<_>, _>>()?;
+        check_bitmap(&self.public_inputs.bitmap, public_keys.len())?;
+        if committee_root(&public_keys) != root {
+            return Err(ProverError::InvalidInput(
+                "public keys do not match committeeRoot".into(),
+            ));
+        }
+        Ok((root, public_keys))
+    }
+}
+
+/// Messaggio che il comitato firma per un batch di ZKRollupBLS, come
+/// committeeMessage del contratto: abi.encode(chainid, contratto, batchId,
+/// stateRoot). Lega la firma a rollup, posizione e stato del batch.
+pub fn committee_batch_message(
+    chain_id: u64,
+    contract: [u8; 20],
+    batch_id: u64,
+    state_root: [u8; 32],
+) -> Vec<u8> {
+    let mut message = vec![0u8; 4 * 32];
+    message[24..32].copy_from_slice(&chain_id.to_be_bytes());
+    message[44..64].copy_from_slice(&contract);
+    message[88..96].copy_from_slice(&batch_id.to_be_bytes());
+    message[96..].copy_from_slice(&state_root);
+    message
+}
+
+fn committee_root(public_keys: &[G2Affine]) -> Fr {
+    public_keys
+        .iter()
+        .flat_map(g2_words)
+        .map(|word| Fr::from_str(&word).expect("128-bit word fits in Fr"))
+        .fold(Fr::zero(), poseidon2)
+}
+
+fn parse_root(value: &str) -> Result<Fr, ProverError> {
+    field_from_biguint(parse_decimal(value, "committeeRoot")?, "committeeRoot")
+}
+
+fn check_bitmap(bitmap: &[bool], committee_size: usize) -> Result<(), ProverError> {
+    if bitmap.len() != committee_size {
+        return Err(ProverError::InvalidInput(format!(
+            "bitmap has {} bits for a committee of {}",
+            bitmap.len(),
+            committee_size
+        )));
+    }
+    if !bitmap.contains(&true) {
+        return Err(ProverError::InvalidInput("empty participant bitmap".into()));
+    }
+    Ok(())
+}
+
+/// Bitmap → parole da 128 bit: il partecipante i è il bit i % 128 della
+/// parola i / 128
+fn bitmap_words(bitmap: &[bool]) -> Vec<String> {
+    bitmap
+        .chunks(CIRCUIT_WORD_BITS)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .rev()
+                .fold(BigUint::default(), |acc, &bit| {
+                    (acc << 1u8) + BigUint::from(bit as u8)
+                })
+                .to_string()
+        })
+        .collect()
+}
+
+/// Punto di G2 derivato da `seed` con try-and-increment: ascissa in Fq2 da
+/// expand_message_xmd(seed || ctr), y più piccola, poi moltiplicazione per il
+/// cofattore del twist. Nessuno conosce il logaritmo discreto del risultato.
+fn hash_to_g2_tai(seed: &[u8]) -> G2Affine {
+    (0u32..)
+        .find_map(|ctr| {
+            let bytes = expand_message_xmd(&[seed, &ctr.to_be_bytes()].concat(), POINT_DST, 96)
+                .expect("fixed DST is valid");
+            let x = Fq2::new(
+                Fq::from_be_bytes_mod_order(&bytes[..48]),
+                Fq::from_be_bytes_mod_order(&bytes[48..]),
+            );
+            let point = G2Affine::get_point_from_x_unchecked(x, false)?.clear_cofactor();
+            (!point.is_zero()).then_some(point)
+        })
+        .expect("try-and-increment finds a point")
+}
+
+/// Punto R di curve_g2_offset
+fn offset_point() -> G2Affine {
+    hash_to_g2_tai(OFFSET_SEED)
+}
+
+/// Punto P di curve_g2_padding
+fn padding_point() -> G2Affine {
+    hash_to_g2_tai(PADDING_SEED)
+}
+
+/// Chiave aggregata calcolata come nel circuito: da R si somma a ogni passo
+/// pk_i se il membro ha firmato e P altrimenti (l'operando è scelto prima di
+/// G2Add), tenendo la somma solo per i firmatari; alla fine si sottrae R.
+/// G2Add richiede ascisse distinte: se un passo le ha uguali il witness non
+/// esiste e la prova non si può generare. Con R e P di logaritmo discreto
+/// ignoto e chiavi con PoP nessun membro può provocarlo.
+fn circuit_key_sum(public_keys: &[G2Affine], bitmap: &[bool]) -> Result<G2Affine, ProverError> {
+    let offset = offset_point();
+    let padding = padding_point();
+    let mut acc = offset;
+    for (i, (pk, &signed)) in public_keys.iter().zip(bitmap).enumerate() {
+        let operand = if signed { *pk } else { padding };
+        if acc.x == operand.x {
+            return Err(ProverError::InvalidInput(format!(
+                "committee key {} collides with the partial key sum",
+                i
+            )));
+        }
+        if signed {
+            acc = (acc + operand).into_affine();
+        }
+    }
+    if acc.x == offset.x {
+        return Err(ProverError::InvalidInput(
+            "aggregate public key is degenerate".into(),
+        ));
+    }
+    Ok((acc.into_group() - offset).into_affine())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{aggregate_signatures, SecretKey};
+    use ark_ff::BigInteger;
+
+    fn committee(size: u8) -> (Vec<SecretKey>, Committee) {
+        let keys: Vec<SecretKey> = (1..=size)
+            .map(|i| SecretKey::from_seed(&[i; 32]).unwrap())
+            .collect();
+        let committee = Committee::new(
+            keys.iter()
+                .map(|sk| (sk.public_key(), sk.pop_prove()))
+                .collect(),
+        )
+        .unwrap();
+        (keys, committee)
+    }
+
+    fn sign(sk: &SecretKey, message: &[u8]) -> Signature {
+        sk.sign_with_dst(message, BN254_G1_POP_SCHEME_DST).unwrap()
+    }
+
+    #[test]
+    fn test_committee_signature() {
+        let (keys, committee) = committee(5);
+        let bitmap = vec![true, false, true, true, false];
+        let signatures: Vec<Signature> = keys
+            .iter()
+            .zip(&bitmap)
+            .filter(|(_, &signed)| signed)
+            .map(|(sk, _)| sign(sk, b"block 7"))
+            .collect();
+        let signature = aggregate_signatures(&signatures).unwrap();
+
+        let aggregate = committee.aggregate_public_key(&bitmap).unwrap();
+        assert!(aggregate
+            .verify_with_dst(b"block 7", BN254_G1_POP_SCHEME_DST, &signature)
+            .unwrap());
+
+        let inputs = CommitteeProofInputs::from_signature(
+            &committee,
+            bitmap.clone(),
+            b"block 7",
+            &signature,
+        )
+        .unwrap();
+        inputs.check_signature().unwrap();
+
+        let circuit_inputs = inputs.circuit_inputs().unwrap();
+        assert_eq!(circuit_inputs[2], ("bitmap", vec!["13".to_string()]));
+        assert_eq!(circuit_inputs[3].1.len(), 5 * 8);
+        // Input pubblici: root, H(m) e una parola di bitmap, non 3 per firma
+        assert_eq!(inputs.public_signals().unwrap().len(), 1 + 4 + 1);
+
+        // La bitmap è pubblica: dichiarare un partecipante in più invalida la prova
+        let mut claimed = inputs.clone();
+        claimed.public_inputs.bitmap[1] = true;
+        assert!(matches!(
+            claimed.check_signature(),
+            Err(ProverError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_committee_batch_message() {
+        let message = committee_batch_message(31337, [0xaa; 20], 7, [0x11; 32]);
+        assert_eq!(
+            hex::encode(&message),
+            format!(
+                "{:064x}{}{}{:064x}{}",
+                31337,
+                "0".repeat(24),
+                "aa".repeat(20),
+                7,
+                "11".repeat(32)
+            )
+        );
+
+        // Batch diversi, messaggi e H(m) diversi: la firma non si riusa
+        let other = committee_batch_message(31337, [0xaa; 20], 8, [0x11; 32]);
+        assert_ne!(
+            hash_to_g1(&message, BN254_G1_POP_SCHEME_DST).unwrap(),
+            hash_to_g1(&other, BN254_G1_POP_SCHEME_DST).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_committee_inputs_validation() {
+        let (keys, committee) = committee(3);
+        let signature = sign(&keys[0], b"m");
+
+        assert!(
+            CommitteeProofInputs::from_signature(&committee, vec![true], b"m", &signature).is_err()
+        );
+        assert!(
+            CommitteeProofInputs::from_signature(&committee, vec![false; 3], b"m", &signature)
+                .is_err()
+        );
+
+        // Chiavi diverse da quelle impegnate in committeeRoot
+        let mut inputs = CommitteeProofInputs::from_signature(
+            &committee,
+            vec![true, false, false],
+            b"m",
+            &signature,
+        )
+        .unwrap();
+        inputs.check_signature().unwrap();
+        inputs.private_inputs.public_keys.swap(0, 1);
+        assert!(matches!(
+            inputs.circuit_inputs(),
+            Err(ProverError::InvalidInput(_))
+        ));
+
+        // Somma che passa per -pk: G2Add non ha witness
+        assert!(circuit_key_sum(&[-offset_point()], &[true]).is_err());
+    }
+
+    #[test]
+    fn test_committee_requires_proof_of_possession() {
+        let (keys, _) = committee(3);
+        let mut members: Vec<(PublicKey, Signature)> = keys
+            .iter()
+            .map(|sk| (sk.public_key(), sk.pop_prove()))
+            .collect();
+
+        // Rogue key: -pk_0 + pk' annulla pk_0 nell'aggregato, ma nessuno ne ha la PoP
+        let rogue_sk = SecretKey::from_seed(&[9; 32]).unwrap();
+        let rogue = (rogue_sk.public_key().point().into_group() - keys[0].public_key().point())
+            .into_affine();
+        members[2] = (PublicKey::from_point(rogue).unwrap(), rogue_sk.pop_prove());
+        let err = Committee::new(members.clone()).unwrap_err();
+        assert!(err.to_string().contains("committee key 2"));
+
+        // PoP scambiate tra chiavi
+        members[2] = (keys[2].public_key(), keys[1].pop_prove());
+        assert!(Committee::new(members.clone()).is_err());
+
+        // Una firma ordinaria non è una PoP
+        let pk = keys[2].public_key();
+        members[2] = (pk, sign(&keys[2], &pk.to_bytes()));
+        assert!(Committee::new(members).is_err());
+    }
+
+    #[test]
+    fn test_offset_matches_circuit() {
+        // g[0][0] di curve_g2_offset(254) e curve_g2_padding(254), limb da 64 bit ricomposti
+        assert_eq!(
+            offset_point().x.c0.to_string(),
+            "21223492816553056665080633248732644611587363263488065189190351665905125208881"
+        );
+        assert_eq!(
+            padding_point().x.c0.to_string(),
+            "8337180872637630358445522198217684898798342328606822056750744395253071086583"
+        );
+        for point in [offset_point(), padding_point()] {
+            assert!(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve());
+        }
+    }
+
+    #[test]
+    fn test_key_sum_skips_non_signers() {
+        let (keys, committee) = committee(3);
+        let points: Vec<G2Affine> = committee
+            .public_keys()
+            .iter()
+            .map(PublicKey::point)
+            .collect();
+
+        // Una chiave che non firma non entra in G2Add, qualunque sia
+        let mut with_offset = points.clone();
+        with_offset[0] = offset_point();
+        let sum = circuit_key_sum(&with_offset, &[false, true, true]).unwrap();
+        assert_eq!(sum, (points[1] + points[2]).into_affine());
+        with_offset[0] = -offset_point();
+        assert!(circuit_key_sum(&with_offset, &[false, true, true]).is_ok());
+
+        // Chiavi ripetute o negate rifiutate alla creazione del comitato
+        let members = |pks: [&SecretKey; 2]| -> Vec<(PublicKey, Signature)> {
+            pks.iter()
+                .map(|sk| (sk.public_key(), sk.pop_prove()))
+                .collect()
+        };
+        assert!(Committee::new(members([&keys[0], &keys[0]])).is_err());
+        let sk = Fr::from_be_bytes_mod_order(&keys[0].to_bytes());
+        let negated = SecretKey::from_bytes(&(-sk).into_bigint().to_bytes_be()).unwrap();
+        assert!(Committee::new(members([&keys[0], &negated])).is_err());
+        assert!(Committee::new(members([&keys[0], &keys[1]])).is_ok());
+    }
+}
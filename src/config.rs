@@ -0,0 +1,456 @@
+// prover/src/config.rs
+// Configurazione di SnarkjsProver / NativeProver
+//
+// Nomi degli artifact, eseguibili e timeout non sono più fissi: si
+// costruiscono con i metodi `with_*`, si caricano da file TOML/JSON e si
+// sovrascrivono con variabili d'ambiente BLS_PROVER_*.
+
+use crate::ProverError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Prefisso delle variabili d'ambiente lette da [`ProverConfig::with_env_overrides`]
+pub const ENV_PREFIX: &str = "BLS_PROVER_";
+
+/// N del main di bls_aggregate_verify.circom
+pub const DEFAULT_COMMITTEE_SIZE: usize = 16;
+
+/// Backend usato da [`crate::BLSProver::from_config`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    /// node + snarkjs come processi esterni
+    #[default]
+    Snarkjs,
+    /// ark-circom + arkworks in-process
+    Native,
+}
+
+impl FromStr for BackendKind {
+    type Err = ProverError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "snarkjs" => Ok(BackendKind::Snarkjs),
+            "native" => Ok(BackendKind::Native),
+            _ => Err(ProverError::Config(format!(
+                "unknown backend {:?} (expected snarkjs or native)",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProverConfig {
+    /// Backend di proving
+    pub backend: BackendKind,
+    /// Directory del circuito (contiene build/)
+    pub circuit_dir: String,
+    /// Nome del circuito, usato per derivare i nomi degli artifact
+    pub circuit_name: String,
+    /// Default: {circuit_dir}/build
+    pub build_dir: Option<String>,
+    /// Default: {build_dir}/{circuit_name}_js/{circuit_name}.wasm
+    pub wasm_path: Option<String>,
+    /// Default: {build_dir}/{circuit_name}_js/generate_witness.js
+    pub witness_generator_path: Option<String>,
+    /// Default: {build_dir}/{circuit_name}.r1cs
+    pub r1cs_path: Option<String>,
+    /// Default: {build_dir}/{circuit_name}_final.zkey
+    pub zkey_path: Option<String>,
+    /// Default: {build_dir}/verification_key.json
+    pub vk_path: Option<String>,
+    /// Comando per node, es. ["node"] o ["/opt/node18/bin/node"]
+    pub node: Vec<String>,
+    /// Comando per snarkjs, es. ["snarkjs"] o ["npx", "snarkjs"]
+    pub snarkjs: Vec<String>,
+    /// Root dei workspace temporanei (default: std::env::temp_dir())
+    pub temp_dir: Option<PathBuf>,
+    /// Timeout della generazione del witness, in millisecondi
+    pub witness_timeout_ms: Option<u64>,
+    /// Timeout di `snarkjs groth16 prove`, in millisecondi
+    pub prove_timeout_ms: Option<u64>,
+    /// Prove concorrenti in BatchProver (default: numero di core)
+    pub max_parallelism: Option<usize>,
+    /// SRS per l'aggregazione delle prove in BatchProver (vedi AggregationSrs::save,
+    /// `bls-prover aggregation-setup` la deriva da due file .ptau).
+    /// Se assente i batch non producono la prova aggregata.
+    pub aggregation_srs_path: Option<String>,
+    /// Chiavi N con cui è compilato bls_aggregate_verify (default
+    /// [`DEFAULT_COMMITTEE_SIZE`]): le prove di comitati di altra dimensione
+    /// vengono rifiutate prima del proving
+    pub committee_size: Option<usize>,
+}
+
+impl Default for ProverConfig {
+    fn default() -> Self {
+        ProverConfig {
+            backend: BackendKind::default(),
+            circuit_dir: "../circuits".to_string(),
+            circuit_name: "bls_verify".to_string(),
+            build_dir: None,
+            wasm_path: None,
+            witness_generator_path: None,
+            r1cs_path: None,
+            zkey_path: None,
+            vk_path: None,
+            node: vec!["node".to_string()],
+            snarkjs: vec!["snarkjs".to_string()],
+            temp_dir: None,
+            witness_timeout_ms: None,
+            prove_timeout_ms: None,
+            max_parallelism: None,
+            aggregation_srs_path: None,
+            committee_size: None,
+        }
+    }
+}
+
+impl ProverConfig {
+    pub fn new(circuit_dir: &str) -> Self {
+        ProverConfig {
+            circuit_dir: circuit_dir.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Carica da file `.toml` o `.json` (in base all'estensione)
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ProverError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => {
+                toml::from_str(&contents).map_err(|e| ProverError::Config(e.to_string()))
+            }
+            Some("json") => Ok(serde_json::from_str(&contents)?),
+            _ => Err(ProverError::Config(format!(
+                "unsupported config file {}: expected .toml or .json",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Applica le variabili d'ambiente BLS_PROVER_* (vedi [`Self::with_overrides`])
+    pub fn with_env_overrides(self) -> Result<Self, ProverError> {
+        self.with_overrides(|key| std::env::var(format!("{}{}", ENV_PREFIX, key)).ok())
+    }
+
+    /// Applica gli override restituiti da `lookup` per le chiavi BACKEND, CIRCUIT_DIR,
+    /// CIRCUIT_NAME, BUILD_DIR, WASM, WITNESS_GENERATOR, R1CS, ZKEY, VK, NODE,
+    /// SNARKJS, TEMP_DIR, WITNESS_TIMEOUT_MS, PROVE_TIMEOUT_MS, MAX_PARALLELISM,
+    /// AGGREGATION_SRS, COMMITTEE_SIZE.
+    /// NODE e SNARKJS sono divisi sugli spazi ("npx snarkjs").
+    pub fn with_overrides(
+        mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ProverError> {
+        if let Some(v) = lookup("BACKEND") {
+            self.backend = v.parse()?;
+        }
+        if let Some(v) = lookup("CIRCUIT_DIR") {
+            self.circuit_dir = v;
+        }
+        if let Some(v) = lookup("CIRCUIT_NAME") {
+            self.circuit_name = v;
+        }
+        for (key, field) in [
+            ("BUILD_DIR", &mut self.build_dir),
+            ("WASM", &mut self.wasm_path),
+            ("WITNESS_GENERATOR", &mut self.witness_generator_path),
+            ("R1CS", &mut self.r1cs_path),
+            ("ZKEY", &mut self.zkey_path),
+            ("VK", &mut self.vk_path),
+            ("AGGREGATION_SRS", &mut self.aggregation_srs_path),
+        ] {
+            if let Some(v) = lookup(key) {
+                *field = Some(v);
+            }
+        }
+        if let Some(v) = lookup("NODE") {
+            self.node = v.split_whitespace().map(str::to_string).collect();
+        }
+        if let Some(v) = lookup("SNARKJS") {
+            self.snarkjs = v.split_whitespace().map(str::to_string).collect();
+        }
+        if let Some(v) = lookup("TEMP_DIR") {
+            self.temp_dir = Some(PathBuf::from(v));
+        }
+        for (key, field) in [
+            ("WITNESS_TIMEOUT_MS", &mut self.witness_timeout_ms),
+            ("PROVE_TIMEOUT_MS", &mut self.prove_timeout_ms),
+        ] {
+            if let Some(v) = lookup(key) {
+                let ms = v.trim().parse().map_err(|_| {
+                    ProverError::Config(format!(
+                        "{}{}: invalid number of milliseconds {:?}",
+                        ENV_PREFIX, key, v
+                    ))
+                })?;
+                *field = Some(ms);
+            }
+        }
+        for (key, field) in [
+            ("MAX_PARALLELISM", &mut self.max_parallelism),
+            ("COMMITTEE_SIZE", &mut self.committee_size),
+        ] {
+            if let Some(v) = lookup(key) {
+                let n = v.trim().parse().map_err(|_| {
+                    ProverError::Config(format!("{}{}: invalid number {:?}", ENV_PREFIX, key, v))
+                })?;
+                *field = Some(n);
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn with_backend(mut self, backend: BackendKind) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    pub fn with_circuit_name(mut self, name: &str) -> Self {
+        self.circuit_name = name.to_string();
+        self
+    }
+
+    pub fn with_build_dir(mut self, path: &str) -> Self {
+        self.build_dir = Some(path.to_string());
+        self
+    }
+
+    pub fn with_wasm_path(mut self, path: &str) -> Self {
+        self.wasm_path = Some(path.to_string());
+        self
+    }
+
+    pub fn with_witness_generator_path(mut self, path: &str) -> Self {
+        self.witness_generator_path = Some(path.to_string());
+        self
+    }
+
+    pub fn with_r1cs_path(mut self, path: &str) -> Self {
+        self.r1cs_path = Some(path.to_string());
+        self
+    }
+
+    pub fn with_zkey_path(mut self, path: &str) -> Self {
+        self.zkey_path = Some(path.to_string());
+        self
+    }
+
+    pub fn with_vk_path(mut self, path: &str) -> Self {
+        self.vk_path = Some(path.to_string());
+        self
+    }
+
+    /// Programma e argomenti iniziali per node
+    pub fn with_node<S: Into<String>>(mut self, command: impl IntoIterator<Item = S>) -> Self {
+        self.node = command.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Programma e argomenti iniziali per snarkjs, es. `["npx", "snarkjs"]`
+    pub fn with_snarkjs<S: Into<String>>(mut self, command: impl IntoIterator<Item = S>) -> Self {
+        self.snarkjs = command.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_temp_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.temp_dir = Some(path.into());
+        self
+    }
+
+    pub fn with_witness_timeout(mut self, timeout: Duration) -> Self {
+        self.witness_timeout_ms = Some(duration_ms(timeout));
+        self
+    }
+
+    pub fn with_prove_timeout(mut self, timeout: Duration) -> Self {
+        self.prove_timeout_ms = Some(duration_ms(timeout));
+        self
+    }
+
+    pub fn with_max_parallelism(mut self, max_parallelism: usize) -> Self {
+        self.max_parallelism = Some(max_parallelism);
+        self
+    }
+
+    pub fn with_aggregation_srs_path(mut self, path: &str) -> Self {
+        self.aggregation_srs_path = Some(path.to_string());
+        self
+    }
+
+    pub fn with_committee_size(mut self, committee_size: usize) -> Self {
+        self.committee_size = Some(committee_size);
+        self
+    }
+
+    // ------------------------------------------------------------------------
+    // Path risolti
+    // ------------------------------------------------------------------------
+
+    pub fn build_dir(&self) -> String {
+        self.build_dir
+            .clone()
+            .unwrap_or_else(|| format!("{}/build", self.circuit_dir))
+    }
+
+    pub fn wasm_path(&self) -> String {
+        self.wasm_path.clone().unwrap_or_else(|| {
+            format!(
+                "{}/{}_js/{}.wasm",
+                self.build_dir(),
+                self.circuit_name,
+                self.circuit_name
+            )
+        })
+    }
+
+    pub fn witness_generator_path(&self) -> String {
+        self.witness_generator_path.clone().unwrap_or_else(|| {
+            format!(
+                "{}/{}_js/generate_witness.js",
+                self.build_dir(),
+                self.circuit_name
+            )
+        })
+    }
+
+    pub fn r1cs_path(&self) -> String {
+        self.r1cs_path
+            .clone()
+            .unwrap_or_else(|| format!("{}/{}.r1cs", self.build_dir(), self.circuit_name))
+    }
+
+    pub fn zkey_path(&self) -> String {
+        self.zkey_path
+            .clone()
+            .unwrap_or_else(|| format!("{}/{}_final.zkey", self.build_dir(), self.circuit_name))
+    }
+
+    pub fn vk_path(&self) -> String {
+        self.vk_path
+            .clone()
+            .unwrap_or_else(|| format!("{}/verification_key.json", self.build_dir()))
+    }
+
+    pub fn temp_dir(&self) -> PathBuf {
+        self.temp_dir.clone().unwrap_or_else(std::env::temp_dir)
+    }
+
+    pub fn witness_timeout(&self) -> Option<Duration> {
+        self.witness_timeout_ms.map(Duration::from_millis)
+    }
+
+    pub fn prove_timeout(&self) -> Option<Duration> {
+        self.prove_timeout_ms.map(Duration::from_millis)
+    }
+
+    pub fn committee_size(&self) -> usize {
+        self.committee_size.unwrap_or(DEFAULT_COMMITTEE_SIZE)
+    }
+
+    pub(crate) fn node_command(&self) -> Result<Command, ProverError> {
+        tool_command(&self.node, "node")
+    }
+
+    pub(crate) fn snarkjs_command(&self) -> Result<Command, ProverError> {
+        tool_command(&self.snarkjs, "snarkjs")
+    }
+}
+
+/// Millisecondi di `timeout`, arrotondati per eccesso: un timeout non nullo
+/// non diventa mai 0
+fn duration_ms(timeout: Duration) -> u64 {
+    let ms = timeout.as_nanos().div_ceil(1_000_000);
+    u64::try_from(ms).unwrap_or(u64::MAX)
+}
+
+fn tool_command(argv: &[String], name: &str) -> Result<Command, ProverError> {
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| ProverError::Config(format!("empty {} command", name)))?;
+    let mut command = Command::new(program);
+    command.args(args);
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_defaults_match_build_layout() {
+        let config = ProverConfig::new("../circuits");
+        assert_eq!(
+            config.wasm_path(),
+            "../circuits/build/bls_verify_js/bls_verify.wasm"
+        );
+        assert_eq!(
+            config.zkey_path(),
+            "../circuits/build/bls_verify_final.zkey"
+        );
+        assert_eq!(config.vk_path(), "../circuits/build/verification_key.json");
+
+        let renamed = ProverConfig::new("/srv/agg").with_circuit_name("bls_agg_64");
+        assert_eq!(renamed.r1cs_path(), "/srv/agg/build/bls_agg_64.r1cs");
+        assert_eq!(
+            renamed.witness_generator_path(),
+            "/srv/agg/build/bls_agg_64_js/generate_witness.js"
+        );
+    }
+
+    #[test]
+    fn test_config_file_and_overrides() {
+        let toml = r#"
+            circuit_dir = "/srv/circuits"
+            circuit_name = "bls_batch"
+            zkey_path = "/keys/batch.zkey"
+            snarkjs = ["npx", "snarkjs"]
+            prove_timeout_ms = 120000
+        "#;
+        let config: ProverConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.zkey_path(), "/keys/batch.zkey");
+        assert_eq!(
+            config.wasm_path(),
+            "/srv/circuits/build/bls_batch_js/bls_batch.wasm"
+        );
+        assert_eq!(config.node, vec!["node"]);
+        assert_eq!(config.prove_timeout(), Some(Duration::from_secs(120)));
+
+        let config = config
+            .with_overrides(|key| match key {
+                "BACKEND" => Some("native".to_string()),
+                "ZKEY" => Some("/other.zkey".to_string()),
+                "SNARKJS" => Some("/usr/local/bin/snarkjs".to_string()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(config.backend, BackendKind::Native);
+        assert_eq!(config.zkey_path(), "/other.zkey");
+        assert_eq!(config.committee_size(), DEFAULT_COMMITTEE_SIZE);
+        assert_eq!(config.snarkjs, vec!["/usr/local/bin/snarkjs"]);
+
+        let bad = ProverConfig::default()
+            .with_overrides(|key| (key == "PROVE_TIMEOUT_MS").then(|| "soon".to_string()));
+        assert!(matches!(bad, Err(ProverError::Config(_))));
+        let sized = ProverConfig::default()
+            .with_overrides(|key| (key == "COMMITTEE_SIZE").then(|| "64".to_string()))
+            .unwrap();
+        assert_eq!(sized.committee_size(), 64);
+        let fast = ProverConfig::default()
+            .with_witness_timeout(Duration::from_millis(500))
+            .with_prove_timeout(Duration::from_micros(1));
+        assert_eq!(fast.witness_timeout(), Some(Duration::from_millis(500)));
+        assert_eq!(fast.prove_timeout(), Some(Duration::from_millis(1)));
+        assert!(ProverConfig::default()
+            .with_snarkjs(Vec::<String>::new())
+            .snarkjs_command()
+            .is_err());
+    }
+}
@@ -0,0 +1,357 @@
+// prover/src/export.rs
+// Export della verification key in più formati
+//
+// La stessa VerifyingKey<Bn254> serve a consumer diversi: snarkjs (JSON),
+// light client Rust (CanonicalSerialize), verifier Go (gnark) e contratti
+// Solidity (blocco di costanti).
+
+use crate::{field_to_decimal, fq12_to_snarkjs, ProverError, SnarkjsVerificationKey};
+use ark_bn254::{Bn254, Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::pairing::Pairing;
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_groth16::VerifyingKey;
+use ark_serialize::CanonicalSerialize;
+use num_bigint::BigUint;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// Formati di export della verification key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VkExportFormat {
+    /// verification_key.json di snarkjs
+    SnarkjsJson,
+    /// arkworks CanonicalSerialize compresso
+    ArkworksCompressed,
+    /// arkworks CanonicalSerialize non compresso
+    ArkworksUncompressed,
+    /// Binario di gnark (VerifyingKey.WriteTo, punti compressi)
+    Gnark,
+    /// Blocco di costanti Solidity (stessi nomi del Verifier.sol di snarkjs)
+    SolidityConstants,
+    /// Contratto Verifier.sol completo, vedi [`crate::generate_solidity_verifier`]
+    SolidityVerifier,
+    /// Contratto Groth16BatchVerifier, vedi [`crate::generate_solidity_batch_verifier`]
+    SolidityBatchVerifier,
+}
+
+impl FromStr for VkExportFormat {
+    type Err = ProverError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "snarkjs" | "json" => Ok(VkExportFormat::SnarkjsJson),
+            "ark-compressed" => Ok(VkExportFormat::ArkworksCompressed),
+            "ark-uncompressed" => Ok(VkExportFormat::ArkworksUncompressed),
+            "gnark" => Ok(VkExportFormat::Gnark),
+            "solidity" => Ok(VkExportFormat::SolidityConstants),
+            "solidity-verifier" => Ok(VkExportFormat::SolidityVerifier),
+            "solidity-batch-verifier" => Ok(VkExportFormat::SolidityBatchVerifier),
+            _ => Err(ProverError::InvalidInput(format!(
+                "unknown verification key format {:?} \
+                 (expected snarkjs, ark-compressed, ark-uncompressed, gnark, solidity, \
+                 solidity-verifier, solidity-batch-verifier)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Esporta una VerifyingKey<Bn254> nei formati di [`VkExportFormat`]
+pub struct VkExporter<'a> {
+    vk: &'a VerifyingKey<Bn254>,
+    /// beta e delta in G1: non fanno parte della VK arkworks ma gnark li serializza
+    g1_points: Option<(G1Affine, G1Affine)>,
+}
+
+impl<'a> VkExporter<'a> {
+    pub fn new(vk: &'a VerifyingKey<Bn254>) -> Self {
+        VkExporter {
+            vk,
+            g1_points: None,
+        }
+    }
+
+    /// Aggiunge beta_g1 e delta_g1 (dall'header della zkey), richiesti dal formato gnark
+    pub fn with_g1_points(mut self, beta_g1: G1Affine, delta_g1: G1Affine) -> Self {
+        self.g1_points = Some((beta_g1, delta_g1));
+        self
+    }
+
+    pub fn export(&self, format: VkExportFormat) -> Result<Vec<u8>, ProverError> {
+        match format {
+            VkExportFormat::SnarkjsJson => Ok(serde_json::to_vec_pretty(&self.to_snarkjs())?),
+            VkExportFormat::ArkworksCompressed => {
+                let mut bytes = Vec::new();
+                self.vk
+                    .serialize_compressed(&mut bytes)
+                    .map_err(|e| ProverError::InvalidVerifyingKey(e.to_string()))?;
+                Ok(bytes)
+            }
+            VkExportFormat::ArkworksUncompressed => {
+                let mut bytes = Vec::new();
+                self.vk
+                    .serialize_uncompressed(&mut bytes)
+                    .map_err(|e| ProverError::InvalidVerifyingKey(e.to_string()))?;
+                Ok(bytes)
+            }
+            VkExportFormat::Gnark => self.to_gnark(),
+            VkExportFormat::SolidityConstants => Ok(self.solidity_constants().into_bytes()),
+            VkExportFormat::SolidityVerifier => {
+                Ok(crate::generate_solidity_verifier(self.vk).into_bytes())
+            }
+            VkExportFormat::SolidityBatchVerifier => {
+                Ok(crate::generate_solidity_batch_verifier(self.vk).into_bytes())
+            }
+        }
+    }
+
+    /// Verification key nel formato di snarkjs (punti con coordinata Z = 1)
+    pub fn to_snarkjs(&self) -> SnarkjsVerificationKey {
+        SnarkjsVerificationKey {
+            protocol: "groth16".to_string(),
+            curve: "bn128".to_string(),
+            n_public: self.vk.gamma_abc_g1.len().saturating_sub(1),
+            vk_alpha_1: g1_to_snarkjs(&self.vk.alpha_g1),
+            vk_beta_2: g2_to_snarkjs(&self.vk.beta_g2),
+            vk_gamma_2: g2_to_snarkjs(&self.vk.gamma_g2),
+            vk_delta_2: g2_to_snarkjs(&self.vk.delta_g2),
+            vk_alphabeta_12: fq12_to_snarkjs(&Bn254::pairing(self.vk.alpha_g1, self.vk.beta_g2).0),
+            ic: self.vk.gamma_abc_g1.iter().map(g1_to_snarkjs).collect(),
+        }
+    }
+
+    /// Layout di gnark (>= v0.9) per groth16/bn254:
+    /// [α]1 [β]1 [β]2 [γ]2 [δ]1 [δ]2, uint32(len K) [K]1,
+    /// PublicAndCommitmentCommitted (vuoto), uint32(len CommitmentKeys) = 0
+    fn to_gnark(&self) -> Result<Vec<u8>, ProverError> {
+        let (beta_g1, delta_g1) = self.g1_points.ok_or_else(|| {
+            ProverError::InvalidInput(
+                "gnark format requires beta_g1 and delta_g1 from the zkey".to_string(),
+            )
+        })?;
+
+        let mut out = Vec::new();
+        out.extend(gnark_g1(&self.vk.alpha_g1));
+        out.extend(gnark_g1(&beta_g1));
+        out.extend(gnark_g2(&self.vk.beta_g2));
+        out.extend(gnark_g2(&self.vk.gamma_g2));
+        out.extend(gnark_g1(&delta_g1));
+        out.extend(gnark_g2(&self.vk.delta_g2));
+
+        out.extend((self.vk.gamma_abc_g1.len() as u32).to_be_bytes());
+        for point in &self.vk.gamma_abc_g1 {
+            out.extend(gnark_g1(point));
+        }
+
+        // Nessun commitment: [][]uint64 vuoto e zero commitment keys
+        out.extend(0u32.to_be_bytes());
+        out.extend(0u32.to_be_bytes());
+        Ok(out)
+    }
+
+    /// Costanti con gli stessi nomi del template Verifier.sol di snarkjs.
+    /// Le coordinate Fq2 sono in ordine (c1, c0) come atteso dalla precompilata.
+    pub fn solidity_constants(&self) -> String {
+        let vk = self.vk;
+        let mut out = String::new();
+
+        let _ = writeln!(out, "    // Verification Key data");
+        write_constant(&mut out, "alphax", &vk.alpha_g1.x);
+        write_constant(&mut out, "alphay", &vk.alpha_g1.y);
+        for (name, point) in [
+            ("beta", &vk.beta_g2),
+            ("gamma", &vk.gamma_g2),
+            ("delta", &vk.delta_g2),
+        ] {
+            write_constant(&mut out, &format!("{}x1", name), &point.x.c1);
+            write_constant(&mut out, &format!("{}x2", name), &point.x.c0);
+            write_constant(&mut out, &format!("{}y1", name), &point.y.c1);
+            write_constant(&mut out, &format!("{}y2", name), &point.y.c0);
+        }
+        let _ = writeln!(out);
+        for (i, point) in vk.gamma_abc_g1.iter().enumerate() {
+            write_constant(&mut out, &format!("IC{}x", i), &point.x);
+            write_constant(&mut out, &format!("IC{}y", i), &point.y);
+            let _ = writeln!(out);
+        }
+        out
+    }
+}
+
+fn write_constant(out: &mut String, name: &str, value: &Fq) {
+    let _ = writeln!(
+        out,
+        "    uint256 constant {:<8}= {};",
+        name,
+        field_to_decimal(value)
+    );
+}
+
+fn g1_to_snarkjs(point: &G1Affine) -> Vec<String> {
+    if point.infinity {
+        return vec!["0".to_string(), "1".to_string(), "0".to_string()];
+    }
+    vec![
+        field_to_decimal(&point.x),
+        field_to_decimal(&point.y),
+        "1".to_string(),
+    ]
+}
+
+fn g2_to_snarkjs(point: &G2Affine) -> Vec<Vec<String>> {
+    let pair = |f: &Fq2| vec![field_to_decimal(&f.c0), field_to_decimal(&f.c1)];
+    if point.infinity {
+        return vec![
+            vec!["0".to_string(), "0".to_string()],
+            vec!["1".to_string(), "0".to_string()],
+            vec!["0".to_string(), "0".to_string()],
+        ];
+    }
+    vec![
+        pair(&point.x),
+        pair(&point.y),
+        vec!["1".to_string(), "0".to_string()],
+    ]
+}
+
+// Flag nei 2 bit alti del primo byte (gnark-crypto, bn254/marshal.go)
+const GNARK_COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+const GNARK_COMPRESSED_LARGEST: u8 = 0b11 << 6;
+const GNARK_COMPRESSED_INFINITY: u8 = 0b01 << 6;
+
+/// y > (q - 1) / 2
+fn fq_lexicographically_largest(y: &Fq) -> bool {
+    let value: BigUint = (*y).into();
+    let half: BigUint = Fq::MODULUS_MINUS_ONE_DIV_TWO.into();
+    value > half
+}
+
+fn fq2_lexicographically_largest(y: &Fq2) -> bool {
+    if y.c1.is_zero() {
+        fq_lexicographically_largest(&y.c0)
+    } else {
+        fq_lexicographically_largest(&y.c1)
+    }
+}
+
+fn fq_to_be(f: &Fq) -> Vec<u8> {
+    f.into_bigint().to_bytes_be()
+}
+
+/// G1 compresso: X big endian (32 bytes) con i flag nel primo byte
+fn gnark_g1(point: &G1Affine) -> Vec<u8> {
+    let mut out = vec![0u8; 32];
+    if point.infinity {
+        out[0] = GNARK_COMPRESSED_INFINITY;
+        return out;
+    }
+    out.copy_from_slice(&fq_to_be(&point.x));
+    out[0] |= if fq_lexicographically_largest(&point.y) {
+        GNARK_COMPRESSED_LARGEST
+    } else {
+        GNARK_COMPRESSED_SMALLEST
+    };
+    out
+}
+
+/// G2 compresso: X.c1 || X.c0 big endian (64 bytes) con i flag nel primo byte
+fn gnark_g2(point: &G2Affine) -> Vec<u8> {
+    let mut out = vec![0u8; 64];
+    if point.infinity {
+        out[0] = GNARK_COMPRESSED_INFINITY;
+        return out;
+    }
+    out[..32].copy_from_slice(&fq_to_be(&point.x.c1));
+    out[32..].copy_from_slice(&fq_to_be(&point.x.c0));
+    out[0] |= if fq2_lexicographically_largest(&point.y) {
+        GNARK_COMPRESSED_LARGEST
+    } else {
+        GNARK_COMPRESSED_SMALLEST
+    };
+    out
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_serialize::CanonicalDeserialize;
+
+    /// VK sintetica con `n_public` input pubblici: multipli primi dei generatori
+    pub(crate) fn test_vk(n_public: usize) -> VerifyingKey<Bn254> {
+        const IC_SCALARS: [u64; 8] = [11, 13, 17, 19, 23, 29, 31, 37];
+        let g1 = |k: u64| (G1Affine::generator() * Fr::from(k)).into_affine();
+        let g2 = |k: u64| (G2Affine::generator() * Fr::from(k)).into_affine();
+        VerifyingKey {
+            alpha_g1: g1(2),
+            beta_g2: g2(3),
+            gamma_g2: g2(5),
+            delta_g2: g2(7),
+            gamma_abc_g1: IC_SCALARS[..=n_public].iter().map(|&k| g1(k)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_export_roundtrips() {
+        let vk = test_vk(2);
+        let exporter = VkExporter::new(&vk);
+
+        let json = exporter.export(VkExportFormat::SnarkjsJson).unwrap();
+        let snarkjs: SnarkjsVerificationKey = serde_json::from_slice(&json).unwrap();
+        assert_eq!(snarkjs.n_public, 2);
+        assert_eq!(snarkjs.to_arkworks_vk().unwrap(), vk);
+
+        let compressed = exporter.export(VkExportFormat::ArkworksCompressed).unwrap();
+        assert_eq!(
+            VerifyingKey::<Bn254>::deserialize_compressed(&compressed[..]).unwrap(),
+            vk
+        );
+
+        let uncompressed = exporter
+            .export(VkExportFormat::ArkworksUncompressed)
+            .unwrap();
+        assert_eq!(
+            VerifyingKey::<Bn254>::deserialize_uncompressed(&uncompressed[..]).unwrap(),
+            vk
+        );
+    }
+
+    #[test]
+    fn test_export_gnark_layout() {
+        let vk = test_vk(2);
+        assert!(VkExporter::new(&vk).export(VkExportFormat::Gnark).is_err());
+
+        let bytes = VkExporter::new(&vk)
+            .with_g1_points(G1Affine::generator(), G1Affine::generator())
+            .export(VkExportFormat::Gnark)
+            .unwrap();
+        assert_eq!(bytes.len(), 3 * 32 + 3 * 64 + 4 + 3 * 32 + 8);
+
+        // Il generatore G1 (1, 2) ha y "piccolo": X = 1 con flag 0b10
+        assert_eq!(bytes[32], GNARK_COMPRESSED_SMALLEST);
+        assert_eq!(bytes[32 + 31], 1);
+    }
+
+    #[test]
+    fn test_export_solidity_constants() {
+        let vk = test_vk(2);
+        let constants = String::from_utf8(
+            VkExporter::new(&vk)
+                .export(VkExportFormat::SolidityConstants)
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert!(constants.contains(&format!(
+            "betax1  = {};",
+            field_to_decimal(&vk.beta_g2.x.c1)
+        )));
+        assert!(constants.contains(&format!(
+            "betax2  = {};",
+            field_to_decimal(&vk.beta_g2.x.c0)
+        )));
+        assert!(constants.contains("IC2x"));
+        assert!(!constants.contains("IC3x"));
+    }
+}
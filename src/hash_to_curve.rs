@@ -0,0 +1,305 @@
+// prover/src/hash_to_curve.rs
+// Hash-to-curve secondo RFC 9380
+//
+// H(m) è un input pubblico dei circuiti, calcolato fuori: firmatario e prover
+// devono mappare il messaggio nello stesso punto. Si usano le suite random
+// oracle (_RO_) con expand_message_xmd e SHA-256:
+//   - BN254 G1: BN254G1_XMD:SHA-256_SVDW_RO_, mappa di Shallue-van de Woestijne
+//     con Z = 1 (stessa scelta di gnark-crypto)
+//   - BLS12-381 G2: BLS12381G2_XMD:SHA-256_SSWU_RO_, SSWU sull'isogena di grado
+//     3 (mappa di ark-bls12-381), come le specifiche consensus di Ethereum
+//
+// expand_message_xmd è implementata qui: quella di ark-ff (0.4 e 0.5) usa Z_pad lungo
+// L byte invece del blocco di SHA-256 (64 byte), e sbaglia quando L ≠ 64 (BN254).
+
+use crate::{Bls12G2PointInput, G1PointInput, ProverError};
+use ark_bn254::{g1, Fq, G1Affine};
+use ark_ec::hashing::curve_maps::wb::WBMap;
+use ark_ec::hashing::map_to_curve_hasher::MapToCurve;
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{AdditiveGroup, BigInteger, Field, One, PrimeField, Zero};
+use sha2::{Digest, Sha256};
+
+/// DST predefinito per le firme su BN254 (schema senza proof of possession)
+pub const BN254_G1_DST: &[u8] = b"BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_NUL_";
+/// DST delle firme su BN254 nello schema proof of possession (firme aggregate
+/// dei comitati)
+pub const BN254_G1_POP_SCHEME_DST: &[u8] = b"BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_POP_";
+/// DST di PopProve/PopVerify: separato da quello delle firme, così una proof of
+/// possession non è mai una firma valida su un messaggio
+pub const BN254_G1_POP_DST: &[u8] = b"BLS_POP_BN254G1_XMD:SHA-256_SVDW_RO_POP_";
+/// DST delle firme di Ethereum consensus (schema proof of possession)
+pub const BLS12_381_G2_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+const SHA256_BLOCK_BYTES: usize = 64;
+const SHA256_OUTPUT_BYTES: usize = 32;
+/// Livello di sicurezza k della suite: L = ⌈(⌈log2 p⌉ + k) / 8⌉
+const SECURITY_BITS: usize = 128;
+const MAX_DST_BYTES: usize = 255;
+const LONG_DST_PREFIX: &[u8] = b"H2C-OVERSIZE-DST-";
+
+/// expand_message_xmd con SHA-256 (RFC 9380, 5.3.1). Un DST più lungo di
+/// 255 byte viene sostituito dal suo hash, come prescritto (5.3.3).
+pub fn expand_message_xmd(message: &[u8], dst: &[u8], len: usize) -> Result<Vec<u8>, ProverError> {
+    if dst.is_empty() {
+        return Err(ProverError::InvalidInput(
+            "hash-to-curve DST must not be empty".to_string(),
+        ));
+    }
+    let ell = len.div_ceil(SHA256_OUTPUT_BYTES);
+    if ell > 255 || len > u16::MAX as usize {
+        return Err(ProverError::InvalidInput(format!(
+            "expand_message_xmd: output length {} too large",
+            len
+        )));
+    }
+
+    let long_dst;
+    let dst = if dst.len() > MAX_DST_BYTES {
+        long_dst = Sha256::new()
+            .chain_update(LONG_DST_PREFIX)
+            .chain_update(dst)
+            .finalize();
+        &long_dst[..]
+    } else {
+        dst
+    };
+    // DST_prime = DST || I2OSP(len(DST), 1)
+    let dst_len = [dst.len() as u8];
+
+    let b0 = Sha256::new()
+        .chain_update([0u8; SHA256_BLOCK_BYTES])
+        .chain_update(message)
+        .chain_update((len as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+    let mut bi = Sha256::new()
+        .chain_update(b0)
+        .chain_update([1u8])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+
+    let mut uniform_bytes = bi.to_vec();
+    for i in 2..=ell as u8 {
+        let xor: Vec<u8> = b0.iter().zip(bi.iter()).map(|(a, b)| a ^ b).collect();
+        bi = Sha256::new()
+            .chain_update(xor)
+            .chain_update([i])
+            .chain_update(dst)
+            .chain_update(dst_len)
+            .finalize();
+        uniform_bytes.extend_from_slice(&bi);
+    }
+    uniform_bytes.truncate(len);
+    Ok(uniform_bytes)
+}
+
+/// hash_to_field (RFC 9380, 5.2): `count` elementi del campo primo, ciascuno
+/// da L byte big endian ridotti modulo p. Per F_p² servono due elementi per
+/// coordinata, nell'ordine c0, c1.
+fn hash_to_field<F: PrimeField>(
+    message: &[u8],
+    dst: &[u8],
+    count: usize,
+) -> Result<Vec<F>, ProverError> {
+    let len = (F::MODULUS_BIT_SIZE as usize + SECURITY_BITS).div_ceil(8);
+    let bytes = expand_message_xmd(message, dst, count * len)?;
+    Ok(bytes.chunks(len).map(F::from_be_bytes_mod_order).collect())
+}
+
+/// H(m) in G1 di BN254 (suite BN254G1_XMD:SHA-256_SVDW_RO_)
+pub fn hash_to_g1(message: &[u8], dst: &[u8]) -> Result<G1Affine, ProverError> {
+    let u: Vec<Fq> = hash_to_field(message, dst, 2)?;
+    // Cofattore 1: clear_cofactor è l'identità
+    Ok((svdw_map(u[0]) + svdw_map(u[1])).into_affine())
+}
+
+/// H(m) in G2 di BLS12-381 (suite BLS12381G2_XMD:SHA-256_SSWU_RO_)
+pub fn hash_to_bls12_g2(
+    message: &[u8],
+    dst: &[u8],
+) -> Result<ark_bls12_381::G2Affine, ProverError> {
+    let u: Vec<ark_bls12_381::Fq> = hash_to_field(message, dst, 4)?;
+    let map_error = |e: ark_ec::hashing::HashToCurveError| {
+        ProverError::InvalidInput(format!("hash-to-curve map failed: {}", e))
+    };
+    let map = |c0, c1| {
+        WBMap::<ark_bls12_381::g2::Config>::map_to_curve(ark_bls12_381::Fq2::new(c0, c1))
+            .map_err(map_error)
+    };
+    let q0 = map(u[0], u[1])?;
+    let q1 = map(u[2], u[3])?;
+    // clear_cofactor di ark-bls12-381 equivale alla moltiplicazione per h_eff
+    Ok((q0 + q1).into_affine().clear_cofactor())
+}
+
+/// Mappa di Shallue-van de Woestijne (RFC 9380, 6.6.1) per y² = x³ + 3 con Z = 1.
+/// Non è a tempo costante: i messaggi firmati sono pubblici.
+fn svdw_map(u: Fq) -> G1Affine {
+    // A = 0
+    let g = |x: Fq| x.square() * x + g1::Config::COEFF_B;
+    let z = Fq::one();
+    let gz = g(z);
+    let three_z2 = z.square() * Fq::from(3u8);
+
+    // Costanti della mappa: c2 = -Z/2, c3 = sqrt(-g(Z)·3Z²) con sgn0(c3) = 0,
+    // c4 = -4·g(Z) / 3Z²
+    let c2 = -z / Fq::from(2u8);
+    let mut c3 = (-gz * three_z2)
+        .sqrt()
+        .expect("-g(Z)·3Z² is a square for Z = 1");
+    if sgn0(&c3) {
+        c3 = -c3;
+    }
+    let c4 = -gz.double().double() / three_z2;
+
+    let tv1 = u.square() * gz;
+    let tv2 = Fq::one() + tv1;
+    let tv1 = Fq::one() - tv1;
+    // inv0: l'inverso di zero è zero
+    let tv3 = (tv1 * tv2).inverse().unwrap_or(Fq::zero());
+    let tv5 = u * tv1 * tv3 * c3;
+
+    let x1 = c2 - tv5;
+    let x2 = c2 + tv5;
+    let x3 = z + c4 * (tv2.square() * tv3).square();
+    let (x, y) = [x1, x2, x3]
+        .into_iter()
+        .find_map(|x| g(x).sqrt().map(|y| (x, y)))
+        .expect("g(x3) is always a square");
+
+    let y = if sgn0(&u) != sgn0(&y) { -y } else { y };
+    G1Affine::new_unchecked(x, y)
+}
+
+/// sgn0 per un campo primo: parità del rappresentante canonico
+fn sgn0(x: &Fq) -> bool {
+    x.into_bigint().is_odd()
+}
+
+impl G1PointInput {
+    /// H(m) come lo riceve bls_verify.circom
+    pub fn from_message(message: &[u8], dst: &[u8]) -> Result<Self, ProverError> {
+        Ok(hash_to_g1(message, dst)?.into())
+    }
+}
+
+impl Bls12G2PointInput {
+    /// H(m) come lo riceve bls12_381_verify.circom
+    pub fn from_message(message: &[u8], dst: &[u8]) -> Result<Self, ProverError> {
+        Ok(hash_to_bls12_g2(message, dst)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Vettori con i DST "QUUX": RFC 9380 J.10.1 per BLS12-381 G2, gnark-crypto
+    // per BN254 G1 (la suite non è nella RFC)
+    const BN254_TEST_DST: &[u8] = b"QUUX-V01-CS02-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+    const BLS12_TEST_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+    fn fe<F: PrimeField>(hex: &str) -> F {
+        F::from_be_bytes_mod_order(&hex::decode(hex).unwrap())
+    }
+
+    fn fe2(c0: &str, c1: &str) -> ark_bls12_381::Fq2 {
+        ark_bls12_381::Fq2::new(fe(c0), fe(c1))
+    }
+
+    #[test]
+    fn test_hash_to_field_and_dst() {
+        // u[0] per il messaggio vuoto (J.10.1)
+        let u: Vec<ark_bls12_381::Fq> = hash_to_field(b"", BLS12_TEST_DST, 4).unwrap();
+        assert_eq!(
+            ark_bls12_381::Fq2::new(u[0], u[1]),
+            fe2(
+                "03dbc2cce174e91ba93cbb08f26b917f98194a2ea08d1cce75b2b9cc9f21689d80bd79b594a613d0a68eb807dfdc1cf8",
+                "05a2acec64114845711a54199ea339abd125ba38253b70a92c876df10598bd1986b739cad67961eb94f7076511b3b39a"
+            )
+        );
+
+        assert_eq!(expand_message_xmd(b"abc", b"DST", 100).unwrap().len(), 100);
+        assert!(expand_message_xmd(b"abc", b"", 32).is_err());
+        assert!(expand_message_xmd(b"abc", b"DST", 256 * 32).is_err());
+
+        // DST lungo: equivale al suo hash con il prefisso H2C-OVERSIZE-DST-
+        let long_dst = [b'x'; 300];
+        let hashed: Vec<u8> = Sha256::new()
+            .chain_update(LONG_DST_PREFIX)
+            .chain_update(long_dst)
+            .finalize()
+            .to_vec();
+        assert_eq!(
+            expand_message_xmd(b"abc", &long_dst, 48).unwrap(),
+            expand_message_xmd(b"abc", &hashed, 48).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hash_to_g1_bn254_vectors() {
+        let vectors = [
+            (
+                &b""[..],
+                "0a976ab906170db1f9638d376514dbf8c42aef256a54bbd48521f20749e59e86",
+                "02925ead66b9e68bfc309b014398640ab55f6619ab59bc1fab2210ad4c4d53d5",
+            ),
+            (
+                &b"abc"[..],
+                "23f717bee89b1003957139f193e6be7da1df5f1374b26a4643b0378b5baf53d1",
+                "04142f826b71ee574452dbc47e05bc3e1a647478403a7ba38b7b93948f4e151d",
+            ),
+        ];
+        for (message, x, y) in vectors {
+            let point = hash_to_g1(message, BN254_TEST_DST).unwrap();
+            assert_eq!(point, G1Affine::new(fe(x), fe(y)));
+        }
+
+        // Il DST separa i domini
+        assert_ne!(
+            hash_to_g1(b"abc", BN254_G1_DST).unwrap(),
+            hash_to_g1(b"abc", BN254_TEST_DST).unwrap()
+        );
+        let input = G1PointInput::from_message(b"abc", BN254_G1_DST).unwrap();
+        assert!(input.to_affine("messageHash").is_ok());
+    }
+
+    #[test]
+    fn test_hash_to_bls12_g2_vectors() {
+        let vectors = [
+            (
+                &b""[..],
+                [
+                    "0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a",
+                    "05cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d",
+                    "0503921d7f6a12805e72940b963c0cf3471c7b2a524950ca195d11062ee75ec076daf2d4bc358c4b190c0c98064fdd92",
+                    "12424ac32561493f3fe3c260708a12b7c620e7be00099a974e259ddc7d1f6395c3c811cdd19f1e8dbf3e9ecfdcbab8d6",
+                ],
+            ),
+            (
+                &b"abc"[..],
+                [
+                    "02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6",
+                    "139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8",
+                    "1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48",
+                    "00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16",
+                ],
+            ),
+        ];
+        for (message, [x0, x1, y0, y1]) in vectors {
+            let point = hash_to_bls12_g2(message, BLS12_TEST_DST).unwrap();
+            assert_eq!(
+                point,
+                ark_bls12_381::G2Affine::new(fe2(x0, x1), fe2(y0, y1))
+            );
+        }
+
+        let input = Bls12G2PointInput::from_message(b"abc", BLS12_381_G2_DST).unwrap();
+        assert!(input.to_affine("messageHash").is_ok());
+    }
+}
@@ -5,35 +5,246 @@
 // Carica i parametri (PK, VK) dal file .zkey generato da snarkjs,
 // così le prove sono compatibili con Verifier.sol generato da snarkjs.
 
-use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
-use ark_circom::{CircomBuilder, CircomConfig};
-use ark_ec::AffineRepr;
-use ark_ff::PrimeField;
-use ark_groth16::{prepare_verifying_key, verify_proof, Proof, ProvingKey, VerifyingKey};
-use ark_relations::r1cs::ConstraintMatrices;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::rand::thread_rng;
-use num_bigint::BigInt;
+use ark_bn254::{Bn254, Fq, Fq12, Fq2, Fq6, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_circom::{CircomReduction, WitnessCalculator};
+use ark_ec::{bn::BnConfig, pairing::Pairing, CurveGroup};
+use ark_ff::{BigInteger, BigInteger256, Field, PrimeField, Zero};
+use ark_groth16::{
+    prepare_verifying_key, Groth16, PreparedVerifyingKey, Proof, ProvingKey, VerifyingKey,
+};
+use ark_relations::r1cs::{ConstraintMatrices, SynthesisError};
+use ark_std::{rand::thread_rng, UniformRand};
+use num_bigint::{BigInt, BigUint};
 use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::{BufReader, Read};
-use std::path::Path;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thiserror::Error;
+
+mod aggregation;
+mod backend;
+mod batch_verify;
+mod bls;
+mod bls12_381;
+mod committee;
+mod config;
+mod export;
+mod hash_to_curve;
+mod poseidon;
+mod process;
+mod solidity;
+pub use aggregation::{
+    aggregate_proofs, verify_aggregate_proof, AggregateProof, AggregationSrs,
+    AggregationVerifierKey, GipaRound,
+};
+pub use backend::{BackendFuture, BackendStats, ProofBackend};
+pub use batch_verify::{verify_batch, verify_snarkjs_batch};
+pub use bls::{
+    aggregate_public_keys, aggregate_signatures, verify_hashed, PublicKey, SecretKey, Signature,
+};
+pub use bls12_381::{
+    decompress_bls12_public_key, decompress_bls12_signature, Bls12G1PointInput,
+    Bls12G2PointInput, Bls12PrivateInputs, Bls12ProofInputs, Bls12PublicInputs,
+};
+pub use committee::{
+    committee_batch_message, Committee, CommitteePrivateInputs, CommitteeProofInputs,
+    CommitteePublicInputs,
+};
+pub use config::{BackendKind, ProverConfig, DEFAULT_COMMITTEE_SIZE};
+pub use export::{VkExportFormat, VkExporter};
+pub use hash_to_curve::{
+    expand_message_xmd, hash_to_bls12_g2, hash_to_g1, BLS12_381_G2_DST, BN254_G1_DST,
+    BN254_G1_POP_DST, BN254_G1_POP_SCHEME_DST,
+};
+pub use process::CancelToken;
+pub use solidity::{generate_solidity_batch_verifier, generate_solidity_verifier};
+
+// ============================================================================
+// ERRORI
+// ============================================================================
+
+/// Errori del prover, distinti per permettere retry/alert mirati
+#[derive(Debug, Error)]
+pub enum ProverError {
+    /// File del circuito (wasm, r1cs, zkey, vk) non trovato
+    #[error("{kind} not found: {path}")]
+    MissingArtifact { kind: &'static str, path: String },
+
+    #[error("invalid zkey file: wrong magic number {0:#010x}")]
+    ZkeyBadMagic(u32),
+
+    #[error("invalid zkey file: missing section {0}")]
+    ZkeyMissingSection(u32),
+
+    #[error("invalid zkey file: truncated data at offset {offset} ({needed} bytes needed)")]
+    ZkeyTruncated { offset: u64, needed: u64 },
+
+    #[error("invalid zkey file: {0}")]
+    ZkeyFormat(String),
+
+    /// Elemento di campo non parsabile o fuori dal campo
+    #[error("invalid field element {field}: {reason}")]
+    InvalidFieldElement { field: String, reason: String },
+
+    /// Punto non valido (fuori dalla curva, dal sottogruppo o malformato)
+    #[error("invalid curve point {field}: {reason}")]
+    InvalidPoint { field: String, reason: String },
+
+    /// Verification key incoerente (protocollo, curva, IC, vk_alphabeta_12)
+    #[error("invalid verification key: {0}")]
+    InvalidVerifyingKey(String),
+
+    #[error("invalid calldata: {0}")]
+    InvalidCalldata(String),
+
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+
+    /// File di configurazione o variabile d'ambiente non validi
+    #[error("invalid configuration: {0}")]
+    Config(String),
+
+    #[error("witness generation failed: {0}")]
+    Witness(String),
+
+    /// Processo esterno (node, snarkjs) terminato con errore.
+    /// snarkjs scrive spesso gli errori su stdout: se stderr è vuoto si mostra quello.
+    #[error("{command} failed (exit code {code:?}): {}", if .stderr.is_empty() { .stdout } else { .stderr })]
+    Subprocess {
+        command: String,
+        code: Option<i32>,
+        stdout: String,
+        stderr: String,
+    },
+
+    /// Processo esterno ucciso allo scadere del timeout
+    #[error("{command} timed out after {timeout:?}: {stderr}")]
+    Timeout {
+        command: String,
+        timeout: std::time::Duration,
+        stdout: String,
+        stderr: String,
+    },
+
+    #[error("{command} cancelled")]
+    Cancelled { command: String },
+
+    /// SRS o prove non aggregabili (SRS troppo piccola, input incoerenti)
+    #[error("proof aggregation failed: {0}")]
+    Aggregation(String),
+
+    /// Uno o più elementi di un batch non sono stati provati
+    #[error("{} of {total} batch items failed, first {}", .failures.len(), .failures[0])]
+    BatchFailed {
+        total: usize,
+        failures: Vec<BatchItemError>,
+    },
+
+    /// Task tokio (spawn_blocking) terminato con panic o annullato
+    #[error("background task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+
+    #[error("proof verification failed")]
+    VerificationFailed,
+
+    /// Firma BLS che non soddisfa l'equazione di pairing (controllo nativo)
+    #[error("invalid BLS signature")]
+    InvalidSignature,
+
+    #[error("prover not initialized: call setup() first")]
+    NotInitialized,
+
+    #[error("groth16 error: {0}")]
+    Groth16(#[from] SynthesisError),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl ProverError {
+    fn subprocess(command: &str, output: &std::process::Output) -> Self {
+        ProverError::Subprocess {
+            command: command.to_string(),
+            code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        }
+    }
+
+    fn missing(kind: &'static str, path: &str) -> Self {
+        ProverError::MissingArtifact {
+            kind,
+            path: path.to_string(),
+        }
+    }
+}
 
 // ============================================================================
 // STRUTTURE DATI
 // ============================================================================
 
+/// Punto di G1 in coordinate affini, coordinate decimali in Fq
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct G1PointInput {
+    pub x: String,
+    pub y: String,
+}
+
+/// Punto di G2 in coordinate affini sul twist, coordinate in Fq2 come [c0, c1]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct G2PointInput {
+    pub x: [String; 2],
+    pub y: [String; 2],
+}
+
+impl G1PointInput {
+    /// Punto validato: sulla curva e nel sottogruppo di ordine r
+    pub fn to_affine(&self, field: &str) -> Result<G1Affine, ProverError> {
+        checked_g1(parse_fq(&self.x, field)?, parse_fq(&self.y, field)?, field)
+    }
+}
+
+impl G2PointInput {
+    /// Punto validato: sul twist e nel sottogruppo di ordine r
+    pub fn to_affine(&self, field: &str) -> Result<G2Affine, ProverError> {
+        let x = Fq2::new(parse_fq(&self.x[0], field)?, parse_fq(&self.x[1], field)?);
+        let y = Fq2::new(parse_fq(&self.y[0], field)?, parse_fq(&self.y[1], field)?);
+        checked_g2(x, y, field)
+    }
+}
+
+impl From<G1Affine> for G1PointInput {
+    fn from(point: G1Affine) -> Self {
+        G1PointInput {
+            x: field_to_decimal(&point.x),
+            y: field_to_decimal(&point.y),
+        }
+    }
+}
+
+impl From<G2Affine> for G2PointInput {
+    fn from(point: G2Affine) -> Self {
+        G2PointInput {
+            x: [field_to_decimal(&point.x.c0), field_to_decimal(&point.x.c1)],
+            y: [field_to_decimal(&point.y.c0), field_to_decimal(&point.y.c1)],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BLSPublicInputs {
-    pub message_hash: String,
-    pub public_key_x: String,
-    pub public_key_y: String,
+    /// H(m) già mappato su G1
+    pub message_hash: G1PointInput,
+    pub public_key: G2PointInput,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BLSPrivateInputs {
-    pub signature_x: String,
-    pub signature_y: String,
+    pub signature: G1PointInput,
 }
 
 #[derive(Debug, Clone)]
@@ -42,6 +253,143 @@ pub struct BLSProofInputs {
     pub private_inputs: BLSPrivateInputs,
 }
 
+/// Bit per parola negli input del circuito: una coordinata di Fq non sta nel
+/// campo scalare, quindi viaggia in parole da 128 bit little endian
+/// (due per BN254, tre per BLS12-381)
+const CIRCUIT_WORD_BITS: usize = 128;
+
+/// Coordinate di Fq → parole decimali da 128 bit, nell'ordine dei segnali
+fn fq_words<F: PrimeField>(coords: &[F]) -> Vec<String> {
+    let mask = (BigUint::from(1u8) << CIRCUIT_WORD_BITS) - 1u8;
+    let words = (F::MODULUS_BIT_SIZE as usize).div_ceil(CIRCUIT_WORD_BITS);
+    coords
+        .iter()
+        .flat_map(|c| {
+            let mut value: BigUint = (*c).into();
+            (0..words)
+                .map(|_| {
+                    let word = &value & &mask;
+                    value >>= CIRCUIT_WORD_BITS;
+                    word.to_string()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn g1_words(point: &G1Affine) -> Vec<String> {
+    fq_words(&[point.x, point.y])
+}
+
+fn g2_words(point: &G2Affine) -> Vec<String> {
+    fq_words(&[point.x.c0, point.x.c1, point.y.c0, point.y.c1])
+}
+
+impl BLSProofInputs {
+    /// Segnali di input del circuito (nome circom, valori decimali appiattiti).
+    ///
+    /// I punti vengono validati qui: il circuito controlla solo che stiano
+    /// sulla curva, l'appartenenza della chiave pubblica al sottogruppo di G2
+    /// è a carico del chiamante.
+    pub fn circuit_inputs(&self) -> Result<Vec<(&'static str, Vec<String>)>, ProverError> {
+        let message_hash = self.public_inputs.message_hash.to_affine("messageHash")?;
+        let public_key = self.public_inputs.public_key.to_affine("publicKey")?;
+        let signature = self.private_inputs.signature.to_affine("signature")?;
+        Ok(vec![
+            ("messageHash", g1_words(&message_hash)),
+            ("publicKey", g2_words(&public_key)),
+            ("signature", g1_words(&signature)),
+        ])
+    }
+
+    /// Segnali pubblici attesi nella prova (come public.json di snarkjs)
+    pub fn public_signals(&self) -> Result<Vec<String>, ProverError> {
+        let message_hash = self.public_inputs.message_hash.to_affine("messageHash")?;
+        let public_key = self.public_inputs.public_key.to_affine("publicKey")?;
+        Ok([g1_words(&message_hash), g2_words(&public_key)].concat())
+    }
+}
+
+/// Input di una prova, per modalità di verifica. Il circuito (e quindi la
+/// zkey) dipende dalla modalità: vedi [`ProverConfig::circuit_name`].
+#[derive(Debug, Clone)]
+pub enum ProofInputs {
+    /// bls_verify.circom: firma in G1 e chiave pubblica in G2 su BN254
+    Bn254(BLSProofInputs),
+    /// bls12_381_verify.circom: chiave pubblica in G1 e firma in G2 su BLS12-381
+    Bls12381(Bls12ProofInputs),
+    /// bls_aggregate_verify.circom: firma aggregata di un comitato su BN254
+    Committee(CommitteeProofInputs),
+}
+
+impl ProofInputs {
+    /// Segnali di input del circuito della modalità (nome circom, valori decimali)
+    pub fn circuit_inputs(&self) -> Result<Vec<(&'static str, Vec<String>)>, ProverError> {
+        match self {
+            ProofInputs::Bn254(inputs) => inputs.circuit_inputs(),
+            ProofInputs::Bls12381(inputs) => inputs.circuit_inputs(),
+            ProofInputs::Committee(inputs) => inputs.circuit_inputs(),
+        }
+    }
+
+    /// Come [`Self::circuit_inputs`], controllando che gli input abbiano la
+    /// forma del circuito compilato: un comitato deve avere esattamente le
+    /// [`ProverConfig::committee_size`] chiavi di bls_aggregate_verify
+    pub fn circuit_inputs_for(
+        &self,
+        config: &ProverConfig,
+    ) -> Result<Vec<(&'static str, Vec<String>)>, ProverError> {
+        if let ProofInputs::Committee(inputs) = self {
+            let size = inputs.private_inputs.public_keys.len();
+            if size != config.committee_size() {
+                return Err(ProverError::InvalidInput(format!(
+                    "committee has {} keys, the circuit is compiled for N = {}",
+                    size,
+                    config.committee_size()
+                )));
+            }
+        }
+        self.circuit_inputs()
+    }
+
+    /// Segnali pubblici attesi nella prova (come public.json di snarkjs)
+    pub fn public_signals(&self) -> Result<Vec<String>, ProverError> {
+        match self {
+            ProofInputs::Bn254(inputs) => inputs.public_signals(),
+            ProofInputs::Bls12381(inputs) => inputs.public_signals(),
+            ProofInputs::Committee(inputs) => inputs.public_signals(),
+        }
+    }
+
+    /// Verifica nativa della firma della modalità, per scartare input non
+    /// validi prima del proving
+    pub fn check_signature(&self) -> Result<(), ProverError> {
+        match self {
+            ProofInputs::Bn254(inputs) => inputs.check_signature(),
+            ProofInputs::Bls12381(inputs) => inputs.check_signature(),
+            ProofInputs::Committee(inputs) => inputs.check_signature(),
+        }
+    }
+}
+
+impl From<BLSProofInputs> for ProofInputs {
+    fn from(inputs: BLSProofInputs) -> Self {
+        ProofInputs::Bn254(inputs)
+    }
+}
+
+impl From<Bls12ProofInputs> for ProofInputs {
+    fn from(inputs: Bls12ProofInputs) -> Self {
+        ProofInputs::Bls12381(inputs)
+    }
+}
+
+impl From<CommitteeProofInputs> for ProofInputs {
+    fn from(inputs: CommitteeProofInputs) -> Self {
+        ProofInputs::Committee(inputs)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProofResult {
     pub proof: Vec<u8>,
@@ -66,136 +414,563 @@ pub struct ProofStats {
     pub num_constraints: usize,
 }
 
+// ============================================================================
+// VALIDAZIONE - Elementi di campo e punti di curva
+// ============================================================================
+
+/// Parsa un intero decimale non negativo (solo cifre, come scritto da snarkjs)
+fn parse_decimal(value: &str, field: &str) -> Result<BigUint, ProverError> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ProverError::InvalidFieldElement {
+            field: field.to_string(),
+            reason: format!("not a decimal integer: {:?}", value),
+        });
+    }
+    BigUint::parse_bytes(value.as_bytes(), 10).ok_or_else(|| ProverError::InvalidFieldElement {
+        field: field.to_string(),
+        reason: format!("not a decimal integer: {:?}", value),
+    })
+}
+
+/// Converte in elemento di campo rifiutando valori >= modulo (nessuna riduzione)
+fn field_from_biguint<F: PrimeField>(value: BigUint, field: &str) -> Result<F, ProverError> {
+    let modulus: BigUint = F::MODULUS.into();
+    if value >= modulus {
+        return Err(ProverError::InvalidFieldElement {
+            field: field.to_string(),
+            reason: "not smaller than the field modulus".to_string(),
+        });
+    }
+    Ok(F::from(value))
+}
+
+/// Parsa una coordinata decimale di Fq
+fn parse_fq(value: &str, field: &str) -> Result<Fq, ProverError> {
+    field_from_biguint(parse_decimal(value, field)?, field)
+}
+
+/// Costruisce un punto G1 verificando che sia sulla curva e nel sottogruppo
+fn checked_g1(x: Fq, y: Fq, field: &str) -> Result<G1Affine, ProverError> {
+    let point = G1Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(ProverError::InvalidPoint {
+            field: field.to_string(),
+            reason: "not on the BN254 G1 curve".to_string(),
+        });
+    }
+    // G1 di BN254 ha cofattore 1: il controllo è banale ma lo manteniamo esplicito
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(ProverError::InvalidPoint {
+            field: field.to_string(),
+            reason: "not in the prime-order subgroup".to_string(),
+        });
+    }
+    Ok(point)
+}
+
+/// Costruisce un punto G2 verificando che sia sulla curva e nel sottogruppo
+fn checked_g2(x: Fq2, y: Fq2, field: &str) -> Result<G2Affine, ProverError> {
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(ProverError::InvalidPoint {
+            field: field.to_string(),
+            reason: "not on the BN254 G2 twist".to_string(),
+        });
+    }
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(ProverError::InvalidPoint {
+            field: field.to_string(),
+            reason: "not in the prime-order subgroup".to_string(),
+        });
+    }
+    Ok(point)
+}
+
 // ============================================================================
 // ZKEY PARSER - Legge il formato snarkjs
 // ============================================================================
 
+/// Magic number "zkey" letto come u32 little endian
+const ZKEY_MAGIC: u32 = 0x79656b7a;
+
+/// Identificativo del protocollo Groth16 nella sezione header
+const ZKEY_PROTOCOL_GROTH16: u32 = 1;
+
+// Sezioni del file zkey (vedi snarkjs/src/zkey_utils.js)
+const SECTION_HEADER: u32 = 1;
+const SECTION_GROTH16_HEADER: u32 = 2;
+const SECTION_IC: u32 = 3;
+const SECTION_COEFFS: u32 = 4;
+const SECTION_POINTS_A: u32 = 5;
+const SECTION_POINTS_B1: u32 = 6;
+const SECTION_POINTS_B2: u32 = 7;
+const SECTION_POINTS_C: u32 = 8;
+const SECTION_POINTS_H: u32 = 9;
+
+/// Dimensione serializzata di un punto G1 (X, Y)
+const ZKEY_G1_SIZE: u64 = 64;
+/// Dimensione serializzata di un punto G2 (X c0, X c1, Y c0, Y c1)
+const ZKEY_G2_SIZE: u64 = 128;
+/// Dimensione di un coefficiente: matrix, constraint, signal (u32) + valore Fr
+const ZKEY_COEFF_SIZE: u64 = 12 + 32;
+
+/// Header Groth16 (sezione 2) del file zkey
+#[derive(Debug, Clone)]
+pub struct ZkeyHeader {
+    pub n8q: u32,
+    pub q: Vec<u8>,
+    pub n8r: u32,
+    pub r: Vec<u8>,
+    pub n_vars: usize,
+    pub n_public: usize,
+    pub domain_size: usize,
+    pub alpha_g1: G1Affine,
+    pub beta_g1: G1Affine,
+    pub beta_g2: G2Affine,
+    pub gamma_g2: G2Affine,
+    pub delta_g1: G1Affine,
+    pub delta_g2: G2Affine,
+}
+
 /// Parser per file .zkey generati da snarkjs
-/// Il formato zkey contiene: header, groth16 params, IC, contributions
-pub struct ZkeyParser {
-    data: Vec<u8>,
-    pos: usize,
+/// Il formato zkey contiene: header, groth16 params, IC, coefficienti,
+/// punti A/B1/B2/C/H e contributions.
+///
+/// Tutti i valori sono little endian; gli elementi di campo sono salvati
+/// in forma di Montgomery (i coefficienti in forma di Montgomery doppia).
+///
+/// Il file viene letto in streaming (una zkey reale può pesare centinaia di MB):
+/// ogni lettura è controllata e le dimensioni delle sezioni sono validate
+/// contro la lunghezza del file prima di allocare.
+///
+/// Il contenitore (magic, versione, tabella delle sezioni) è quello comune ai
+/// file binari di snarkjs: [`ZkeyParser::binfile`] lo usa per i .ptau.
+pub struct ZkeyParser<R: Read + Seek = BufReader<File>> {
+    reader: R,
+    magic: u32,
+    pos: u64,
+    file_len: u64,
+    /// (tipo, offset, dimensione) di ogni sezione
+    sections: Vec<(u32, u64, u64)>,
 }
 
-impl ZkeyParser {
-    pub fn new(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let mut file = File::open(path)?;
-        let mut data = Vec::new();
-        file.read_to_end(&mut data)?;
-        Ok(ZkeyParser { data, pos: 0 })
-    }
-
-    fn read_u32(&mut self) -> u32 {
-        let val = u32::from_le_bytes([
-            self.data[self.pos],
-            self.data[self.pos + 1],
-            self.data[self.pos + 2],
-            self.data[self.pos + 3],
-        ]);
-        self.pos += 4;
-        val
-    }
-
-    fn read_u64(&mut self) -> u64 {
-        let val = u64::from_le_bytes([
-            self.data[self.pos],
-            self.data[self.pos + 1],
-            self.data[self.pos + 2],
-            self.data[self.pos + 3],
-            self.data[self.pos + 4],
-            self.data[self.pos + 5],
-            self.data[self.pos + 6],
-            self.data[self.pos + 7],
-        ]);
-        self.pos += 8;
-        val
-    }
-
-    fn read_bytes(&mut self, n: usize) -> Vec<u8> {
-        let bytes = self.data[self.pos..self.pos + n].to_vec();
-        self.pos += n;
-        bytes
-    }
-
-    fn read_g1(&mut self) -> Result<G1Affine, Box<dyn std::error::Error>> {
-        // snarkjs usa formato uncompressed: 32 bytes X + 32 bytes Y
-        let x_bytes = self.read_bytes(32);
-        let y_bytes = self.read_bytes(32);
+impl ZkeyParser<BufReader<File>> {
+    pub fn new(path: &str) -> Result<Self, ProverError> {
+        let file = File::open(path)?;
+        Self::from_reader(BufReader::new(file))
+    }
+}
+
+impl<R: Read + Seek> ZkeyParser<R> {
+    pub fn from_reader(reader: R) -> Result<Self, ProverError> {
+        Self::binfile(reader, ZKEY_MAGIC)
+    }
+
+    /// File binario di snarkjs con magic `magic` (es. "ptau")
+    pub(crate) fn binfile(mut reader: R, magic: u32) -> Result<Self, ProverError> {
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(0))?;
+        Ok(ZkeyParser {
+            reader,
+            magic,
+            pos: 0,
+            file_len,
+            sections: Vec::new(),
+        })
+    }
+
+    pub(crate) fn read_array<const N: usize>(&mut self) -> Result<[u8; N], ProverError> {
+        let mut buf = [0u8; N];
+        self.reader.read_exact(&mut buf).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                ProverError::ZkeyTruncated {
+                    offset: self.pos,
+                    needed: N as u64,
+                }
+            } else {
+                ProverError::Io(e)
+            }
+        })?;
+        self.pos += N as u64;
+        Ok(buf)
+    }
+
+    pub(crate) fn read_u32(&mut self) -> Result<u32, ProverError> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
 
-        let x = Fq::from_be_bytes_mod_order(&x_bytes);
-        let y = Fq::from_be_bytes_mod_order(&y_bytes);
+    fn read_u64(&mut self) -> Result<u64, ProverError> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_bigint(&mut self) -> Result<BigInteger256, ProverError> {
+        // 32 bytes little endian -> 4 limb u64
+        let mut limbs = [0u64; 4];
+        for limb in limbs.iter_mut() {
+            *limb = self.read_u64()?;
+        }
+        Ok(BigInteger256::new(limbs))
+    }
+
+    fn read_fq(&mut self) -> Result<Fq, ProverError> {
+        let offset = self.pos;
+        let bigint = self.read_bigint()?;
+        // Un valore in forma di Montgomery deve comunque essere < q
+        if bigint >= Fq::MODULUS {
+            return Err(ProverError::InvalidFieldElement {
+                field: format!("zkey coordinate at offset {}", offset),
+                reason: "not smaller than the base field modulus".to_string(),
+            });
+        }
+        // I punti sono già in forma di Montgomery: Fq::new moltiplicherebbe per R
+        Ok(Fq::new_unchecked(bigint))
+    }
+
+    fn read_fr_coeff(&mut self) -> Result<Fr, ProverError> {
+        // snarkjs salva i coefficienti moltiplicati per R^2: dividiamo una volta per R
+        Ok(Fr::new_unchecked(Fr::new_unchecked(self.read_bigint()?).into_bigint()))
+    }
+
+    fn read_g1(&mut self) -> Result<G1Affine, ProverError> {
+        let offset = self.pos;
+        // snarkjs usa formato uncompressed: 32 bytes X + 32 bytes Y
+        let x = self.read_fq()?;
+        let y = self.read_fq()?;
 
-        // Costruisci il punto G1
-        let point = G1Affine::new(x, y);
-        Ok(point)
+        // Il punto all'infinito è codificato come (0, 0)
+        if x.is_zero() && y.is_zero() {
+            return Ok(G1Affine::identity());
+        }
+        checked_g1(x, y, &format!("zkey G1 point at offset {}", offset))
     }
 
-    fn read_g2(&mut self) -> Result<G2Affine, Box<dyn std::error::Error>> {
+    fn read_g2(&mut self) -> Result<G2Affine, ProverError> {
+        let offset = self.pos;
         // G2 ha coordinate in Fq2, quindi 64 bytes per X e 64 per Y
         // Ogni Fq2 = c0 + c1 * u, dove c0 e c1 sono Fq (32 bytes ciascuno)
-        let x_c0_bytes = self.read_bytes(32);
-        let x_c1_bytes = self.read_bytes(32);
-        let y_c0_bytes = self.read_bytes(32);
-        let y_c1_bytes = self.read_bytes(32);
-
-        let x_c0 = Fq::from_be_bytes_mod_order(&x_c0_bytes);
-        let x_c1 = Fq::from_be_bytes_mod_order(&x_c1_bytes);
-        let y_c0 = Fq::from_be_bytes_mod_order(&y_c0_bytes);
-        let y_c1 = Fq::from_be_bytes_mod_order(&y_c1_bytes);
+        let x_c0 = self.read_fq()?;
+        let x_c1 = self.read_fq()?;
+        let y_c0 = self.read_fq()?;
+        let y_c1 = self.read_fq()?;
 
         let x = Fq2::new(x_c0, x_c1);
         let y = Fq2::new(y_c0, y_c1);
 
-        let point = G2Affine::new(x, y);
-        Ok(point)
+        if x.is_zero() && y.is_zero() {
+            return Ok(G2Affine::identity());
+        }
+        checked_g2(x, y, &format!("zkey G2 point at offset {}", offset))
     }
 
-    /// Parsa il file zkey e restituisce ProvingKey e VerifyingKey
-    pub fn parse(&mut self) -> Result<(ProvingKey<Bn254>, VerifyingKey<Bn254>), Box<dyn std::error::Error>> {
-        // Verifica magic number "zkey"
-        let magic = self.read_u32();
-        if magic != 0x796b657a {
-            // "zkey" in little endian
-            return Err("Invalid zkey file: wrong magic number".into());
+    /// Legge `n` punti G1 dalla sezione indicata
+    pub(crate) fn read_g1_section(
+        &mut self,
+        section_type: u32,
+        n: usize,
+    ) -> Result<Vec<G1Affine>, ProverError> {
+        self.seek_section(section_type, n as u64 * ZKEY_G1_SIZE)?;
+        let mut points = Vec::with_capacity(n);
+        for _ in 0..n {
+            points.push(self.read_g1()?);
+        }
+        Ok(points)
+    }
+
+    /// Legge `n` punti G2 dalla sezione indicata
+    pub(crate) fn read_g2_section(
+        &mut self,
+        section_type: u32,
+        n: usize,
+    ) -> Result<Vec<G2Affine>, ProverError> {
+        self.seek_section(section_type, n as u64 * ZKEY_G2_SIZE)?;
+        let mut points = Vec::with_capacity(n);
+        for _ in 0..n {
+            points.push(self.read_g2()?);
+        }
+        Ok(points)
+    }
+
+    /// Legge magic, versione e tabella delle sezioni (una sola volta)
+    fn read_section_table(&mut self) -> Result<(), ProverError> {
+        if !self.sections.is_empty() {
+            return Ok(());
+        }
+
+        self.reader.seek(SeekFrom::Start(0))?;
+        self.pos = 0;
+
+        // Verifica magic number ("zkey" per le zkey)
+        let magic = self.read_u32()?;
+        if magic != self.magic {
+            return Err(ProverError::ZkeyBadMagic(magic));
         }
 
-        let version = self.read_u32();
+        let version = self.read_u32()?;
         println!("[ZKEY] Version: {}", version);
 
-        let num_sections = self.read_u32();
+        let num_sections = self.read_u32()?;
         println!("[ZKEY] Sections: {}", num_sections);
 
-        // Leggi section headers
-        let mut sections: Vec<(u32, u64, u64)> = Vec::new();
+        // Leggi section headers, verificando che ogni sezione stia nel file
         for _ in 0..num_sections {
-            let section_type = self.read_u32();
-            let section_size = self.read_u64();
-            let section_pos = self.pos as u64;
-            sections.push((section_type, section_pos, section_size));
-            self.pos += section_size as usize;
+            let section_type = self.read_u32()?;
+            let section_size = self.read_u64()?;
+            let section_pos = self.pos;
+
+            let section_end = section_pos.saturating_add(section_size);
+            if section_end > self.file_len {
+                return Err(ProverError::ZkeyTruncated {
+                    offset: self.file_len,
+                    needed: section_end - self.file_len,
+                });
+            }
+
+            self.sections.push((section_type, section_pos, section_size));
+            self.pos = self.reader.seek(SeekFrom::Start(section_end))?;
         }
 
-        // Section 2: Groth16 specific data
-        let groth16_section = sections
+        Ok(())
+    }
+
+    /// Posiziona il cursore all'inizio della sezione richiesta,
+    /// verificando che contenga almeno `needed` bytes
+    pub(crate) fn seek_section(&mut self, section_type: u32, needed: u64) -> Result<u64, ProverError> {
+        self.read_section_table()?;
+
+        let (_, section_pos, section_size) = *self
+            .sections
             .iter()
-            .find(|(t, _, _)| *t == 2)
-            .ok_or("Missing Groth16 section")?;
+            .find(|(t, _, _)| *t == section_type)
+            .ok_or(ProverError::ZkeyMissingSection(section_type))?;
+
+        if needed > section_size {
+            return Err(ProverError::ZkeyTruncated {
+                offset: section_pos + section_size,
+                needed: needed - section_size,
+            });
+        }
+
+        self.pos = self.reader.seek(SeekFrom::Start(section_pos))?;
+        Ok(section_size)
+    }
+
+    /// Legge l'header Groth16 (sezioni 1 e 2)
+    pub fn header(&mut self) -> Result<ZkeyHeader, ProverError> {
+        self.seek_section(SECTION_HEADER, 4)?;
+        let protocol = self.read_u32()?;
+        if protocol != ZKEY_PROTOCOL_GROTH16 {
+            return Err(ProverError::ZkeyFormat(format!(
+                "unsupported protocol {}",
+                protocol
+            )));
+        }
+
+        // n8q, q, n8r, r, nVars, nPublic, domainSize, 3 punti G1 e 3 punti G2
+        self.seek_section(
+            SECTION_GROTH16_HEADER,
+            4 + 32 + 4 + 32 + 12 + 3 * ZKEY_G1_SIZE + 3 * ZKEY_G2_SIZE,
+        )?;
+
+        // Il prover supporta solo BN254: verifica che i moduli coincidano
+        let n8q = self.read_u32()?;
+        if n8q != 32 {
+            return Err(ProverError::ZkeyFormat(format!(
+                "unsupported base field size {}",
+                n8q
+            )));
+        }
+        let q = self.read_array::<32>()?.to_vec();
+        if q != Fq::MODULUS.to_bytes_le() {
+            return Err(ProverError::ZkeyFormat(
+                "base field is not BN254".to_string(),
+            ));
+        }
+
+        let n8r = self.read_u32()?;
+        if n8r != 32 {
+            return Err(ProverError::ZkeyFormat(format!(
+                "unsupported scalar field size {}",
+                n8r
+            )));
+        }
+        let r = self.read_array::<32>()?.to_vec();
+        if r != Fr::MODULUS.to_bytes_le() {
+            return Err(ProverError::ZkeyFormat(
+                "scalar field is not BN254".to_string(),
+            ));
+        }
+
+        let n_vars = self.read_u32()? as usize;
+        let n_public = self.read_u32()? as usize;
+        let domain_size = self.read_u32()? as usize;
+
+        if n_public >= n_vars {
+            return Err(ProverError::ZkeyFormat(format!(
+                "nPublic ({}) must be smaller than nVars ({})",
+                n_public, n_vars
+            )));
+        }
+        if !domain_size.is_power_of_two() {
+            return Err(ProverError::ZkeyFormat(format!(
+                "domainSize {} is not a power of two",
+                domain_size
+            )));
+        }
+
+        let alpha_g1 = self.read_g1()?;
+        let beta_g1 = self.read_g1()?;
+        let beta_g2 = self.read_g2()?;
+        let gamma_g2 = self.read_g2()?;
+        let delta_g1 = self.read_g1()?;
+        let delta_g2 = self.read_g2()?;
+
+        println!(
+            "[ZKEY] nVars: {}, nPublic: {}, domainSize: {}",
+            n_vars, n_public, domain_size
+        );
+
+        Ok(ZkeyHeader {
+            n8q,
+            q,
+            n8r,
+            r,
+            n_vars,
+            n_public,
+            domain_size,
+            alpha_g1,
+            beta_g1,
+            beta_g2,
+            gamma_g2,
+            delta_g1,
+            delta_g2,
+        })
+    }
+
+    /// Parsa il file zkey e restituisce ProvingKey e VerifyingKey
+    pub fn parse(&mut self) -> Result<(ProvingKey<Bn254>, VerifyingKey<Bn254>), ProverError> {
+        let header = self.header()?;
+
+        // Section 3: IC (nPublic + 1 punti G1)
+        let gamma_abc_g1 = self.read_g1_section(SECTION_IC, header.n_public + 1)?;
+
+        // Sections 5-9: query del proving key
+        let a_query = self.read_g1_section(SECTION_POINTS_A, header.n_vars)?;
+        let b_g1_query = self.read_g1_section(SECTION_POINTS_B1, header.n_vars)?;
+        let b_g2_query = self.read_g2_section(SECTION_POINTS_B2, header.n_vars)?;
+
+        // La sezione C contiene solo le variabili private
+        let l_query =
+            self.read_g1_section(SECTION_POINTS_C, header.n_vars - header.n_public - 1)?;
+
+        let h_query = self.read_g1_section(SECTION_POINTS_H, header.domain_size)?;
+
+        let vk = VerifyingKey {
+            alpha_g1: header.alpha_g1,
+            beta_g2: header.beta_g2,
+            gamma_g2: header.gamma_g2,
+            delta_g2: header.delta_g2,
+            gamma_abc_g1,
+        };
+
+        let pk = ProvingKey {
+            vk: vk.clone(),
+            beta_g1: header.beta_g1,
+            delta_g1: header.delta_g1,
+            a_query,
+            b_g1_query,
+            b_g2_query,
+            h_query,
+            l_query,
+        };
+
+        Ok((pk, vk))
+    }
+
+    /// Legge la sezione dei coefficienti (4) e ricostruisce le matrici A e B.
+    /// La matrice C non è salvata da snarkjs: viene calcolata dal witness.
+    pub fn constraint_matrices(&mut self) -> Result<ConstraintMatrices<Fr>, ProverError> {
+        let header = self.header()?;
+
+        let section_size = self.seek_section(SECTION_COEFFS, 4)?;
+        let num_coeffs = self.read_u32()?;
+        if 4 + num_coeffs as u64 * ZKEY_COEFF_SIZE > section_size {
+            return Err(ProverError::ZkeyTruncated {
+                offset: self.pos,
+                needed: 4 + num_coeffs as u64 * ZKEY_COEFF_SIZE - section_size,
+            });
+        }
 
-        self.pos = groth16_section.1 as usize;
+        // I coefficienti si raccolgono sparsi: le righe si allocano solo a fine
+        // lettura, quando il numero di vincoli è noto
+        let mut entries: [Vec<(usize, Fr, usize)>; 2] = [Vec::new(), Vec::new()];
+        let mut max_constraint_index = 0usize;
+        for _ in 0..num_coeffs {
+            let matrix = self.read_u32()? as usize;
+            let constraint = self.read_u32()? as usize;
+            let signal = self.read_u32()? as usize;
+            let value = self.read_fr_coeff()?;
+
+            if matrix > 1 || constraint >= header.domain_size || signal >= header.n_vars {
+                return Err(ProverError::ZkeyFormat(format!(
+                    "invalid coefficient: matrix {} constraint {} signal {}",
+                    matrix, constraint, signal
+                )));
+            }
+
+            max_constraint_index = max_constraint_index.max(constraint);
+            entries[matrix].push((constraint, value, signal));
+        }
 
-        // Parse Groth16 parameters
-        // Questo è un parsing semplificato - il formato completo è più complesso
+        // snarkjs aggiunge nPublic + 1 vincoli per gli input pubblici (gli
+        // indici da nConstraints a nConstraints + nPublic): arkworks li
+        // aggiunge da solo, quindi vanno rimossi
+        let num_constraints = max_constraint_index.saturating_sub(header.n_public);
+
+        // num_constraints < domainSize, e la sezione H deve contenere davvero
+        // domainSize punti: un header falsificato non può far allocare più
+        // righe di quante ne giustifichi la lunghezza del file
+        self.seek_section(SECTION_POINTS_H, header.domain_size as u64 * ZKEY_G1_SIZE)?;
+
+        let matrices = entries.map(|entries| {
+            let mut rows = vec![Vec::new(); num_constraints];
+            for (constraint, value, signal) in entries {
+                if constraint < num_constraints {
+                    rows[constraint].push((value, signal));
+                }
+            }
+            rows
+        });
 
-        // Per ora, usiamo un approccio alternativo: caricare da verification_key.json
-        Err("Direct zkey parsing not fully implemented - use verification_key.json instead".into())
+        let [a, b] = matrices;
+        let a_num_non_zero = a.iter().map(|lc| lc.len()).sum();
+        let b_num_non_zero = b.iter().map(|lc| lc.len()).sum();
+
+        // Il wire 0 (costante 1) è una variabile di istanza
+        Ok(ConstraintMatrices {
+            num_instance_variables: header.n_public + 1,
+            num_witness_variables: header.n_vars - header.n_public - 1,
+            num_constraints,
+            a_num_non_zero,
+            b_num_non_zero,
+            c_num_non_zero: 0,
+            a,
+            b,
+            c: Vec::new(),
+        })
     }
 }
 
+/// Esponente m = 2z(6z² + 3z + 1) introdotto dall'esponenziazione finale di arkworks
+fn final_exponent_multiplier() -> BigUint {
+    let z = BigUint::from(<ark_bn254::Config as BnConfig>::X[0]);
+    BigUint::from(2u32) * &z * (BigUint::from(6u32) * &z * &z + BigUint::from(3u32) * &z + 1u32)
+}
+
 // ============================================================================
 // VERIFICATION KEY LOADER - Carica da verification_key.json di snarkjs
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SnarkjsVerificationKey {
     pub protocol: String,
     pub curve: String,
@@ -211,66 +986,158 @@ pub struct SnarkjsVerificationKey {
 }
 
 impl SnarkjsVerificationKey {
-    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn load(path: &str) -> Result<Self, ProverError> {
         let file = File::open(path)?;
         let reader = BufReader::new(file);
         let vk: SnarkjsVerificationKey = serde_json::from_reader(reader)?;
+        vk.validate_metadata()?;
         Ok(vk)
     }
 
-    fn parse_g1_point(coords: &[String]) -> Result<G1Affine, Box<dyn std::error::Error>> {
-        if coords.len() < 2 {
-            return Err("Invalid G1 point".into());
+    /// Verifica protocollo, curva e numero di punti IC rispetto a nPublic
+    pub fn validate_metadata(&self) -> Result<(), ProverError> {
+        if self.protocol != "groth16" {
+            return Err(ProverError::InvalidVerifyingKey(format!(
+                "unsupported protocol {:?}, expected \"groth16\"",
+                self.protocol
+            )));
+        }
+        if self.curve != "bn128" {
+            return Err(ProverError::InvalidVerifyingKey(format!(
+                "unsupported curve {:?}, expected \"bn128\"",
+                self.curve
+            )));
+        }
+        if self.ic.len() != self.n_public + 1 {
+            return Err(ProverError::InvalidVerifyingKey(format!(
+                "IC has {} points but nPublic is {} (expected {})",
+                self.ic.len(),
+                self.n_public,
+                self.n_public + 1
+            )));
+        }
+        Ok(())
+    }
+
+    /// Parsa vk_alphabeta_12: [[c0.c0, c0.c1, c0.c2], [c1.c0, c1.c1, c1.c2]]
+    /// dove ogni elemento è una coppia Fq2 [c0, c1]
+    fn parse_fq12(coords: &[Vec<Vec<String>>], field: &str) -> Result<Fq12, ProverError> {
+        if coords.len() != 2 || coords.iter().any(|c| c.len() != 3 || c.iter().any(|e| e.len() != 2)) {
+            return Err(ProverError::InvalidFieldElement {
+                field: field.to_string(),
+                reason: "expected a 2x3x2 array".to_string(),
+            });
         }
 
-        let x_big = BigInt::parse_bytes(coords[0].as_bytes(), 10)
-            .ok_or("Invalid X coordinate")?;
-        let y_big = BigInt::parse_bytes(coords[1].as_bytes(), 10)
-            .ok_or("Invalid Y coordinate")?;
+        let fq2 = |i: usize, j: usize| -> Result<Fq2, ProverError> {
+            Ok(Fq2::new(
+                parse_fq(&coords[i][j][0], &format!("{}[{}][{}][0]", field, i, j))?,
+                parse_fq(&coords[i][j][1], &format!("{}[{}][{}][1]", field, i, j))?,
+            ))
+        };
+        let fq6 = |i: usize| -> Result<Fq6, ProverError> {
+            Ok(Fq6::new(fq2(i, 0)?, fq2(i, 1)?, fq2(i, 2)?))
+        };
+
+        Ok(Fq12::new(fq6(0)?, fq6(1)?))
+    }
 
-        let x = Fq::from_be_bytes_mod_order(&x_big.to_bytes_be().1);
-        let y = Fq::from_be_bytes_mod_order(&y_big.to_bytes_be().1);
+    /// Confronta vk_alphabeta_12 con e(alpha, beta) calcolato in Rust.
+    ///
+    /// arkworks calcola e(alpha, beta)^m con m = 2z(6z² + 3z + 1) (esponenziazione
+    /// finale di Fuentes-Castañeda et al.), mentre snarkjs può esportare il
+    /// pairing "esatto": m è coprimo con r, quindi accettiamo entrambe le forme.
+    fn check_alphabeta(&self, alpha_g1: &G1Affine, beta_g2: &G2Affine) -> Result<(), ProverError> {
+        let expected = Bn254::pairing(*alpha_g1, *beta_g2).0;
+        let declared = Self::parse_fq12(&self.vk_alphabeta_12, "vk_alphabeta_12")?;
+
+        if declared == expected || declared.pow(final_exponent_multiplier().to_u64_digits()) == expected {
+            return Ok(());
+        }
 
-        Ok(G1Affine::new(x, y))
+        Err(ProverError::InvalidVerifyingKey(
+            "vk_alphabeta_12 does not match e(vk_alpha_1, vk_beta_2)".to_string(),
+        ))
     }
 
-    fn parse_g2_point(coords: &[Vec<String>]) -> Result<G2Affine, Box<dyn std::error::Error>> {
-        if coords.len() < 2 || coords[0].len() < 2 || coords[1].len() < 2 {
-            return Err("Invalid G2 point".into());
+    /// Parsa un punto G1 `[X, Y, Z]` in coordinate Jacobiane (come ffjavascript
+    /// e arkworks): x = X/Z², y = Y/Z³. Z = 0 indica il punto all'infinito,
+    /// se Z manca il punto è considerato affine.
+    fn parse_g1_point(coords: &[String], field: &str) -> Result<G1Affine, ProverError> {
+        if coords.len() < 2 || coords.len() > 3 {
+            return Err(ProverError::InvalidPoint {
+                field: field.to_string(),
+                reason: format!("expected 2 or 3 coordinates, got {}", coords.len()),
+            });
         }
 
-        // G2 point: [[x_c0, x_c1], [y_c0, y_c1]]
-        let x_c0_big = BigInt::parse_bytes(coords[0][0].as_bytes(), 10)
-            .ok_or("Invalid X c0")?;
-        let x_c1_big = BigInt::parse_bytes(coords[0][1].as_bytes(), 10)
-            .ok_or("Invalid X c1")?;
-        let y_c0_big = BigInt::parse_bytes(coords[1][0].as_bytes(), 10)
-            .ok_or("Invalid Y c0")?;
-        let y_c1_big = BigInt::parse_bytes(coords[1][1].as_bytes(), 10)
-            .ok_or("Invalid Y c1")?;
+        let x = parse_fq(&coords[0], &format!("{}.x", field))?;
+        let y = parse_fq(&coords[1], &format!("{}.y", field))?;
+
+        let (x, y) = match coords.get(2) {
+            Some(z) => {
+                let z = parse_fq(z, &format!("{}.z", field))?;
+                if z.is_zero() {
+                    return Ok(G1Affine::identity());
+                }
+                let affine = G1Projective::new_unchecked(x, y, z).into_affine();
+                (affine.x, affine.y)
+            }
+            None => (x, y),
+        };
 
-        let x_c0 = Fq::from_be_bytes_mod_order(&x_c0_big.to_bytes_be().1);
-        let x_c1 = Fq::from_be_bytes_mod_order(&x_c1_big.to_bytes_be().1);
-        let y_c0 = Fq::from_be_bytes_mod_order(&y_c0_big.to_bytes_be().1);
-        let y_c1 = Fq::from_be_bytes_mod_order(&y_c1_big.to_bytes_be().1);
+        checked_g1(x, y, field)
+    }
 
-        let x = Fq2::new(x_c0, x_c1);
-        let y = Fq2::new(y_c0, y_c1);
+    /// Parsa un punto G2 `[[X_c0, X_c1], [Y_c0, Y_c1], [Z_c0, Z_c1]]` in
+    /// coordinate Jacobiane. Z = ["0", "0"] indica il punto all'infinito.
+    fn parse_g2_point(coords: &[Vec<String>], field: &str) -> Result<G2Affine, ProverError> {
+        if coords.len() < 2 || coords.len() > 3 || coords.iter().any(|c| c.len() != 2) {
+            return Err(ProverError::InvalidPoint {
+                field: field.to_string(),
+                reason: "expected 2 or 3 pairs of coordinates".to_string(),
+            });
+        }
+
+        let parse_fq2 = |pair: &[String], name: &str| -> Result<Fq2, ProverError> {
+            Ok(Fq2::new(
+                parse_fq(&pair[0], &format!("{}.{}.c0", field, name))?,
+                parse_fq(&pair[1], &format!("{}.{}.c1", field, name))?,
+            ))
+        };
 
-        Ok(G2Affine::new(x, y))
+        let x = parse_fq2(&coords[0], "x")?;
+        let y = parse_fq2(&coords[1], "y")?;
+
+        let (x, y) = match coords.get(2) {
+            Some(z) => {
+                let z = parse_fq2(z, "z")?;
+                if z.is_zero() {
+                    return Ok(G2Affine::identity());
+                }
+                let affine = G2Projective::new_unchecked(x, y, z).into_affine();
+                (affine.x, affine.y)
+            }
+            None => (x, y),
+        };
+
+        checked_g2(x, y, field)
     }
 
-    pub fn to_arkworks_vk(&self) -> Result<VerifyingKey<Bn254>, Box<dyn std::error::Error>> {
-        let alpha_g1 = Self::parse_g1_point(&self.vk_alpha_1)?;
-        let beta_g2 = Self::parse_g2_point(&self.vk_beta_2)?;
-        let gamma_g2 = Self::parse_g2_point(&self.vk_gamma_2)?;
-        let delta_g2 = Self::parse_g2_point(&self.vk_delta_2)?;
+    pub fn to_arkworks_vk(&self) -> Result<VerifyingKey<Bn254>, ProverError> {
+        let alpha_g1 = Self::parse_g1_point(&self.vk_alpha_1, "vk_alpha_1")?;
+        let beta_g2 = Self::parse_g2_point(&self.vk_beta_2, "vk_beta_2")?;
+        let gamma_g2 = Self::parse_g2_point(&self.vk_gamma_2, "vk_gamma_2")?;
+        let delta_g2 = Self::parse_g2_point(&self.vk_delta_2, "vk_delta_2")?;
 
         let mut gamma_abc_g1: Vec<G1Affine> = Vec::new();
-        for ic_point in &self.ic {
-            gamma_abc_g1.push(Self::parse_g1_point(ic_point)?);
+        for (i, ic_point) in self.ic.iter().enumerate() {
+            gamma_abc_g1.push(Self::parse_g1_point(ic_point, &format!("IC[{}]", i))?);
         }
 
+        self.validate_metadata()?;
+        self.check_alphabeta(&alpha_g1, &beta_g2)?;
+
         Ok(VerifyingKey {
             alpha_g1,
             beta_g2,
@@ -281,6 +1148,47 @@ impl SnarkjsVerificationKey {
     }
 }
 
+// ============================================================================
+// PROOF LOADER - Carica proof.json di snarkjs
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnarkjsProof {
+    pub pi_a: Vec<String>,
+    pub pi_b: Vec<Vec<String>>,
+    pub pi_c: Vec<String>,
+    #[serde(default)]
+    pub protocol: String,
+    #[serde(default)]
+    pub curve: String,
+}
+
+impl SnarkjsProof {
+    pub fn from_json(proof_json: &str) -> Result<Self, ProverError> {
+        Ok(serde_json::from_str(proof_json)?)
+    }
+
+    pub fn to_arkworks_proof(&self) -> Result<Proof<Bn254>, ProverError> {
+        Ok(Proof {
+            a: SnarkjsVerificationKey::parse_g1_point(&self.pi_a, "pi_a")?,
+            b: SnarkjsVerificationKey::parse_g2_point(&self.pi_b, "pi_b")?,
+            c: SnarkjsVerificationKey::parse_g1_point(&self.pi_c, "pi_c")?,
+        })
+    }
+}
+
+/// Converte i public inputs decimali (public.json) in elementi di Fr
+pub fn parse_public_inputs(public_inputs: &[String]) -> Result<Vec<Fr>, ProverError> {
+    public_inputs
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let field = format!("public input [{}]", i);
+            field_from_biguint(parse_decimal(s, &field)?, &field)
+        })
+        .collect()
+}
+
 // ============================================================================
 // SNARKJS PROOF GENERATOR - Usa snarkjs come backend
 // ============================================================================
@@ -288,46 +1196,68 @@ impl SnarkjsVerificationKey {
 /// Genera prove usando snarkjs come processo esterno.
 /// Questo garantisce 100% compatibilità con Verifier.sol.
 pub struct SnarkjsProver {
-    circuit_path: String,
-    wasm_path: String,
-    zkey_path: String,
-    vk_path: String,
+    config: ProverConfig,
     verifying_key: Option<VerifyingKey<Bn254>>,
+    prepared_vk: Option<PreparedVerifyingKey<Bn254>>,
+    stats: backend::StatsRecorder,
 }
 
 impl SnarkjsProver {
     pub fn new(circuit_dir: &str) -> Self {
-        let build_dir = format!("{}/build", circuit_dir);
+        Self::from_config(ProverConfig::new(circuit_dir))
+    }
+
+    pub fn from_config(config: ProverConfig) -> Self {
         SnarkjsProver {
-            circuit_path: circuit_dir.to_string(),
-            wasm_path: format!("{}/bls_verify_js/bls_verify.wasm", build_dir),
-            zkey_path: format!("{}/bls_verify_final.zkey", build_dir),
-            vk_path: format!("{}/verification_key.json", build_dir),
+            config,
             verifying_key: None,
+            prepared_vk: None,
+            stats: Default::default(),
         }
     }
 
-    pub fn setup(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn config(&self) -> &ProverConfig {
+        &self.config
+    }
+
+    /// Imposta la directory sotto cui creare i workspace di ogni prova
+    pub fn with_temp_root(mut self, temp_root: impl Into<PathBuf>) -> Self {
+        self.config.temp_dir = Some(temp_root.into());
+        self
+    }
+
+    /// Workspace privato per una singola chiamata: nome univoco, permessi 0700,
+    /// rimosso al drop su ogni percorso di uscita (anche in caso di errore)
+    fn workspace(&self) -> Result<tempfile::TempDir, ProverError> {
+        Ok(tempfile::Builder::new()
+            .prefix("bls-prover-")
+            .tempdir_in(self.config.temp_dir())?)
+    }
+
+    pub fn setup(&mut self) -> Result<(), ProverError> {
         println!("[SETUP] Caricamento verification key da snarkjs...");
 
         // Verifica che i file esistano
-        if !Path::new(&self.wasm_path).exists() {
-            return Err(format!("WASM file not found: {}", self.wasm_path).into());
-        }
-        if !Path::new(&self.zkey_path).exists() {
-            return Err(format!("Zkey file not found: {}", self.zkey_path).into());
-        }
-        if !Path::new(&self.vk_path).exists() {
-            return Err(format!("Verification key not found: {}", self.vk_path).into());
+        for (kind, path) in [
+            ("WASM file", self.config.wasm_path()),
+            ("Witness generator", self.config.witness_generator_path()),
+            ("Zkey file", self.config.zkey_path()),
+            ("Verification key", self.config.vk_path()),
+        ] {
+            if !Path::new(&path).exists() {
+                return Err(ProverError::missing(kind, &path));
+            }
         }
 
         // Carica verification key
-        let snarkjs_vk = SnarkjsVerificationKey::load(&self.vk_path)?;
+        let snarkjs_vk = SnarkjsVerificationKey::load(&self.config.vk_path())?;
         println!("[SETUP] Protocol: {}", snarkjs_vk.protocol);
         println!("[SETUP] Curve: {}", snarkjs_vk.curve);
         println!("[SETUP] Public inputs: {}", snarkjs_vk.n_public);
 
-        self.verifying_key = Some(snarkjs_vk.to_arkworks_vk()?);
+        let vk = snarkjs_vk.to_arkworks_vk()?;
+        self.prepared_vk = Some(prepare_verifying_key(&vk));
+        self.verifying_key = Some(vk);
 
         println!("[SETUP] Completato - usando parametri snarkjs");
         Ok(())
@@ -336,116 +1266,213 @@ impl SnarkjsProver {
     /// Genera prova usando snarkjs CLI
     pub fn generate_proof(
         &self,
-        inputs: BLSProofInputs,
-    ) -> Result<(ProofResult, ProofStats), Box<dyn std::error::Error>> {
+        inputs: impl Into<ProofInputs>,
+    ) -> Result<(ProofResult, ProofStats), ProverError> {
+        self.generate_proof_cancellable(inputs, &CancelToken::new())
+    }
+
+    /// Come [`Self::generate_proof`], interrompibile tramite `cancel`.
+    /// I processi node/snarkjs in corso vengono uccisi su cancel, timeout o errore.
+    pub fn generate_proof_cancellable(
+        &self,
+        inputs: impl Into<ProofInputs>,
+        cancel: &CancelToken,
+    ) -> Result<(ProofResult, ProofStats), ProverError> {
         let start = std::time::Instant::now();
         println!("[PROVE] Generazione prova con snarkjs...");
 
-        // Crea file input temporaneo
-        let temp_dir = std::env::temp_dir();
-        let input_file = temp_dir.join("bls_input.json");
-        let witness_file = temp_dir.join("witness.wtns");
-        let proof_file = temp_dir.join("proof.json");
-        let public_file = temp_dir.join("public.json");
-
-        // Scrivi input JSON
-        let input_json = serde_json::json!({
-            "messageHash": inputs.public_inputs.message_hash,
-            "publicKeyX": inputs.public_inputs.public_key_x,
-            "publicKeyY": inputs.public_inputs.public_key_y,
-            "signatureX": inputs.private_inputs.signature_x,
-            "signatureY": inputs.private_inputs.signature_y
-        });
-
-        std::fs::write(&input_file, serde_json::to_string_pretty(&input_json)?)?;
+        let job = self.prepare_job(&inputs.into())?;
 
         // Step 1: Genera witness
         println!("[PROVE] Generazione witness...");
-        let witness_output = std::process::Command::new("node")
-            .arg(format!(
-                "{}/build/bls_verify_js/generate_witness.js",
-                self.circuit_path
-            ))
-            .arg(&self.wasm_path)
-            .arg(&input_file)
-            .arg(&witness_file)
-            .output()?;
-
-        if !witness_output.status.success() {
-            let stderr = String::from_utf8_lossy(&witness_output.stderr);
-            return Err(format!("Witness generation failed: {}", stderr).into());
-        }
+        process::run_step(
+            self.witness_command(&job)?,
+            "generate_witness.js",
+            self.config.witness_timeout(),
+            cancel,
+        )?;
 
         // Step 2: Genera prova Groth16
         println!("[PROVE] Generazione prova Groth16...");
-        let prove_output = std::process::Command::new("snarkjs")
-            .args([
-                "groth16",
-                "prove",
-                &self.zkey_path,
-                witness_file.to_str().unwrap(),
-                proof_file.to_str().unwrap(),
-                public_file.to_str().unwrap(),
-            ])
-            .output()?;
-
-        if !prove_output.status.success() {
-            let stderr = String::from_utf8_lossy(&prove_output.stderr);
-            return Err(format!("Proof generation failed: {}", stderr).into());
-        }
+        process::run_step(
+            self.prove_command(&job)?,
+            "snarkjs groth16 prove",
+            self.config.prove_timeout(),
+            cancel,
+        )?;
 
         let proving_time = start.elapsed();
         println!("[PROVE] Generato in {:?}", proving_time);
 
-        // Leggi prova e public inputs
-        let proof_json: serde_json::Value =
-            serde_json::from_str(&std::fs::read_to_string(&proof_file)?)?;
-        let public_json: Vec<String> =
-            serde_json::from_str(&std::fs::read_to_string(&public_file)?)?;
-
         // Step 3: Verifica locale
+        let (proof_str, public_json) = job.read_output()?;
         let verify_start = std::time::Instant::now();
         println!("[PROVE] Verifica locale...");
 
-        let verify_output = std::process::Command::new("snarkjs")
-            .args([
-                "groth16",
-                "verify",
-                &self.vk_path,
-                public_file.to_str().unwrap(),
-                proof_file.to_str().unwrap(),
-            ])
-            .output()?;
+        let is_valid = self.verify_proof(&proof_str, &public_json)?;
 
-        let verification_time = verify_start.elapsed();
+        job.finish(proof_str, public_json, is_valid, proving_time, verify_start.elapsed())
+    }
 
-        if !verify_output.status.success() {
-            return Err("Proof verification failed".into());
-        }
-        println!("[PROVE] Verificato in {:?}", verification_time);
+    /// Variante async di [`Self::generate_proof`]: witness e prova girano con
+    /// tokio::process, la verifica su spawn_blocking. Droppare il future
+    /// uccide il processo in corso e rimuove il workspace.
+    pub async fn generate_proof_async(
+        &self,
+        inputs: impl Into<ProofInputs>,
+    ) -> Result<(ProofResult, ProofStats), ProverError> {
+        let start = std::time::Instant::now();
+        println!("[PROVE] Generazione prova con snarkjs (async)...");
 
-        // Genera Solidity calldata
-        let calldata_output = std::process::Command::new("snarkjs")
-            .args([
-                "zkey",
-                "export",
-                "soliditycalldata",
-                public_file.to_str().unwrap(),
-                proof_file.to_str().unwrap(),
-            ])
-            .output()?;
-
-        let calldata_str = String::from_utf8_lossy(&calldata_output.stdout);
-        let solidity_calldata = parse_solidity_calldata(&calldata_str)?;
+        let job = self.prepare_job(&inputs.into())?;
 
-        // Serializza prova per compatibilità
-        let proof_bytes = serde_json::to_vec(&proof_json)?;
+        println!("[PROVE] Generazione witness...");
+        process::run_step_async(
+            self.witness_command(&job)?,
+            "generate_witness.js",
+            self.config.witness_timeout(),
+        )
+        .await?;
 
-        // Cleanup
-        let _ = std::fs::remove_file(&input_file);
-        let _ = std::fs::remove_file(&witness_file);
-        let _ = std::fs::remove_file(&proof_file);
-        let _ = std::fs::remove_file(&public_file);
+        println!("[PROVE] Generazione prova Groth16...");
+        process::run_step_async(
+            self.prove_command(&job)?,
+            "snarkjs groth16 prove",
+            self.config.prove_timeout(),
+        )
+        .await?;
+
+        let proving_time = start.elapsed();
+        println!("[PROVE] Generato in {:?}", proving_time);
+
+        let (proof_str, public_json) = job.read_output()?;
+        let verify_start = std::time::Instant::now();
+        println!("[PROVE] Verifica locale...");
+
+        let is_valid = self.verify_proof_async(&proof_str, &public_json).await?;
+
+        job.finish(proof_str, public_json, is_valid, proving_time, verify_start.elapsed())
+    }
+
+    /// Crea il workspace privato e scrive l'input del circuito
+    fn prepare_job(&self, inputs: &ProofInputs) -> Result<SnarkjsJob, ProverError> {
+        let circuit_inputs = inputs.circuit_inputs_for(&self.config)?;
+
+        // Workspace isolato: prove concorrenti non condividono file
+        let workspace = self.workspace()?;
+        let job = SnarkjsJob {
+            input_file: workspace.path().join("input.json"),
+            witness_file: workspace.path().join("witness.wtns"),
+            proof_file: workspace.path().join("proof.json"),
+            public_file: workspace.path().join("public.json"),
+            workspace,
+        };
+
+        // Scrivi input JSON
+        let input_json: serde_json::Map<String, serde_json::Value> = circuit_inputs
+            .into_iter()
+            .map(|(name, values)| (name.to_string(), serde_json::json!(values)))
+            .collect();
+
+        std::fs::write(&job.input_file, serde_json::to_string_pretty(&input_json)?)?;
+        Ok(job)
+    }
+
+    fn witness_command(&self, job: &SnarkjsJob) -> Result<std::process::Command, ProverError> {
+        let mut command = self.config.node_command()?;
+        command
+            .arg(self.config.witness_generator_path())
+            .arg(self.config.wasm_path())
+            .arg(&job.input_file)
+            .arg(&job.witness_file);
+        Ok(command)
+    }
+
+    fn prove_command(&self, job: &SnarkjsJob) -> Result<std::process::Command, ProverError> {
+        let mut command = self.config.snarkjs_command()?;
+        command
+            .args(["groth16", "prove"])
+            .arg(self.config.zkey_path())
+            .arg(&job.witness_file)
+            .arg(&job.proof_file)
+            .arg(&job.public_file);
+        Ok(command)
+    }
+
+    /// Verifica una prova usando la VK caricata (pairing check in Rust)
+    pub fn verify_proof(
+        &self,
+        proof_json: &str,
+        public_inputs: &[String],
+    ) -> Result<bool, ProverError> {
+        let pvk = self
+            .prepared_vk
+            .as_ref()
+            .ok_or(ProverError::NotInitialized)?;
+
+        verify_snarkjs_proof(pvk, proof_json, public_inputs)
+    }
+
+    /// Variante async di [`Self::verify_proof`]: il pairing gira su spawn_blocking
+    pub async fn verify_proof_async(
+        &self,
+        proof_json: &str,
+        public_inputs: &[String],
+    ) -> Result<bool, ProverError> {
+        let pvk = self
+            .prepared_vk
+            .clone()
+            .ok_or(ProverError::NotInitialized)?;
+        let proof_json = proof_json.to_string();
+        let public_inputs = public_inputs.to_vec();
+
+        tokio::task::spawn_blocking(move || verify_snarkjs_proof(&pvk, &proof_json, &public_inputs))
+            .await?
+    }
+}
+
+/// File di una singola prova snarkjs, dentro il workspace privato
+struct SnarkjsJob {
+    workspace: tempfile::TempDir,
+    input_file: PathBuf,
+    witness_file: PathBuf,
+    proof_file: PathBuf,
+    public_file: PathBuf,
+}
+
+impl SnarkjsJob {
+    /// Legge proof.json e public.json prodotti da snarkjs
+    fn read_output(&self) -> Result<(String, Vec<String>), ProverError> {
+        let proof_str = std::fs::read_to_string(&self.proof_file)?;
+        let public_json: Vec<String> =
+            serde_json::from_str(&std::fs::read_to_string(&self.public_file)?)?;
+        Ok((proof_str, public_json))
+    }
+
+    /// Costruisce il risultato e rimuove il workspace
+    fn finish(
+        self,
+        proof_str: String,
+        public_json: Vec<String>,
+        is_valid: bool,
+        proving_time: std::time::Duration,
+        verification_time: std::time::Duration,
+    ) -> Result<(ProofResult, ProofStats), ProverError> {
+        if !is_valid {
+            return Err(ProverError::VerificationFailed);
+        }
+        println!("[PROVE] Verificato in {:?}", verification_time);
+
+        // Genera Solidity calldata
+        let solidity_calldata =
+            SolidityCalldata::from_snarkjs(&SnarkjsProof::from_json(&proof_str)?, &public_json)?;
+
+        // Serializza prova per compatibilità
+        let proof_json: serde_json::Value = serde_json::from_str(&proof_str)?;
+        let proof_bytes = serde_json::to_vec(&proof_json)?;
+
+        // Cleanup esplicito per segnalare eventuali errori; il drop copre i percorsi d'errore
+        self.workspace.close()?;
 
         let stats = ProofStats {
             proving_time_ms: proving_time.as_millis(),
@@ -463,95 +1490,542 @@ impl SnarkjsProver {
             stats,
         ))
     }
+}
+
+/// Pairing check di una prova snarkjs JSON contro `pvk`
+fn verify_snarkjs_proof(
+    pvk: &PreparedVerifyingKey<Bn254>,
+    proof_json: &str,
+    public_inputs: &[String],
+) -> Result<bool, ProverError> {
+    let proof = SnarkjsProof::from_json(proof_json)?.to_arkworks_proof()?;
+    let inputs = parse_public_inputs(public_inputs)?;
+
+    if inputs.len() + 1 != pvk.vk.gamma_abc_g1.len() {
+        return Err(ProverError::InvalidInput(format!(
+            "wrong number of public inputs: expected {}, got {}",
+            pvk.vk.gamma_abc_g1.len() - 1,
+            inputs.len()
+        )));
+    }
+
+    Ok(Groth16::<Bn254>::verify_proof(pvk, &proof, &inputs)?)
+}
+
+/// Parsa l'output di `snarkjs zkey export soliditycalldata`.
+/// Il formato è: ["0x..","0x.."],[["0x..","0x.."],["0x..","0x.."]],["0x..","0x.."],["0x..",...]
+/// cioè i quattro argomenti di verifyProof separati da virgola: racchiuso
+/// tra parentesi quadre diventa un array JSON valido.
+pub fn parse_solidity_calldata(calldata: &str) -> Result<SolidityCalldata, ProverError> {
+    let trimmed = calldata.trim();
+    if trimmed.is_empty() {
+        return Err(ProverError::InvalidCalldata("empty input".to_string()));
+    }
+
+    let parts: Vec<serde_json::Value> = serde_json::from_str(&format!("[{}]", trimmed))
+        .map_err(|e| ProverError::InvalidCalldata(e.to_string()))?;
+    if parts.len() != 4 {
+        return Err(ProverError::InvalidCalldata(format!(
+            "expected 4 components, got {}",
+            parts.len()
+        )));
+    }
+
+    let a = calldata_pair(&parts[0], "a")?;
+    let b = match parts[1].as_array().map(Vec::as_slice) {
+        Some([b0, b1]) => [calldata_pair(b0, "b[0]")?, calldata_pair(b1, "b[1]")?],
+        _ => return Err(ProverError::InvalidCalldata("b must be a 2x2 array".to_string())),
+    };
+    let c = calldata_pair(&parts[2], "c")?;
+    let inputs = parts[3]
+        .as_array()
+        .ok_or_else(|| ProverError::InvalidCalldata("inputs must be an array".to_string()))?
+        .iter()
+        .enumerate()
+        .map(|(i, v)| calldata_word(v, &format!("inputs[{}]", i)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(SolidityCalldata { a, b, c, inputs })
+}
 
-    /// Verifica una prova usando la VK caricata
+/// Estrae una coppia di uint256 esadecimali
+fn calldata_pair(value: &serde_json::Value, name: &str) -> Result<[String; 2], ProverError> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([x, y]) => Ok([
+            calldata_word(x, &format!("{}[0]", name))?,
+            calldata_word(y, &format!("{}[1]", name))?,
+        ]),
+        _ => Err(ProverError::InvalidCalldata(format!(
+            "{} must be an array of 2 elements",
+            name
+        ))),
+    }
+}
+
+/// Valida un singolo uint256 esadecimale ("0x" + al massimo 64 cifre)
+fn calldata_word(value: &serde_json::Value, name: &str) -> Result<String, ProverError> {
+    let word = value
+        .as_str()
+        .ok_or_else(|| ProverError::InvalidCalldata(format!("{} is not a string", name)))?;
+    let digits = word
+        .strip_prefix("0x")
+        .ok_or_else(|| ProverError::InvalidCalldata(format!("{} is not hex: {}", name, word)))?;
+    if digits.is_empty() || digits.len() > 64 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ProverError::InvalidCalldata(format!(
+            "{} is not a uint256: {}",
+            name, word
+        )));
+    }
+    Ok(word.to_string())
+}
+
+impl SolidityCalldata {
+    /// Costruisce il calldata direttamente da proof.json e public.json,
+    /// senza invocare `snarkjs zkey export soliditycalldata`
+    pub fn from_snarkjs(
+        proof: &SnarkjsProof,
+        public_inputs: &[String],
+    ) -> Result<Self, ProverError> {
+        let proof = proof.to_arkworks_proof()?;
+        let inputs = parse_public_inputs(public_inputs)?;
+        Ok(solidity_calldata_from_proof(&proof, &inputs))
+    }
+}
+
+// ============================================================================
+// CONVERSIONI - Formato snarkjs per prove generate in Rust
+// ============================================================================
+
+/// Elemento di campo come stringa decimale (formato snarkjs)
+fn field_to_decimal<F: PrimeField>(f: &F) -> String {
+    let value: BigUint = (*f).into();
+    value.to_string()
+}
+
+/// Elemento di campo come uint256 esadecimale (formato soliditycalldata)
+fn field_to_hex<F: PrimeField>(f: &F) -> String {
+    let value: BigUint = (*f).into();
+    format!("0x{:064x}", value)
+}
+
+/// Elemento di Fq12 nel formato annidato di snarkjs (vk_alphabeta_12)
+fn fq12_to_snarkjs(f: &Fq12) -> Vec<Vec<Vec<String>>> {
+    [&f.c0, &f.c1]
+        .iter()
+        .map(|fq6| {
+            [&fq6.c0, &fq6.c1, &fq6.c2]
+                .iter()
+                .map(|fq2| vec![field_to_decimal(&fq2.c0), field_to_decimal(&fq2.c1)])
+                .collect()
+        })
+        .collect()
+}
+
+/// Serializza una prova arkworks nel formato proof.json di snarkjs
+fn proof_to_snarkjs_json(proof: &Proof<Bn254>) -> serde_json::Value {
+    serde_json::json!({
+        "pi_a": [field_to_decimal(&proof.a.x), field_to_decimal(&proof.a.y), "1"],
+        "pi_b": [
+            [field_to_decimal(&proof.b.x.c0), field_to_decimal(&proof.b.x.c1)],
+            [field_to_decimal(&proof.b.y.c0), field_to_decimal(&proof.b.y.c1)],
+            ["1", "0"]
+        ],
+        "pi_c": [field_to_decimal(&proof.c.x), field_to_decimal(&proof.c.y), "1"],
+        "protocol": "groth16",
+        "curve": "bn128"
+    })
+}
+
+/// Costruisce il calldata Solidity da una prova arkworks.
+/// Come in snarkjs, le coordinate Fq2 di B sono invertite (c1, c0)
+/// per rispettare l'ordine atteso dalla precompilata di pairing.
+fn solidity_calldata_from_proof(proof: &Proof<Bn254>, public_inputs: &[Fr]) -> SolidityCalldata {
+    SolidityCalldata {
+        a: [field_to_hex(&proof.a.x), field_to_hex(&proof.a.y)],
+        b: [
+            [field_to_hex(&proof.b.x.c1), field_to_hex(&proof.b.x.c0)],
+            [field_to_hex(&proof.b.y.c1), field_to_hex(&proof.b.y.c0)],
+        ],
+        c: [field_to_hex(&proof.c.x), field_to_hex(&proof.c.y)],
+        inputs: public_inputs.iter().map(field_to_hex).collect(),
+    }
+}
+
+// ============================================================================
+// NATIVE PROVER - Witness e prova Groth16 in-process (ark-circom)
+// ============================================================================
+
+/// Genera prove senza processi esterni: il witness è calcolato eseguendo il
+/// WASM del circuito con ark-circom, la prova usa i parametri del .zkey.
+/// Le prove sono quindi verificabili dal Verifier.sol generato da snarkjs.
+///
+/// Contiene solo dati Send + Sync: il modulo WASM compilato e i parametri
+/// della zkey. Lo Store wasmer non lo è, quindi ogni prova istanzia il
+/// proprio witness calculator (vedi [`WitnessModule`]).
+pub struct NativeProver {
+    config: ProverConfig,
+    // Arc / Clone economici: condivisi senza copia con i task di generate_proof_async
+    witness_module: Option<WitnessModule>,
+    proving_key: Option<Arc<ProvingKey<Bn254>>>,
+    prepared_vk: Option<Arc<PreparedVerifyingKey<Bn254>>>,
+    matrices: Option<Arc<ConstraintMatrices<Fr>>>,
+    stats: backend::StatsRecorder,
+}
+
+impl NativeProver {
+    pub fn new(circuit_dir: &str) -> Self {
+        Self::from_config(ProverConfig::new(circuit_dir))
+    }
+
+    pub fn from_config(config: ProverConfig) -> Self {
+        NativeProver {
+            config,
+            witness_module: None,
+            proving_key: None,
+            prepared_vk: None,
+            matrices: None,
+            stats: Default::default(),
+        }
+    }
+
+    pub fn setup(&mut self) -> Result<(), ProverError> {
+        println!("[SETUP] Caricamento circuito e zkey (native)...");
+
+        let wasm_path = self.config.wasm_path();
+        let zkey_path = self.config.zkey_path();
+        for (kind, path) in [("WASM file", &wasm_path), ("Zkey file", &zkey_path)] {
+            if !Path::new(path).exists() {
+                return Err(ProverError::missing(kind, path));
+            }
+        }
+
+        let witness_module = WitnessModule::load(&wasm_path)?;
+
+        let mut parser = ZkeyParser::new(&zkey_path)?;
+        let (proving_key, _) = parser.parse()?;
+        let matrices = parser.constraint_matrices()?;
+        println!("[SETUP] Constraints: {}", matrices.num_constraints);
+
+        self.witness_module = Some(witness_module);
+        self.prepared_vk = Some(Arc::new(prepare_verifying_key(&proving_key.vk)));
+        self.proving_key = Some(Arc::new(proving_key));
+        self.matrices = Some(Arc::new(matrices));
+
+        println!("[SETUP] Completato - usando parametri snarkjs");
+        Ok(())
+    }
+
+    /// Genera prova calcolando il witness in-process
+    pub fn generate_proof(
+        &self,
+        inputs: impl Into<ProofInputs>,
+    ) -> Result<(ProofResult, ProofStats), ProverError> {
+        let (witness_module, proving_key, matrices) = self.loaded()?;
+        prove_native(
+            witness_module,
+            proving_key,
+            matrices,
+            inputs.into().circuit_inputs_for(&self.config)?,
+        )
+    }
+
+    /// Variante async di [`Self::generate_proof`]: witness e prova sono CPU-bound
+    /// e girano su spawn_blocking per non bloccare il runtime
+    pub async fn generate_proof_async(
+        &self,
+        inputs: impl Into<ProofInputs>,
+    ) -> Result<(ProofResult, ProofStats), ProverError> {
+        self.prove_circuit_async(inputs.into().circuit_inputs_for(&self.config)?)
+            .await
+    }
+
+    /// Il task riceve solo cloni economici (modulo WASM e Arc): witness
+    /// calculator e Store wasmer vengono creati dentro spawn_blocking
+    async fn prove_circuit_async(
+        &self,
+        circuit_inputs: Vec<(&'static str, Vec<String>)>,
+    ) -> Result<(ProofResult, ProofStats), ProverError> {
+        let (witness_module, proving_key, matrices) = self.loaded()?;
+        let (witness_module, proving_key, matrices) =
+            (witness_module.clone(), proving_key.clone(), matrices.clone());
+
+        tokio::task::spawn_blocking(move || {
+            prove_native(&witness_module, &proving_key, &matrices, circuit_inputs)
+        })
+        .await?
+    }
+
+    /// Verifica una prova snarkjs JSON con la VK contenuta nella zkey
     pub fn verify_proof(
         &self,
         proof_json: &str,
         public_inputs: &[String],
-    ) -> Result<bool, Box<dyn std::error::Error>> {
-        // Usa snarkjs per verificare
-        let temp_dir = std::env::temp_dir();
-        let proof_file = temp_dir.join("verify_proof.json");
-        let public_file = temp_dir.join("verify_public.json");
-
-        std::fs::write(&proof_file, proof_json)?;
-        std::fs::write(&public_file, serde_json::to_string(public_inputs)?)?;
+    ) -> Result<bool, ProverError> {
+        let pvk = self
+            .prepared_vk
+            .as_ref()
+            .ok_or(ProverError::NotInitialized)?;
 
-        let output = std::process::Command::new("snarkjs")
-            .args([
-                "groth16",
-                "verify",
-                &self.vk_path,
-                public_file.to_str().unwrap(),
-                proof_file.to_str().unwrap(),
-            ])
-            .output()?;
+        verify_snarkjs_proof(pvk, proof_json, public_inputs)
+    }
 
-        let _ = std::fs::remove_file(&proof_file);
-        let _ = std::fs::remove_file(&public_file);
+    pub async fn verify_proof_async(
+        &self,
+        proof_json: &str,
+        public_inputs: &[String],
+    ) -> Result<bool, ProverError> {
+        let pvk = self
+            .prepared_vk
+            .clone()
+            .ok_or(ProverError::NotInitialized)?;
+        let proof_json = proof_json.to_string();
+        let public_inputs = public_inputs.to_vec();
+
+        tokio::task::spawn_blocking(move || verify_snarkjs_proof(&pvk, &proof_json, &public_inputs))
+            .await?
+    }
 
-        Ok(output.status.success())
+    #[allow(clippy::type_complexity)]
+    fn loaded(
+        &self,
+    ) -> Result<
+        (
+            &WitnessModule,
+            &Arc<ProvingKey<Bn254>>,
+            &Arc<ConstraintMatrices<Fr>>,
+        ),
+        ProverError,
+    > {
+        match (&self.witness_module, &self.proving_key, &self.matrices) {
+            (Some(w), Some(pk), Some(m)) => Ok((w, pk, m)),
+            _ => Err(ProverError::NotInitialized),
+        }
     }
 }
 
-/// Parsa l'output di snarkjs soliditycalldata
-fn parse_solidity_calldata(calldata: &str) -> Result<SolidityCalldata, Box<dyn std::error::Error>> {
-    // Il formato è: ["0x...", "0x..."],[[...],[...]],["0x...", "0x..."],["0x..."]
-    // Semplificazione: estraiamo i componenti
+/// WASM del circuito compilato una volta in setup. Engine e Module sono
+/// Send + Sync e si clonano senza copia; lo Store con l'istanza si crea a
+/// ogni calcolo del witness.
+#[derive(Clone)]
+struct WitnessModule {
+    engine: wasmer::Engine,
+    module: wasmer::Module,
+}
 
-    let trimmed = calldata.trim();
+impl WitnessModule {
+    fn load(wasm_path: &str) -> Result<Self, ProverError> {
+        let engine = wasmer::Engine::default();
+        let module = wasmer::Module::from_file(&engine, wasm_path)
+            .map_err(|e| ProverError::Witness(format!("failed to load circuit: {}", e)))?;
+        Ok(WitnessModule { engine, module })
+    }
 
-    // Per ora, parsing semplificato - in produzione servirebbe un parser più robusto
-    // Restituiamo placeholder che verranno popolati dal formato corretto
+    /// Witness completo nell'ordine dei wire: 1, input pubblici, resto
+    fn calculate(
+        &self,
+        inputs: Vec<(&'static str, Vec<String>)>,
+    ) -> Result<Vec<Fr>, ProverError> {
+        let mut named = Vec::with_capacity(inputs.len());
+        for (name, values) in inputs {
+            let values = values
+                .iter()
+                .map(|value| {
+                    BigInt::parse_bytes(value.as_bytes(), 10)
+                        .ok_or_else(|| ProverError::InvalidInput(format!("{}: {}", name, value)))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            named.push((name.to_string(), values));
+        }
 
-    Ok(SolidityCalldata {
-        a: ["0".to_string(), "0".to_string()],
-        b: [
-            ["0".to_string(), "0".to_string()],
-            ["0".to_string(), "0".to_string()],
-        ],
-        c: ["0".to_string(), "0".to_string()],
-        inputs: vec![],
-    })
+        // Il WasiEnv di wasmer-wasix va creato dentro un runtime tokio: fuori
+        // da un runtime (generate_proof sincrona) se ne usa uno locale
+        let runtime;
+        let _guard = match tokio::runtime::Handle::try_current() {
+            Ok(_) => None,
+            Err(_) => {
+                runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?;
+                Some(runtime.enter())
+            }
+        };
+
+        let mut store = wasmer::Store::new(self.engine.clone());
+        let mut calculator = WitnessCalculator::from_module(&mut store, self.module.clone())
+            .map_err(|e| ProverError::Witness(e.to_string()))?;
+        calculator
+            .calculate_witness_element::<Fr, _>(&mut store, named, false)
+            .map_err(|e| ProverError::Witness(e.to_string()))
+    }
+}
+
+/// Witness con ark-circom e prova Groth16 con la proving key della zkey
+fn prove_native(
+    witness_module: &WitnessModule,
+    proving_key: &ProvingKey<Bn254>,
+    matrices: &ConstraintMatrices<Fr>,
+    circuit_inputs: Vec<(&'static str, Vec<String>)>,
+) -> Result<(ProofResult, ProofStats), ProverError> {
+    let start = std::time::Instant::now();
+    println!("[PROVE] Generazione witness (native)...");
+
+    let witness = witness_module.calculate(circuit_inputs)?;
+    if witness.len() < matrices.num_instance_variables + matrices.num_witness_variables {
+        return Err(ProverError::Witness(format!(
+            "witness has {} wires, the zkey expects {}",
+            witness.len(),
+            matrices.num_instance_variables + matrices.num_witness_variables
+        )));
+    }
+    // Il wire 0 è la costante 1
+    let public_inputs = witness[1..matrices.num_instance_variables].to_vec();
+
+    println!("[PROVE] Generazione prova Groth16 (native)...");
+    let mut rng = thread_rng();
+    let r = Fr::rand(&mut rng);
+    let s = Fr::rand(&mut rng);
+    let proof = Groth16::<Bn254, CircomReduction>::create_proof_with_reduction_and_matrices(
+        proving_key,
+        r,
+        s,
+        matrices,
+        matrices.num_instance_variables,
+        matrices.num_constraints,
+        &witness,
+    )?;
+
+    let proving_time = start.elapsed();
+    println!("[PROVE] Generato in {:?}", proving_time);
+
+    // Verifica locale
+    let verify_start = std::time::Instant::now();
+    let pvk = prepare_verifying_key(&proving_key.vk);
+    if !Groth16::<Bn254>::verify_proof(&pvk, &proof, &public_inputs)? {
+        return Err(ProverError::VerificationFailed);
+    }
+    let verification_time = verify_start.elapsed();
+    println!("[PROVE] Verificato in {:?}", verification_time);
+
+    let solidity_calldata = solidity_calldata_from_proof(&proof, &public_inputs);
+    let proof_bytes = serde_json::to_vec(&proof_to_snarkjs_json(&proof))?;
+
+    let stats = ProofStats {
+        proving_time_ms: proving_time.as_millis(),
+        verification_time_ms: verification_time.as_millis(),
+        proof_size_bytes: proof_bytes.len(),
+        num_constraints: matrices.num_constraints,
+    };
+
+    Ok((
+        ProofResult {
+            proof: proof_bytes,
+            public_inputs: public_inputs.iter().map(field_to_decimal).collect(),
+            solidity_calldata,
+        },
+        stats,
+    ))
 }
 
 // ============================================================================
-// PROVER PRINCIPALE - Wrapper che usa SnarkjsProver
+// PROVER PRINCIPALE - Wrapper su un ProofBackend (default: snarkjs)
 // ============================================================================
 
 pub struct BLSProver {
-    inner: SnarkjsProver,
+    backend: Box<dyn ProofBackend>,
 }
 
 impl BLSProver {
     pub fn new(circuit_path: &str) -> Self {
-        BLSProver {
-            inner: SnarkjsProver::new(circuit_path),
-        }
+        Self::from_config(ProverConfig::new(circuit_path))
+    }
+
+    /// Sceglie il backend in base a `config.backend`
+    pub fn from_config(config: ProverConfig) -> Self {
+        let backend: Box<dyn ProofBackend> = match config.backend {
+            BackendKind::Snarkjs => Box::new(SnarkjsProver::from_config(config)),
+            BackendKind::Native => Box::new(NativeProver::from_config(config)),
+        };
+        Self::with_backend(backend)
+    }
+
+    /// Usa un backend arbitrario (es. un mock nei test)
+    pub fn with_backend(backend: Box<dyn ProofBackend>) -> Self {
+        BLSProver { backend }
     }
 
-    pub fn setup(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        self.inner.setup()
+    pub fn backend(&self) -> &dyn ProofBackend {
+        self.backend.as_ref()
+    }
+
+    pub fn setup(&mut self) -> Result<(), ProverError> {
+        self.backend.setup()
     }
 
     pub fn generate_proof(
         &self,
-        inputs: BLSProofInputs,
-    ) -> Result<(ProofResult, ProofStats), Box<dyn std::error::Error>> {
-        self.inner.generate_proof(inputs)
+        inputs: impl Into<ProofInputs>,
+    ) -> Result<(ProofResult, ProofStats), ProverError> {
+        self.backend.prove(inputs.into())
+    }
+
+    pub fn generate_proof_cancellable(
+        &self,
+        inputs: impl Into<ProofInputs>,
+        cancel: &CancelToken,
+    ) -> Result<(ProofResult, ProofStats), ProverError> {
+        self.backend.prove_cancellable(inputs.into(), cancel)
+    }
+
+    pub async fn generate_proof_async(
+        &self,
+        inputs: impl Into<ProofInputs>,
+    ) -> Result<(ProofResult, ProofStats), ProverError> {
+        self.backend.prove_async(inputs.into()).await
     }
 
     pub fn verify_proof(
         &self,
         proof_json: &str,
         public_inputs: &[String],
-    ) -> Result<bool, Box<dyn std::error::Error>> {
-        self.inner.verify_proof(proof_json, public_inputs)
+    ) -> Result<bool, ProverError> {
+        self.backend.verify(proof_json, public_inputs)
     }
 
-    pub fn export_verifying_key(&self) -> Result<String, Box<dyn std::error::Error>> {
-        std::fs::read_to_string(&self.inner.vk_path).map_err(|e| e.into())
+    pub async fn verify_proof_async(
+        &self,
+        proof_json: &str,
+        public_inputs: &[String],
+    ) -> Result<bool, ProverError> {
+        self.backend.verify_async(proof_json, public_inputs).await
+    }
+
+    /// Verifica molte coppie (proof.json, public inputs) con un solo multi-pairing.
+    /// Ok(false) se almeno una prova non è valida (vedi [`verify_batch`]).
+    pub fn verify_batch(&self, proofs: &[(String, Vec<String>)]) -> Result<bool, ProverError> {
+        let pvk = prepare_verifying_key(self.backend.verifying_key()?);
+        verify_snarkjs_batch(&pvk, proofs)
+    }
+
+    pub async fn verify_batch_async(
+        &self,
+        proofs: Vec<(String, Vec<String>)>,
+    ) -> Result<bool, ProverError> {
+        let pvk = prepare_verifying_key(self.backend.verifying_key()?);
+        tokio::task::spawn_blocking(move || verify_snarkjs_batch(&pvk, &proofs)).await?
+    }
+
+    /// verification_key.json nel formato di snarkjs
+    pub fn export_verifying_key(&self) -> Result<String, ProverError> {
+        let json = self.backend.export_vk(VkExportFormat::SnarkjsJson)?;
+        String::from_utf8(json).map_err(|e| ProverError::InvalidVerifyingKey(e.to_string()))
+    }
+
+    pub fn export_verifying_key_as(&self, format: VkExportFormat) -> Result<Vec<u8>, ProverError> {
+        self.backend.export_vk(format)
+    }
+
+    pub fn stats(&self) -> BackendStats {
+        self.backend.stats()
     }
 }
 
@@ -560,48 +2034,280 @@ impl BLSProver {
 // ============================================================================
 
 pub struct BatchProofResult {
-    pub proofs: Vec<ProofResult>,
-    pub aggregated_calldata: Vec<u8>,
+    /// Esito di ogni input (prova o errore), nell'ordine degli input
+    pub items: Vec<BatchItem>,
+    /// Prova aggregata delle prove riuscite, se il BatchProver ha una SRS di aggregazione.
+    /// Si verifica off-chain; on-chain le prove del batch vanno al Groth16BatchVerifier.
+    /// Se l'aggregazione fallisce l'errore resta qui e le prove in `items` restano valide.
+    pub aggregate_proof: Option<Result<AggregateProof, ProverError>>,
     pub total_proving_time_ms: u128,
 }
 
+/// Esito di un singolo input del batch
+#[derive(Debug)]
+pub struct BatchItem {
+    /// Posizione dell'input nel batch
+    pub index: usize,
+    pub result: Result<ProofResult, ProverError>,
+    pub proving_time_ms: u128,
+}
+
+impl BatchItem {
+    pub fn proof(&self) -> Option<&ProofResult> {
+        self.result.as_ref().ok()
+    }
+
+    pub fn error(&self) -> Option<&ProverError> {
+        self.result.as_ref().err()
+    }
+}
+
+impl BatchProofResult {
+    /// Prove riuscite, nell'ordine degli input
+    pub fn proofs(&self) -> impl Iterator<Item = &ProofResult> {
+        self.items.iter().filter_map(BatchItem::proof)
+    }
+
+    /// Indici degli input provati, nello stesso ordine di [`Self::proofs`]
+    pub fn proved_indices(&self) -> Vec<usize> {
+        self.items
+            .iter()
+            .filter(|item| item.result.is_ok())
+            .map(|item| item.index)
+            .collect()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &BatchItem> {
+        self.items.iter().filter(|item| item.result.is_err())
+    }
+}
+
+/// Comportamento di [`BatchProver::prove_batch`] quando un input fallisce
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BatchMode {
+    /// Errore [`ProverError::BatchFailed`] se almeno un input fallisce
+    #[default]
+    AllOrNothing,
+    /// Restituisce le prove riuscite e lo stato di ogni input
+    ContinueOnError,
+}
+
+/// Errore di un singolo elemento di un batch
+#[derive(Debug)]
+pub struct BatchItemError {
+    /// Posizione dell'input nel batch
+    pub index: usize,
+    pub error: ProverError,
+}
+
+impl std::fmt::Display for BatchItemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "item {}: {}", self.index, self.error)
+    }
+}
+
 pub struct BatchProver {
     prover: BLSProver,
+    /// Numero massimo di prove generate in parallelo
+    max_parallelism: usize,
+    mode: BatchMode,
+    aggregation_srs: Option<AggregationSrs>,
+}
+
+/// Default: un worker per core
+fn default_parallelism() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
 }
 
 impl BatchProver {
-    pub fn new(circuit_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let mut prover = BLSProver::new(circuit_path);
+    pub fn new(circuit_path: &str) -> Result<Self, ProverError> {
+        Self::from_config(ProverConfig::new(circuit_path))
+    }
+
+    /// Backend, parallelismo e SRS di aggregazione da `config`; esegue setup()
+    pub fn from_config(config: ProverConfig) -> Result<Self, ProverError> {
+        let max_parallelism = config.max_parallelism;
+        let aggregation_srs = config
+            .aggregation_srs_path
+            .as_deref()
+            .map(AggregationSrs::load)
+            .transpose()?;
+        let mut prover = BLSProver::from_config(config);
         prover.setup()?;
-        Ok(BatchProver { prover })
+
+        let mut batch = Self::from_prover(prover);
+        if let Some(n) = max_parallelism {
+            batch = batch.with_max_parallelism(n);
+        }
+        if let Some(srs) = aggregation_srs {
+            batch = batch.with_aggregation_srs(srs);
+        }
+        Ok(batch)
+    }
+
+    /// Usa un prover già inizializzato (setup() già chiamato)
+    pub fn from_prover(prover: BLSProver) -> Self {
+        BatchProver {
+            prover,
+            max_parallelism: default_parallelism(),
+            mode: BatchMode::default(),
+            aggregation_srs: None,
+        }
+    }
+
+    /// Limita le prove concorrenti (minimo 1). Con il backend snarkjs ogni
+    /// prova è un processo node: dimensionare sulla memoria disponibile.
+    pub fn with_max_parallelism(mut self, max_parallelism: usize) -> Self {
+        self.max_parallelism = max_parallelism.max(1);
+        self
+    }
+
+    pub fn with_mode(mut self, mode: BatchMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Aggrega le prove riuscite di ogni batch in una sola [`AggregateProof`]
+    pub fn with_aggregation_srs(mut self, srs: AggregationSrs) -> Self {
+        self.aggregation_srs = Some(srs);
+        self
+    }
+
+    /// Verifica la prova aggregata di `result` con la VK del backend
+    pub fn verify_aggregate(&self, result: &BatchProofResult) -> Result<bool, ProverError> {
+        let (Some(srs), Some(aggregate)) = (&self.aggregation_srs, &result.aggregate_proof) else {
+            return Err(ProverError::Aggregation(
+                "batch has no aggregate proof".to_string(),
+            ));
+        };
+        let aggregate = aggregate
+            .as_ref()
+            .map_err(|e| ProverError::Aggregation(format!("batch aggregation failed: {}", e)))?;
+        let public_inputs = result
+            .proofs()
+            .map(|p| parse_public_inputs(&p.public_inputs))
+            .collect::<Result<Vec<_>, _>>()?;
+        verify_aggregate_proof(
+            &srs.verifier_key(),
+            self.prover.backend().verifying_key()?,
+            &public_inputs,
+            aggregate,
+        )
     }
 
-    /// Genera prove per un batch di firme
+    fn aggregate(&self, proofs: &[&ProofResult]) -> Option<Result<AggregateProof, ProverError>> {
+        let srs = self.aggregation_srs.as_ref()?;
+        if proofs.is_empty() {
+            return None;
+        }
+        let aggregate = || {
+            let (proofs, public_inputs): (Vec<_>, Vec<_>) = proofs
+                .iter()
+                .map(|proof| aggregation::decode_proof_result(proof))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .unzip();
+            aggregate_proofs(srs, &proofs, &public_inputs)
+        };
+        Some(aggregate())
+    }
+
+    /// Prova tutti gli input con al più `max_parallelism` worker.
+    /// I risultati (con il tempo di ciascuno) sono nell'ordine degli input.
+    fn prove_all(
+        &self,
+        inputs: &[ProofInputs],
+    ) -> Vec<(Result<ProofResult, ProverError>, std::time::Duration)> {
+        let next = std::sync::atomic::AtomicUsize::new(0);
+        let slots: Vec<std::sync::Mutex<Option<_>>> =
+            inputs.iter().map(|_| std::sync::Mutex::new(None)).collect();
+        let workers = self.max_parallelism.min(inputs.len());
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let i = next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    let Some(input) = inputs.get(i) else {
+                        break;
+                    };
+                    println!("[BATCH] Generazione prova {}/{}...", i + 1, inputs.len());
+                    let item_start = std::time::Instant::now();
+                    let result = self.prover.generate_proof(input.clone()).map(|(p, _)| p);
+                    *slots[i].lock().unwrap_or_else(|e| e.into_inner()) =
+                        Some((result, item_start.elapsed()));
+                });
+            }
+        });
+
+        slots
+            .into_iter()
+            .map(|slot| {
+                slot.into_inner()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .expect("every batch item is proved by a worker")
+            })
+            .collect()
+    }
+
+    /// Genera prove per un batch di firme, in parallelo.
+    /// Tutti gli input vengono provati anche se alcuni falliscono. In
+    /// [`BatchMode::AllOrNothing`] l'errore [`ProverError::BatchFailed`] riporta
+    /// ogni elemento fallito; in [`BatchMode::ContinueOnError`] gli errori
+    /// restano in `items` e `proofs` contiene solo le prove riuscite.
     pub fn prove_batch(
         &self,
-        inputs: Vec<BLSProofInputs>,
-    ) -> Result<BatchProofResult, Box<dyn std::error::Error>> {
+        inputs: Vec<impl Into<ProofInputs>>,
+    ) -> Result<BatchProofResult, ProverError> {
         let start = std::time::Instant::now();
-        let mut proofs = Vec::new();
+        let inputs: Vec<ProofInputs> = inputs.into_iter().map(Into::into).collect();
+        let total = inputs.len();
+
+        // Un batch che la SRS non può aggregare si rifiuta prima di provare
+        if let Some(srs) = &self.aggregation_srs {
+            if total > srs.max_proofs() {
+                return Err(ProverError::Aggregation(format!(
+                    "batch of {} inputs exceeds the aggregation SRS ({} proofs)",
+                    total,
+                    srs.max_proofs()
+                )));
+            }
+        }
 
-        for (i, input) in inputs.iter().enumerate() {
-            println!("[BATCH] Generazione prova {}/{}...", i + 1, inputs.len());
-            let (proof, _) = self.prover.generate_proof(input.clone())?;
-            proofs.push(proof);
+        let mut items = Vec::with_capacity(total);
+        for (index, (result, elapsed)) in self.prove_all(&inputs).into_iter().enumerate() {
+            if let Err(error) = &result {
+                println!("[BATCH] Prova {}/{} fallita: {}", index + 1, total, error);
+            }
+            items.push(BatchItem {
+                index,
+                result,
+                proving_time_ms: elapsed.as_millis(),
+            });
         }
 
-        let total_time = start.elapsed();
+        if self.mode == BatchMode::AllOrNothing && items.iter().any(|i| i.result.is_err()) {
+            let failures = items
+                .into_iter()
+                .filter_map(|item| {
+                    item.result.err().map(|error| BatchItemError {
+                        index: item.index,
+                        error,
+                    })
+                })
+                .collect();
+            return Err(ProverError::BatchFailed { total, failures });
+        }
 
-        // Per ora, aggregated_calldata è la concatenazione
-        // In futuro potrebbe essere una prova aggregata
-        let aggregated_calldata = proofs
-            .iter()
-            .flat_map(|p| p.proof.clone())
-            .collect();
+        let proofs: Vec<_> = items.iter().filter_map(BatchItem::proof).collect();
+        let aggregate_proof = self.aggregate(&proofs);
+        if let Some(Err(error)) = &aggregate_proof {
+            println!("[BATCH] Aggregazione fallita: {}", error);
+        }
+        let total_time = start.elapsed();
 
         Ok(BatchProofResult {
-            proofs,
-            aggregated_calldata,
+            items,
+            aggregate_proof,
             total_proving_time_ms: total_time.as_millis(),
         })
     }
@@ -614,15 +2320,593 @@ impl BatchProver {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ark_ec::AffineRepr;
+    use ark_ff::One;
 
     #[test]
     fn test_prover_setup() {
-        let mut prover = BLSProver::new("../circuits");
+        let _prover = BLSProver::new("../circuits");
         // Questo test richiede che i file del circuito esistano
         // let result = prover.setup();
         // assert!(result.is_ok());
     }
 
+    /// Backend finto: la "prova" è il message hash, le prove con hash "0" falliscono
+    struct MockBackend {
+        vk: VerifyingKey<Bn254>,
+        stats: backend::StatsRecorder,
+    }
+
+    impl MockBackend {
+        fn new() -> Self {
+            MockBackend {
+                vk: VerifyingKey::default(),
+                stats: Default::default(),
+            }
+        }
+    }
+
+    impl ProofBackend for MockBackend {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn setup(&mut self) -> Result<(), ProverError> {
+            Ok(())
+        }
+
+        fn prove(&self, inputs: ProofInputs) -> Result<(ProofResult, ProofStats), ProverError> {
+            let ProofInputs::Bn254(inputs) = inputs else {
+                return Err(ProverError::InvalidInput("mock proves BN254 inputs".to_string()));
+            };
+            let hash = inputs.public_inputs.message_hash.x;
+            let result = if hash == "0" {
+                Err(ProverError::VerificationFailed)
+            } else {
+                let zero = || ["0".to_string(), "0".to_string()];
+                Ok((
+                    ProofResult {
+                        proof: hash.clone().into_bytes(),
+                        public_inputs: vec![hash.clone()],
+                        solidity_calldata: SolidityCalldata {
+                            a: zero(),
+                            b: [zero(), zero()],
+                            c: zero(),
+                            inputs: vec![hash],
+                        },
+                    },
+                    ProofStats {
+                        proving_time_ms: 5,
+                        verification_time_ms: 1,
+                        proof_size_bytes: 1,
+                        num_constraints: 0,
+                    },
+                ))
+            };
+            self.stats.record(result)
+        }
+
+        fn verify(&self, proof_json: &str, public_inputs: &[String]) -> Result<bool, ProverError> {
+            Ok(public_inputs == [proof_json])
+        }
+
+        fn verifying_key(&self) -> Result<&VerifyingKey<Bn254>, ProverError> {
+            Ok(&self.vk)
+        }
+
+        fn stats(&self) -> BackendStats {
+            self.stats.snapshot()
+        }
+    }
+
+    fn mock_inputs(message_hash: &str) -> BLSProofInputs {
+        BLSProofInputs {
+            public_inputs: BLSPublicInputs {
+                message_hash: G1PointInput {
+                    x: message_hash.to_string(),
+                    y: "2".to_string(),
+                },
+                public_key: G2Affine::generator().into(),
+            },
+            private_inputs: BLSPrivateInputs {
+                signature: G1Affine::generator().into(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_prover_with_mock_backend() {
+        let mut prover = BLSProver::with_backend(Box::new(MockBackend::new()));
+        prover.setup().unwrap();
+        assert_eq!(prover.backend().name(), "mock");
+
+        let (proof, _) = prover.generate_proof(mock_inputs("42")).unwrap();
+        assert_eq!(proof.public_inputs, vec!["42"]);
+        assert!(prover.verify_proof("42", &proof.public_inputs).unwrap());
+        assert!(prover.generate_proof(mock_inputs("0")).is_err());
+
+        let stats = prover.stats();
+        assert_eq!((stats.proofs_generated, stats.proofs_failed), (1, 1));
+        assert_eq!(stats.total_proving_time_ms, 5);
+
+        let batch = BatchProver::from_prover(prover)
+            .prove_batch(vec![mock_inputs("7"), mock_inputs("8")])
+            .unwrap();
+        assert_eq!(batch.proofs().count(), 2);
+        assert!(batch.aggregate_proof.is_none());
+    }
+
+    #[test]
+    fn test_batch_prover_parallel_order_and_failures() {
+        let batch = BatchProver::from_prover(BLSProver::with_backend(Box::new(MockBackend::new())))
+            .with_max_parallelism(3);
+
+        let inputs: Vec<_> = ["1", "2", "3", "4", "5", "6"].map(mock_inputs).into();
+        let hashes = |result: &BatchProofResult| -> String {
+            result.proofs().map(|p| p.public_inputs.concat()).collect()
+        };
+        let result = batch.prove_batch(inputs).unwrap();
+        assert_eq!(hashes(&result), "123456");
+
+        let inputs: Vec<_> = ["1", "0", "3", "0", "5"].map(mock_inputs).into();
+        match batch.prove_batch(inputs) {
+            Err(ProverError::BatchFailed { total, failures }) => {
+                assert_eq!(total, 5);
+                let indices: Vec<_> = failures.iter().map(|f| f.index).collect();
+                assert_eq!(indices, vec![1, 3]);
+            }
+            other => panic!("unexpected result: {:?}", other.map(|r| r.items.len())),
+        }
+
+        let inputs: Vec<_> = ["1", "0", "3", "0", "5"].map(mock_inputs).into();
+        let result = batch
+            .with_mode(BatchMode::ContinueOnError)
+            .prove_batch(inputs)
+            .unwrap();
+        assert_eq!(result.proved_indices(), vec![0, 2, 4]);
+        assert_eq!(hashes(&result), "135");
+        assert_eq!(result.failures().map(|f| f.index).collect::<Vec<_>>(), vec![1, 3]);
+        assert!(matches!(result.items[1].error(), Some(ProverError::VerificationFailed)));
+        assert_eq!(result.items[2].proof().unwrap().public_inputs, vec!["3"]);
+    }
+
+    #[test]
+    fn test_batch_aggregation_failure_keeps_proofs() {
+        let srs = AggregationSrs::setup(&mut ark_std::test_rng(), 4);
+        let batch = BatchProver::from_prover(BLSProver::with_backend(Box::new(MockBackend::new())))
+            .with_mode(BatchMode::ContinueOnError)
+            .with_aggregation_srs(srs);
+
+        // Oltre la capacità della SRS: rifiutato prima di provare
+        let inputs: Vec<_> = ["1", "2", "3", "4", "5"].map(mock_inputs).into();
+        assert!(matches!(
+            batch.prove_batch(inputs),
+            Err(ProverError::Aggregation(_))
+        ));
+        assert_eq!(batch.prover.stats().proofs_generated, 0);
+
+        // Le prove finte non si aggregano: l'errore non scarta le prove riuscite
+        let inputs: Vec<_> = ["1", "0", "3"].map(mock_inputs).into();
+        let result = batch.prove_batch(inputs).unwrap();
+        assert_eq!(result.proved_indices(), vec![0, 2]);
+        assert!(matches!(result.aggregate_proof, Some(Err(_))));
+        assert!(batch.verify_aggregate(&result).is_err());
+    }
+
+    #[test]
+    fn test_snarkjs_workspace_isolated() {
+        let root = tempfile::tempdir().unwrap();
+        let prover = SnarkjsProver::new("../circuits").with_temp_root(root.path());
+
+        let first = prover.workspace().unwrap();
+        let second = prover.workspace().unwrap();
+        assert_ne!(first.path(), second.path());
+        assert!(first.path().starts_with(root.path()));
+
+        let path = first.path().to_path_buf();
+        drop(first);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_zkey_wrong_magic() {
+        let path = std::env::temp_dir().join("test_wrong_magic.zkey");
+        std::fs::write(&path, b"abcd\x01\x00\x00\x00\x00\x00\x00\x00").unwrap();
+
+        let mut parser = ZkeyParser::new(path.to_str().unwrap()).unwrap();
+        assert!(matches!(parser.parse(), Err(ProverError::ZkeyBadMagic(_))));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_zkey_truncated() {
+        // File più corto dell'header
+        let mut parser = ZkeyParser::from_reader(std::io::Cursor::new(b"zk".to_vec())).unwrap();
+        assert!(matches!(parser.parse(), Err(ProverError::ZkeyTruncated { .. })));
+
+        // Sezione che dichiara più bytes di quelli presenti nel file
+        let mut data = Vec::new();
+        data.extend_from_slice(&ZKEY_MAGIC.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&SECTION_HEADER.to_le_bytes());
+        data.extend_from_slice(&1_000_000u64.to_le_bytes());
+        data.extend_from_slice(&ZKEY_PROTOCOL_GROTH16.to_le_bytes());
+
+        let mut parser = ZkeyParser::from_reader(std::io::Cursor::new(data)).unwrap();
+        assert!(matches!(parser.parse(), Err(ProverError::ZkeyTruncated { .. })));
+    }
+
+    /// Circuito minimo c = a * b (test/fixtures/multiplier, zkey di snarkjs)
+    fn multiplier_config() -> ProverConfig {
+        ProverConfig::new(concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures/multiplier"))
+            .with_circuit_name("multiplier")
+    }
+
+    #[test]
+    fn test_zkey_fixture_matches_vk_json() {
+        let config = multiplier_config();
+        let mut parser = ZkeyParser::new(&config.zkey_path()).unwrap();
+        let (proving_key, vk) = parser.parse().unwrap();
+
+        // Stesso setup: la VK della zkey coincide con verification_key.json
+        let json_vk = SnarkjsVerificationKey::load(&config.vk_path())
+            .unwrap()
+            .to_arkworks_vk()
+            .unwrap();
+        assert_eq!(vk, json_vk);
+        assert_eq!(proving_key.vk, json_vk);
+
+        // 4 wire (1, c, a, b) di cui 1 pubblico, un solo vincolo
+        assert_eq!(proving_key.a_query.len(), 4);
+        assert_eq!(proving_key.b_g2_query.len(), 4);
+        assert_eq!(proving_key.l_query.len(), 2);
+
+        let matrices = parser.constraint_matrices().unwrap();
+        assert_eq!(matrices.num_instance_variables, 2);
+        assert_eq!(matrices.num_witness_variables, 2);
+        assert_eq!(matrices.num_constraints, 1);
+    }
+
+    #[test]
+    fn test_zkey_forged_domain_size() {
+        // Offset dei dati di una sezione nella zkey
+        fn section(data: &[u8], wanted: u32) -> usize {
+            let mut offset = 12;
+            loop {
+                let section_type = u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+                let size = u64::from_le_bytes(data[offset + 4..offset + 12].try_into().unwrap());
+                if section_type == wanted {
+                    return offset + 12;
+                }
+                offset += 12 + size as usize;
+            }
+        }
+
+        // domainSize 2^30 e un coefficiente sul vincolo 2^29: senza una sezione
+        // H di 2^30 punti la zkey va rifiutata prima di allocare le righe
+        let mut data = std::fs::read(multiplier_config().zkey_path()).unwrap();
+        let header = section(&data, SECTION_GROTH16_HEADER);
+        data[header + 80..header + 84].copy_from_slice(&(1u32 << 30).to_le_bytes());
+        let coeffs = section(&data, SECTION_COEFFS);
+        data[coeffs + 8..coeffs + 12].copy_from_slice(&(1u32 << 29).to_le_bytes());
+
+        let mut parser = ZkeyParser::from_reader(std::io::Cursor::new(data)).unwrap();
+        assert!(matches!(
+            parser.constraint_matrices(),
+            Err(ProverError::ZkeyTruncated { .. })
+        ));
+    }
+
+    #[test]
+    fn test_native_prove_multiplier() {
+        let mut prover = NativeProver::from_config(multiplier_config());
+        prover.setup().unwrap();
+
+        let (witness_module, proving_key, matrices) = prover.loaded().unwrap();
+        let inputs = vec![("a", vec!["3".to_string()]), ("b", vec!["11".to_string()])];
+        let (result, stats) = prove_native(witness_module, proving_key, matrices, inputs).unwrap();
+
+        assert_eq!(result.public_inputs, vec!["33"]);
+        assert_eq!(stats.num_constraints, matrices.num_constraints);
+
+        let proof_json = String::from_utf8(result.proof.clone()).unwrap();
+        assert!(prover.verify_proof(&proof_json, &result.public_inputs).unwrap());
+        assert!(!prover.verify_proof(&proof_json, &["34".to_string()]).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_native_prove_async_concurrent() {
+        let mut prover = NativeProver::from_config(multiplier_config());
+        prover.setup().unwrap();
+
+        let inputs = |a: &str, b: &str| vec![("a", vec![a.to_string()]), ("b", vec![b.to_string()])];
+        let (first, second) = tokio::join!(
+            prover.prove_circuit_async(inputs("3", "11")),
+            prover.prove_circuit_async(inputs("5", "7")),
+        );
+        assert_eq!(first.unwrap().0.public_inputs, vec!["33"]);
+        assert_eq!(second.unwrap().0.public_inputs, vec!["35"]);
+    }
+
+    #[test]
+    fn test_native_prover_shared_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<NativeProver>();
+
+        let mut prover = NativeProver::from_config(multiplier_config());
+        prover.setup().unwrap();
+
+        let prover = &prover;
+        let outputs: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = ["3", "5"]
+                .into_iter()
+                .map(|a| {
+                    scope.spawn(move || {
+                        let (witness_module, proving_key, matrices) = prover.loaded().unwrap();
+                        let inputs = vec![("a", vec![a.to_string()]), ("b", vec!["2".to_string()])];
+                        prove_native(witness_module, proving_key, matrices, inputs).unwrap()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap().0.public_inputs).collect()
+        });
+        assert_eq!(outputs, vec![vec!["6"], vec!["10"]]);
+    }
+
+    #[test]
+    fn test_snarkjs_proof_roundtrip() {
+        use ark_ec::{CurveGroup, PrimeGroup};
+
+        let mut rng = thread_rng();
+        let proof = Proof::<Bn254> {
+            a: (G1Projective::generator() * Fr::rand(&mut rng)).into_affine(),
+            b: (G2Projective::generator() * Fr::rand(&mut rng)).into_affine(),
+            c: (G1Projective::generator() * Fr::rand(&mut rng)).into_affine(),
+        };
+
+        let json = proof_to_snarkjs_json(&proof).to_string();
+        let parsed = SnarkjsProof::from_json(&json).unwrap().to_arkworks_proof().unwrap();
+        assert_eq!(parsed, proof);
+    }
+
+    #[test]
+    fn test_public_inputs_out_of_field() {
+        let modulus: BigUint = Fr::MODULUS.into();
+        assert!(parse_public_inputs(&["42".to_string()]).is_ok());
+        assert!(parse_public_inputs(&[modulus.to_string()]).is_err());
+        assert!(parse_public_inputs(&["0x2a".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_solidity_calldata() {
+        let calldata = r#"["0x01", "0x02"],[["0x03", "0x04"],["0x05", "0x06"]],["0x07", "0x08"],["0x09","0x0a"]"#;
+        let parsed = parse_solidity_calldata(calldata).unwrap();
+        assert_eq!(parsed.a, ["0x01", "0x02"]);
+        assert_eq!(parsed.b[1], ["0x05", "0x06"]);
+        assert_eq!(parsed.c, ["0x07", "0x08"]);
+        assert_eq!(parsed.inputs, vec!["0x09", "0x0a"]);
+
+        assert!(parse_solidity_calldata("").is_err());
+        assert!(parse_solidity_calldata(r#"["0x01"],[],[],[]"#).is_err());
+        assert!(parse_solidity_calldata(r#"["0x01","12"],[["0x03","0x04"],["0x05","0x06"]],["0x07","0x08"],[]"#).is_err());
+    }
+
+    #[test]
+    fn test_calldata_matches_snarkjs_layout() {
+        use ark_ec::{CurveGroup, PrimeGroup};
+
+        let proof = Proof::<Bn254> {
+            a: G1Projective::generator().into_affine(),
+            b: G2Projective::generator().into_affine(),
+            c: (G1Projective::generator() * Fr::from(2u64)).into_affine(),
+        };
+        let snarkjs_proof = SnarkjsProof::from_json(&proof_to_snarkjs_json(&proof).to_string()).unwrap();
+        let calldata = SolidityCalldata::from_snarkjs(&snarkjs_proof, &["5".to_string()]).unwrap();
+
+        assert_eq!(calldata.a[0], format!("0x{:064x}", 1));
+        assert_eq!(calldata.a[1], format!("0x{:064x}", 2));
+        // B è serializzato come [c1, c0]
+        assert_eq!(calldata.b[0][0], field_to_hex(&proof.b.x.c1));
+        assert_eq!(calldata.b[0][1], field_to_hex(&proof.b.x.c0));
+        assert_eq!(calldata.inputs, vec![format!("0x{:064x}", 5)]);
+    }
+
+    #[test]
+    fn test_point_validation() {
+        let to_strings = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        // Generatore di G1 e punto all'infinito
+        let g1 = SnarkjsVerificationKey::parse_g1_point(&to_strings(&["1", "2", "1"]), "g").unwrap();
+        assert_eq!(g1, G1Affine::generator());
+        let inf = SnarkjsVerificationKey::parse_g1_point(&to_strings(&["0", "1", "0"]), "g").unwrap();
+        assert!(inf.infinity);
+
+        // Punto fuori dalla curva
+        let err = SnarkjsVerificationKey::parse_g1_point(&to_strings(&["1", "3", "1"]), "vk_alpha_1");
+        assert!(matches!(err, Err(ProverError::InvalidPoint { field, .. }) if field == "vk_alpha_1"));
+
+        // Coordinata non ridotta (x + q) deve essere rifiutata, non ridotta
+        let q: BigUint = Fq::MODULUS.into();
+        let x_plus_q = (q + 1u32).to_string();
+        let err = SnarkjsVerificationKey::parse_g1_point(&[x_plus_q, "2".to_string()], "IC[0]");
+        assert!(matches!(err, Err(ProverError::InvalidFieldElement { field, .. }) if field == "IC[0].x"));
+
+        // Punto sulla twist ma fuori dal sottogruppo di ordine primo
+        let point = (1u64..)
+            .filter_map(|i| G2Affine::get_point_from_x_unchecked(Fq2::new(Fq::from(i), Fq::one()), false))
+            .find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap();
+        let coords = vec![
+            vec![field_to_decimal(&point.x.c0), field_to_decimal(&point.x.c1)],
+            vec![field_to_decimal(&point.y.c0), field_to_decimal(&point.y.c1)],
+        ];
+        let err = SnarkjsVerificationKey::parse_g2_point(&coords, "vk_beta_2");
+        assert!(matches!(err, Err(ProverError::InvalidPoint { reason, .. }) if reason.contains("subgroup")));
+    }
+
+    #[test]
+    fn test_bls_circuit_inputs() {
+        let sk = Fr::from(123456789u64);
+        let message_hash = (G1Affine::generator() * Fr::from(987654321u64)).into_affine();
+        let mut inputs = BLSProofInputs {
+            public_inputs: BLSPublicInputs {
+                message_hash: message_hash.into(),
+                public_key: (G2Affine::generator() * sk).into_affine().into(),
+            },
+            private_inputs: BLSPrivateInputs {
+                signature: (message_hash * sk).into_affine().into(),
+            },
+        };
+
+        // Ogni coordinata di Fq in due parole da 128 bit: 2 + 4 + 2 coordinate
+        let signals = inputs.circuit_inputs().unwrap();
+        let names: Vec<_> = signals.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["messageHash", "publicKey", "signature"]);
+        assert_eq!(signals.iter().map(|(_, v)| v.len()).collect::<Vec<_>>(), [4, 8, 4]);
+        let x: BigUint = message_hash.x.into();
+        let lo: BigUint = signals[0].1[0].parse().unwrap();
+        let hi: BigUint = signals[0].1[1].parse().unwrap();
+        assert_eq!(lo + (hi << 128), x);
+
+        let public = inputs.public_signals().unwrap();
+        assert_eq!(public, [signals[0].1.clone(), signals[1].1.clone()].concat());
+
+        // Chiave pubblica fuori dalla curva: errore prima di generare il witness
+        inputs.public_inputs.public_key.y[0] = "1".to_string();
+        let err = inputs.circuit_inputs();
+        assert!(matches!(err, Err(ProverError::InvalidPoint { field, .. }) if field == "publicKey"));
+    }
+
+    #[test]
+    fn test_committee_size_checked_against_circuit() {
+        let keys: Vec<SecretKey> = (1..=3u8)
+            .map(|i| SecretKey::from_seed(&[i; 32]).unwrap())
+            .collect();
+        let committee = Committee::new(
+            keys.iter()
+                .map(|sk| (sk.public_key(), sk.pop_prove()))
+                .collect(),
+        )
+        .unwrap();
+        let signature = keys[1]
+            .sign_with_dst(b"block 1", BN254_G1_POP_SCHEME_DST)
+            .unwrap();
+        let inputs: ProofInputs = CommitteeProofInputs::from_signature(
+            &committee,
+            vec![false, true, false],
+            b"block 1",
+            &signature,
+        )
+        .unwrap()
+        .into();
+
+        // Il circuito predefinito ha N = 16: 3 chiavi non producono un witness
+        let err = inputs
+            .circuit_inputs_for(&ProverConfig::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("N = 16"), "{}", err);
+        assert!(inputs
+            .circuit_inputs_for(&ProverConfig::default().with_committee_size(3))
+            .is_ok());
+
+        // Il prover rifiuta prima di calcolare il witness
+        let mut prover = NativeProver::from_config(multiplier_config());
+        prover.setup().unwrap();
+        let err = prover.generate_proof(inputs).unwrap_err();
+        assert!(matches!(err, ProverError::InvalidInput(_)), "{}", err);
+    }
+
+    #[test]
+    fn test_projective_point_normalisation() {
+        // Generatore di G1 in coordinate Jacobiane non normalizzate: (X·Z², Y·Z³, Z)
+        let z = Fq::from(7u64);
+        let g = G1Affine::generator();
+        let coords = vec![
+            field_to_decimal(&(g.x * z * z)),
+            field_to_decimal(&(g.y * z * z * z)),
+            field_to_decimal(&z),
+        ];
+        assert_eq!(SnarkjsVerificationKey::parse_g1_point(&coords, "g").unwrap(), g);
+
+        let z2 = Fq2::new(Fq::from(3u64), Fq::from(5u64));
+        let h = G2Affine::generator();
+        let x = h.x * z2 * z2;
+        let y = h.y * z2 * z2 * z2;
+        let coords = vec![
+            vec![field_to_decimal(&x.c0), field_to_decimal(&x.c1)],
+            vec![field_to_decimal(&y.c0), field_to_decimal(&y.c1)],
+            vec![field_to_decimal(&z2.c0), field_to_decimal(&z2.c1)],
+        ];
+        assert_eq!(SnarkjsVerificationKey::parse_g2_point(&coords, "h").unwrap(), h);
+
+        // Z = 0 è il punto all'infinito indipendentemente da X e Y
+        let coords = vec![
+            vec!["5".to_string(), "0".to_string()],
+            vec!["1".to_string(), "0".to_string()],
+            vec!["0".to_string(), "0".to_string()],
+        ];
+        assert!(SnarkjsVerificationKey::parse_g2_point(&coords, "h").unwrap().infinity);
+    }
+
+    /// Verification key snarkjs sintetica con alpha = a·G1, beta = b·G2
+    fn synthetic_vk(alphabeta: Fq12) -> SnarkjsVerificationKey {
+        let alpha = (G1Affine::generator() * Fr::from(3u64)).into_affine();
+        let beta = (G2Affine::generator() * Fr::from(5u64)).into_affine();
+        let g1 = |p: &G1Affine| vec![field_to_decimal(&p.x), field_to_decimal(&p.y), "1".to_string()];
+        let g2 = |p: &G2Affine| {
+            vec![
+                vec![field_to_decimal(&p.x.c0), field_to_decimal(&p.x.c1)],
+                vec![field_to_decimal(&p.y.c0), field_to_decimal(&p.y.c1)],
+                vec!["1".to_string(), "0".to_string()],
+            ]
+        };
+        SnarkjsVerificationKey {
+            protocol: "groth16".to_string(),
+            curve: "bn128".to_string(),
+            n_public: 1,
+            vk_alpha_1: g1(&alpha),
+            vk_beta_2: g2(&beta),
+            vk_gamma_2: g2(&G2Affine::generator()),
+            vk_delta_2: g2(&G2Affine::generator()),
+            vk_alphabeta_12: fq12_to_snarkjs(&alphabeta),
+            ic: vec![g1(&G1Affine::generator()), g1(&alpha)],
+        }
+    }
+
+    #[test]
+    fn test_vk_alphabeta_cross_check() {
+        let alpha = (G1Affine::generator() * Fr::from(3u64)).into_affine();
+        let beta = (G2Affine::generator() * Fr::from(5u64)).into_affine();
+        let arkworks_pairing = Bn254::pairing(alpha, beta).0;
+
+        // Valore nella convenzione arkworks
+        assert!(synthetic_vk(arkworks_pairing).to_arkworks_vk().is_ok());
+
+        // Pairing "esatto": e^(1/m) nel sottogruppo di ordine r di Fq12
+        let r: BigUint = Fr::MODULUS.into();
+        let m_inv = final_exponent_multiplier().modpow(&(&r - 2u32), &r);
+        let exact = arkworks_pairing.pow(m_inv.to_u64_digits());
+        assert!(synthetic_vk(exact).to_arkworks_vk().is_ok());
+
+        // Valore manomesso
+        let tampered = arkworks_pairing * arkworks_pairing;
+        assert!(matches!(
+            synthetic_vk(tampered).to_arkworks_vk(),
+            Err(ProverError::InvalidVerifyingKey(_))
+        ));
+
+        // nPublic incoerente con IC
+        let mut vk = synthetic_vk(arkworks_pairing);
+        vk.n_public = 2;
+        assert!(matches!(vk.validate_metadata(), Err(ProverError::InvalidVerifyingKey(_))));
+
+        let mut vk = synthetic_vk(arkworks_pairing);
+        vk.protocol = "plonk".to_string();
+        assert!(vk.validate_metadata().is_err());
+    }
+
     #[test]
     fn test_vk_loader() {
         // Test del caricamento della verification key
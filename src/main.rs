@@ -1,7 +1,10 @@
 // prover/src/main.rs
 // CLI interface for BLS ZK Prover
 
-use bls_zk_prover::{BLSProver, BLSProofInputs, BLSPublicInputs, BLSPrivateInputs};
+use bls_zk_prover::{
+    AggregationSrs, BLSProver, BLSProofInputs, BLSPublicInputs, BLSPrivateInputs, G1PointInput, G2PointInput,
+    ProverConfig, SecretKey, VkExportFormat, BN254_G1_DST,
+};
 use clap::{Parser, Subcommand};
 use std::fs;
 
@@ -9,10 +12,26 @@ use std::fs;
 #[command(name = "bls-prover")]
 #[command(about = "BLS Signature ZK Prover CLI", long_about = None)]
 struct Cli {
+    /// File di configurazione (.toml o .json); le variabili BLS_PROVER_* hanno la precedenza
+    #[arg(long, global = true)]
+    config: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// Costruisce il prover da --config (o dal circuit path) più gli override d'ambiente
+fn load_prover(
+    config: Option<&str>,
+    circuit_path: &str,
+) -> Result<BLSProver, Box<dyn std::error::Error>> {
+    let config = match config {
+        Some(path) => ProverConfig::from_file(path)?,
+        None => ProverConfig::new(circuit_path),
+    };
+    Ok(BLSProver::from_config(config.with_env_overrides()?))
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Esegue il trusted setup del circuito
@@ -22,24 +41,51 @@ enum Commands {
 
         #[arg(short, long)]
         output: Option<String>,
-    },
 
-    /// Genera una prova ZK
-    Prove {
+        /// Formato della verifying key: snarkjs, ark-compressed, ark-uncompressed, gnark,
+        /// solidity, solidity-verifier, solidity-batch-verifier
         #[arg(short, long)]
-        message_hash: String,
+        format: Option<VkExportFormat>,
+    },
 
+    /// Deriva la SRS di aggregazione da due cerimonie Powers of Tau indipendenti
+    AggregationSetup {
+        /// .ptau delle potenze di a
         #[arg(long)]
-        public_key_x: String,
+        alpha_ptau: String,
 
+        /// .ptau delle potenze di b, di una cerimonia diversa
         #[arg(long)]
-        public_key_y: String,
+        beta_ptau: String,
 
-        #[arg(long)]
-        signature_x: String,
+        #[arg(short, long, default_value = "64")]
+        max_proofs: usize,
 
-        #[arg(long)]
-        signature_y: String,
+        #[arg(short, long)]
+        output: String,
+    },
+
+    /// Genera una prova ZK
+    Prove {
+        /// H(m) in G1: x y
+        #[arg(short, long, num_args = 2, value_names = ["X", "Y"], required_unless_present = "message")]
+        message_hash: Vec<String>,
+
+        /// Messaggio firmato, al posto di --message-hash: H(m) secondo RFC 9380
+        #[arg(long, conflicts_with = "message_hash")]
+        message: Option<String>,
+
+        /// Domain separation tag dell'hash-to-curve (default BN254_G1_DST)
+        #[arg(long, requires = "message")]
+        dst: Option<String>,
+
+        /// Chiave pubblica in G2: x.c0 x.c1 y.c0 y.c1
+        #[arg(long, required = true, num_args = 4, value_names = ["X_C0", "X_C1", "Y_C0", "Y_C1"])]
+        public_key: Vec<String>,
+
+        /// Firma in G1: x y
+        #[arg(long, required = true, num_args = 2, value_names = ["X", "Y"])]
+        signature: Vec<String>,
 
         #[arg(short, long, default_value = "../circuits")]
         circuit_path: String,
@@ -48,6 +94,23 @@ enum Commands {
         output: Option<String>,
     },
 
+    /// Firma un messaggio con una chiave derivata dal seed e stampa gli input della prova
+    Sign {
+        /// Seed della chiave in hex (almeno 32 byte)
+        #[arg(short, long)]
+        seed: String,
+
+        #[arg(short, long)]
+        message: String,
+
+        /// Domain separation tag dell'hash-to-curve (default BN254_G1_DST)
+        #[arg(long)]
+        dst: Option<String>,
+
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
     /// Verifica una prova
     Verify {
         #[arg(short, long)]
@@ -75,54 +138,93 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Setup { circuit_path, output } => {
+        Commands::Setup {
+            circuit_path,
+            output,
+            format,
+        } => {
             println!("=== BLS ZK Prover - Trusted Setup ===\n");
 
-            let mut prover = BLSProver::new(&circuit_path);
+            let mut prover = load_prover(cli.config.as_deref(), &circuit_path)?;
             prover.setup()?;
 
             if let Some(output_path) = output {
-                let vk = prover.export_verifying_key()?;
-                fs::write(&output_path, vk)?;
+                match format {
+                    Some(format) => {
+                        fs::write(&output_path, prover.export_verifying_key_as(format)?)?
+                    }
+                    None => fs::write(&output_path, prover.export_verifying_key()?)?,
+                }
                 println!("\nVerifying key salvata in: {}", output_path);
             }
 
             println!("\nSetup completato con successo");
         }
 
+        Commands::AggregationSetup {
+            alpha_ptau,
+            beta_ptau,
+            max_proofs,
+            output,
+        } => {
+            println!("=== BLS ZK Prover - SRS di aggregazione ===\n");
+
+            let srs = AggregationSrs::from_ptau(&alpha_ptau, &beta_ptau, max_proofs)?;
+            srs.save(&output)?;
+            println!("SRS per {} prove salvata in: {}", srs.max_proofs(), output);
+        }
+
         Commands::Prove {
             message_hash,
-            public_key_x,
-            public_key_y,
-            signature_x,
-            signature_y,
+            message,
+            dst,
+            public_key,
+            signature,
             circuit_path,
             output,
         } => {
             println!("=== BLS ZK Prover - Generazione Prova ===\n");
 
-            let mut prover = BLSProver::new(&circuit_path);
+            let mut prover = load_prover(cli.config.as_deref(), &circuit_path)?;
             prover.setup()?;
 
+            let message_hash = match message {
+                Some(message) => G1PointInput::from_message(
+                    message.as_bytes(),
+                    dst.as_ref().map_or(BN254_G1_DST, |dst| dst.as_bytes()),
+                )?,
+                None => G1PointInput {
+                    x: message_hash[0].clone(),
+                    y: message_hash[1].clone(),
+                },
+            };
+
             let inputs = BLSProofInputs {
                 public_inputs: BLSPublicInputs {
                     message_hash: message_hash.clone(),
-                    public_key_x: public_key_x.clone(),
-                    public_key_y: public_key_y.clone(),
+                    public_key: G2PointInput {
+                        x: [public_key[0].clone(), public_key[1].clone()],
+                        y: [public_key[2].clone(), public_key[3].clone()],
+                    },
                 },
                 private_inputs: BLSPrivateInputs {
-                    signature_x,
-                    signature_y,
+                    signature: G1PointInput {
+                        x: signature[0].clone(),
+                        y: signature[1].clone(),
+                    },
                 },
             };
 
+            // Una firma non valida farebbe fallire il witness: meglio saperlo subito
+            inputs.check_signature()?;
+
             println!("Input pubblici:");
-            println!("  Message hash: {}", message_hash);
-            println!("  Public key X: {}", public_key_x);
-            println!("  Public key Y: {}", public_key_y);
+            println!("  Message hash: ({}, {})", message_hash.x, message_hash.y);
+            println!("  Public key X: ({}, {})", public_key[0], public_key[1]);
+            println!("  Public key Y: ({}, {})", public_key[2], public_key[3]);
             println!();
 
-            let (result, stats) = prover.generate_proof(inputs)?;
+            let (result, stats) = prover.generate_proof_async(inputs).await?;
 
             println!("\n=== Statistiche ===");
             println!("Proving time: {} ms", stats.proving_time_ms);
@@ -144,14 +246,46 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
 
+        Commands::Sign {
+            seed,
+            message,
+            dst,
+            output,
+        } => {
+            let dst = dst.as_ref().map_or(BN254_G1_DST, |dst| dst.as_bytes());
+            let sk = SecretKey::from_seed(&hex::decode(seed)?)?;
+            let public_key = sk.public_key();
+            let signature = sk.sign_with_dst(message.as_bytes(), dst)?;
+            let inputs = BLSProofInputs::from_signature_with_dst(
+                &public_key,
+                message.as_bytes(),
+                dst,
+                &signature,
+            )?;
+
+            let output_data = serde_json::json!({
+                "publicInputs": inputs.public_inputs,
+                "privateInputs": inputs.private_inputs,
+            });
+            let json = serde_json::to_string_pretty(&output_data)?;
+
+            match output {
+                Some(output_path) => {
+                    fs::write(&output_path, json)?;
+                    println!("Input della prova salvati in: {}", output_path);
+                }
+                None => println!("{}", json),
+            }
+        }
+
         Commands::Verify {
             proof_file,
-            inputs_file,
+            inputs_file: _,
             circuit_path,
         } => {
             println!("=== BLS ZK Prover - Verifica Prova ===\n");
 
-            let mut prover = BLSProver::new(&circuit_path);
+            let mut prover = load_prover(cli.config.as_deref(), &circuit_path)?;
             prover.setup()?;
 
             let proof_data = fs::read_to_string(proof_file)?;
@@ -159,6 +293,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
             let proof_hex = proof_json["proof"].as_str().ok_or("Missing proof")?;
             let proof_bytes = hex::decode(proof_hex)?;
+            let proof_str = String::from_utf8(proof_bytes)?;
 
             let public_inputs: Vec<String> = proof_json["publicInputs"]
                 .as_array()
@@ -167,7 +302,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .map(|v| v.as_str().unwrap().to_string())
                 .collect();
 
-            let is_valid = prover.verify_proof(&proof_bytes, &public_inputs)?;
+            let is_valid = prover.verify_proof_async(&proof_str, &public_inputs).await?;
 
             if is_valid {
                 println!("PROVA VALIDA");
@@ -183,7 +318,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("=== BLS ZK Prover - Benchmark ===\n");
             println!("Iterazioni: {}\n", iterations);
 
-            let mut prover = BLSProver::new(&circuit_path);
+            let mut prover = load_prover(cli.config.as_deref(), &circuit_path)?;
             prover.setup()?;
 
             let mut total_proving_time = 0u128;
@@ -191,19 +326,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             let mut total_proof_size = 0usize;
 
             for i in 0..iterations {
-                let inputs = BLSProofInputs {
-                    public_inputs: BLSPublicInputs {
-                        message_hash: format!("{}", i * 12345),
-                        public_key_x: format!("{}", i * 67890),
-                        public_key_y: format!("{}", i * 11111),
-                    },
-                    private_inputs: BLSPrivateInputs {
-                        signature_x: format!("{}", i * 22222),
-                        signature_y: format!("{}", i * 33333),
-                    },
-                };
-
-                let (result, stats) = prover.generate_proof(inputs)?;
+                // Chiave deterministica per iterazione, firma vera su un messaggio distinto
+                let mut seed = [0u8; 32];
+                seed[..8].copy_from_slice(&(i as u64).to_be_bytes());
+                let sk = SecretKey::from_seed(&seed)?;
+                let message = format!("benchmark {}", i);
+                let signature = sk.sign(message.as_bytes());
+                let inputs =
+                    BLSProofInputs::from_signature(&sk.public_key(), message.as_bytes(), &signature);
+
+                let (_result, stats) = prover.generate_proof_async(inputs).await?;
 
                 total_proving_time += stats.proving_time_ms;
                 total_verification_time += stats.verification_time_ms;
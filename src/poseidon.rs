@@ -0,0 +1,179 @@
+// prover/src/poseidon.rs
+// Poseidon compatibile con circomlib (circuits/poseidon.circom) su Fr di BN254
+//
+// Serve a calcolare fuori dal circuito gli stessi impegni che il circuito
+// vincola (committeeRoot in bls_aggregate_verify.circom). Parametri di
+// circomlib: S-box x^5, R_F = 8 round completi, R_P dalla tabella per
+// larghezza, costanti di round e matrice MDS (di Cauchy) generate con il
+// Grain LFSR del paper di Poseidon. Lo stato iniziale è [0, input...] e
+// l'output è il primo elemento dello stato finale.
+//
+// Qui serve solo la larghezza t = 3 (due input): i parametri si generano
+// una volta e restano in cache.
+
+use ark_bn254::Fr;
+use ark_ff::{Field, PrimeField, Zero};
+use num_bigint::BigUint;
+use std::sync::OnceLock;
+
+/// Round completi (metà all'inizio, metà alla fine)
+const FULL_ROUNDS: usize = 8;
+/// Round parziali per t = 2..=17, come in circomlib
+const PARTIAL_ROUNDS: [usize; 16] = [
+    56, 57, 56, 60, 60, 63, 64, 63, 60, 66, 60, 65, 70, 60, 64, 68,
+];
+/// Larghezza dello stato per Poseidon(2)
+const WIDTH: usize = 3;
+
+struct Params {
+    partial_rounds: usize,
+    round_constants: Vec<Fr>,
+    mds: [[Fr; WIDTH]; WIDTH],
+}
+
+/// Poseidon(2) di circomlib: hash di due elementi di Fr
+pub(crate) fn poseidon2(a: Fr, b: Fr) -> Fr {
+    let params = params();
+    let mut state = [Fr::zero(), a, b];
+    let half = FULL_ROUNDS / 2;
+    for round in 0..FULL_ROUNDS + params.partial_rounds {
+        for (i, s) in state.iter_mut().enumerate() {
+            *s += params.round_constants[round * WIDTH + i];
+        }
+        if round < half || round >= half + params.partial_rounds {
+            state.iter_mut().for_each(|s| *s = sbox(*s));
+        } else {
+            state[0] = sbox(state[0]);
+        }
+        state = std::array::from_fn(|i| (0..WIDTH).map(|j| params.mds[i][j] * state[j]).sum());
+    }
+    state[0]
+}
+
+fn sbox(x: Fr) -> Fr {
+    let x2 = x.square();
+    x2.square() * x
+}
+
+fn params() -> &'static Params {
+    static PARAMS: OnceLock<Params> = OnceLock::new();
+    PARAMS.get_or_init(|| {
+        let partial_rounds = PARTIAL_ROUNDS[WIDTH - 2];
+        let mut grain = Grain::new(WIDTH, partial_rounds);
+        let modulus: BigUint = Fr::MODULUS.into();
+
+        // Costanti di round: campionamento con rifiuto dei valori >= r
+        let round_constants = (0..(FULL_ROUNDS + partial_rounds) * WIDTH)
+            .map(|_| loop {
+                let value = grain.next_field_bits();
+                if value < modulus {
+                    break Fr::from(value);
+                }
+            })
+            .collect();
+
+        // MDS di Cauchy: M[i][j] = 1 / (x_i + y_j), con x e y ridotti mod r
+        let points: Vec<Fr> = (0..2 * WIDTH)
+            .map(|_| Fr::from(grain.next_field_bits()))
+            .collect();
+        let (xs, ys) = points.split_at(WIDTH);
+        let mds = std::array::from_fn(|i| {
+            std::array::from_fn(|j| {
+                (xs[i] + ys[j])
+                    .inverse()
+                    .expect("parametri di Poseidon: x_i + y_j nullo")
+            })
+        });
+
+        Params {
+            partial_rounds,
+            round_constants,
+            mds,
+        }
+    })
+}
+
+/// Grain LFSR a 80 bit del paper di Poseidon, inizializzato con i parametri
+/// dell'istanza (campo primo, S-box x^alpha, n = 254, t, R_F, R_P)
+struct Grain {
+    state: [bool; 80],
+}
+
+impl Grain {
+    fn new(width: usize, partial_rounds: usize) -> Self {
+        let mut bits = Vec::with_capacity(80);
+        let mut push = |value: usize, len: usize| {
+            bits.extend((0..len).rev().map(|i| (value >> i) & 1 == 1));
+        };
+        push(1, 2);
+        push(0, 4);
+        push(Fr::MODULUS_BIT_SIZE as usize, 12);
+        push(width, 12);
+        push(FULL_ROUNDS, 10);
+        push(partial_rounds, 10);
+        bits.extend([true; 30]);
+
+        let mut grain = Grain {
+            state: bits.try_into().expect("stato Grain di 80 bit"),
+        };
+        for _ in 0..160 {
+            grain.step();
+        }
+        grain
+    }
+
+    fn step(&mut self) -> bool {
+        let s = &self.state;
+        let bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0];
+        self.state.copy_within(1.., 0);
+        self.state[79] = bit;
+        bit
+    }
+
+    /// Bit in uscita con lo scarto a coppie: si tiene il secondo bit solo se
+    /// il primo vale 1
+    fn next_bit(&mut self) -> bool {
+        loop {
+            if self.step() {
+                return self.step();
+            }
+            self.step();
+        }
+    }
+
+    /// Intero di 254 bit, big endian
+    fn next_field_bits(&mut self) -> BigUint {
+        (0..Fr::MODULUS_BIT_SIZE).fold(BigUint::default(), |acc, _| {
+            (acc << 1u8) + BigUint::from(self.next_bit() as u8)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_poseidon2_matches_circomlib() {
+        // Valore di riferimento di circomlibjs: poseidon([1, 2])
+        let expected = Fr::from_str(
+            "7853200120776062878684798364095072458815029376092732009249414926327459813530",
+        )
+        .unwrap();
+        assert_eq!(poseidon2(Fr::from(1u64), Fr::from(2u64)), expected);
+    }
+
+    #[test]
+    fn test_poseidon2_parameters() {
+        let params = params();
+        assert_eq!(params.round_constants.len(), (FULL_ROUNDS + 57) * WIDTH);
+        assert_eq!(
+            params.round_constants[0],
+            Fr::from_str(
+                "6745197990210204598374042828761989596302876299545964402857411729872131034734"
+            )
+            .unwrap()
+        );
+    }
+}
@@ -0,0 +1,419 @@
+// prover/src/process.rs
+// Esecuzione controllata dei processi esterni (node, snarkjs)
+//
+// Ogni step gira con timeout opzionale, può essere annullato da un altro
+// thread tramite CancelToken e il processo figlio viene ucciso se la
+// funzione esce per qualsiasi motivo (errore, timeout, panic).
+// La variante async usa tokio::process: l'annullamento è il drop del future.
+//
+// Su unix ogni step parte in un nuovo gruppo di processi e il kill colpisce
+// tutto il gruppo: snarkjs e node possono avviare worker propri, che
+// altrimenti sopravvivrebbero al padre tenendo CPU e pipe.
+
+use crate::ProverError;
+use std::io::Read;
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Massimo numero di byte di stdout/stderr conservati per step (si tiene la coda)
+const MAX_CAPTURED_OUTPUT: usize = 64 * 1024;
+
+/// Intervallo di polling del processo figlio
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Annullamento cooperativo di una prova in corso.
+/// I clone condividono lo stesso stato: `cancel()` da un thread interrompe
+/// lo step in esecuzione su un altro.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Avvia il figlio come leader di un nuovo gruppo di processi (pgid = pid)
+fn new_process_group(command: &mut Command) -> &mut Command {
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+    command
+}
+
+/// SIGKILL a tutto il gruppo guidato da `pid`
+fn kill_process_group(pid: u32) {
+    #[cfg(unix)]
+    if let Ok(pid) = libc::pid_t::try_from(pid) {
+        // SAFETY: kill non accede a memoria; un pgid negativo indica il gruppo
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = pid;
+}
+
+/// Figlio (con il suo gruppo di processi) ucciso al drop se ancora in esecuzione
+struct KillOnDrop(Child);
+
+impl Drop for KillOnDrop {
+    fn drop(&mut self) {
+        if let Ok(None) = self.0.try_wait() {
+            kill_process_group(self.0.id());
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+}
+
+/// Controparte async di [`KillOnDrop`]: kill_on_drop di tokio colpisce solo il
+/// figlio diretto, il gruppo va ucciso finché il figlio non è stato atteso
+struct KillGroupOnDrop(Option<u32>);
+
+impl KillGroupOnDrop {
+    fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for KillGroupOnDrop {
+    fn drop(&mut self) {
+        if let Some(pid) = self.0 {
+            kill_process_group(pid);
+        }
+    }
+}
+
+type Captured = Arc<Mutex<Vec<u8>>>;
+
+/// Legge `source` fino a EOF tenendo solo gli ultimi MAX_CAPTURED_OUTPUT byte
+fn capture(source: Option<impl Read + Send + 'static>) -> (Captured, Option<JoinHandle<()>>) {
+    let buffer = Captured::default();
+    let handle = source.map(|mut source| {
+        let buffer = buffer.clone();
+        std::thread::spawn(move || {
+            let mut chunk = [0u8; 8192];
+            while let Ok(n) = source.read(&mut chunk) {
+                if n == 0 {
+                    break;
+                }
+                append_capped(&buffer, &chunk[..n]);
+            }
+        })
+    });
+    (buffer, handle)
+}
+
+/// Aggiunge `chunk` a `buffer` mantenendo al massimo MAX_CAPTURED_OUTPUT byte
+fn append_capped(buffer: &Captured, chunk: &[u8]) {
+    let mut buffer = buffer.lock().unwrap_or_else(|e| e.into_inner());
+    buffer.extend_from_slice(chunk);
+    if buffer.len() > MAX_CAPTURED_OUTPUT {
+        let excess = buffer.len() - MAX_CAPTURED_OUTPUT;
+        buffer.drain(..excess);
+    }
+}
+
+fn take(buffer: &Captured) -> Vec<u8> {
+    std::mem::take(&mut *buffer.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+fn snapshot(buffer: &Captured) -> String {
+    let buffer = buffer.lock().unwrap_or_else(|e| e.into_inner());
+    String::from_utf8_lossy(&buffer).trim().to_string()
+}
+
+/// Esegue `command` e restituisce l'output se termina con successo.
+/// Exit code != 0, timeout e annullamento diventano errori con stdout/stderr catturati.
+pub(crate) fn run_step(
+    mut command: Command,
+    name: &str,
+    timeout: Option<Duration>,
+    cancel: &CancelToken,
+) -> Result<Output, ProverError> {
+    if cancel.is_cancelled() {
+        return Err(ProverError::Cancelled {
+            command: name.to_string(),
+        });
+    }
+
+    let mut child = KillOnDrop(
+        new_process_group(&mut command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?,
+    );
+    let (stdout, stdout_reader) = capture(child.0.stdout.take());
+    let (stderr, stderr_reader) = capture(child.0.stderr.take());
+
+    let deadline = timeout.map(|t| Instant::now() + t);
+    let status: ExitStatus = loop {
+        if let Some(status) = child.0.try_wait()? {
+            break status;
+        }
+        if cancel.is_cancelled() {
+            return Err(ProverError::Cancelled {
+                command: name.to_string(),
+            });
+        }
+        if let (Some(deadline), Some(timeout)) = (deadline, timeout) {
+            if Instant::now() >= deadline {
+                // Kill prima dello snapshot: i reader non vanno joinati, eventuali
+                // nipoti potrebbero tenere aperte le pipe
+                drop(child);
+                return Err(ProverError::Timeout {
+                    command: name.to_string(),
+                    timeout,
+                    stdout: snapshot(&stdout),
+                    stderr: snapshot(&stderr),
+                });
+            }
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    for reader in [stdout_reader, stderr_reader].into_iter().flatten() {
+        let _ = reader.join();
+    }
+    let output = Output {
+        status,
+        stdout: take(&stdout),
+        stderr: take(&stderr),
+    };
+
+    if !output.status.success() {
+        return Err(ProverError::subprocess(name, &output));
+    }
+    Ok(output)
+}
+
+/// Come `capture`, su una pipe tokio
+fn capture_async(
+    source: Option<impl AsyncRead + Unpin + Send + 'static>,
+) -> (Captured, Option<tokio::task::JoinHandle<()>>) {
+    let buffer = Captured::default();
+    let handle = source.map(|mut source| {
+        let buffer = buffer.clone();
+        tokio::spawn(async move {
+            let mut chunk = [0u8; 8192];
+            while let Ok(n) = source.read(&mut chunk).await {
+                if n == 0 {
+                    break;
+                }
+                append_capped(&buffer, &chunk[..n]);
+            }
+        })
+    });
+    (buffer, handle)
+}
+
+/// Variante async di [`run_step`]. Se il future viene droppato (es.
+/// `tokio::select!` o `timeout` esterno) muore tutto il gruppo del processo.
+pub(crate) async fn run_step_async(
+    mut command: Command,
+    name: &str,
+    timeout: Option<Duration>,
+) -> Result<Output, ProverError> {
+    new_process_group(&mut command);
+    let mut command = tokio::process::Command::from(command);
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let mut group = KillGroupOnDrop(child.id());
+    let (stdout, stdout_reader) = capture_async(child.stdout.take());
+    let (stderr, stderr_reader) = capture_async(child.stderr.take());
+
+    let status = match timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, child.wait()).await {
+            Ok(status) => status?,
+            Err(_) => {
+                drop(group);
+                let _ = child.kill().await;
+                return Err(ProverError::Timeout {
+                    command: name.to_string(),
+                    timeout,
+                    stdout: snapshot(&stdout),
+                    stderr: snapshot(&stderr),
+                });
+            }
+        },
+        None => child.wait().await?,
+    };
+    // Figlio atteso: il pid può essere riusato, il gruppo non va più toccato
+    group.disarm();
+
+    for reader in [stdout_reader, stderr_reader].into_iter().flatten() {
+        let _ = reader.await;
+    }
+    let output = Output {
+        status,
+        stdout: take(&stdout),
+        stderr: take(&stderr),
+    };
+
+    if !output.status.success() {
+        return Err(ProverError::subprocess(name, &output));
+    }
+    Ok(output)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn sh(script: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        command
+    }
+
+    #[test]
+    fn test_run_step_captures_failure_output() {
+        let err = run_step(
+            sh("echo progress; echo boom >&2; exit 3"),
+            "failing step",
+            None,
+            &CancelToken::new(),
+        )
+        .unwrap_err();
+
+        match err {
+            ProverError::Subprocess {
+                code,
+                stdout,
+                stderr,
+                ..
+            } => {
+                assert_eq!(code, Some(3));
+                assert_eq!(stdout, "progress");
+                assert_eq!(stderr, "boom");
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_run_step_timeout_and_cancel() {
+        let start = Instant::now();
+        let err = run_step(
+            sh("echo started; exec sleep 30"),
+            "hung step",
+            Some(Duration::from_millis(200)),
+            &CancelToken::new(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ProverError::Timeout { .. }), "{err}");
+        assert!(start.elapsed() < Duration::from_secs(10));
+
+        let cancel = CancelToken::new();
+        let remote = cancel.clone();
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            remote.cancel();
+        });
+        let err = run_step(sh("exec sleep 30"), "cancelled step", None, &cancel).unwrap_err();
+        canceller.join().unwrap();
+        assert!(matches!(err, ProverError::Cancelled { .. }), "{err}");
+    }
+
+    /// Vero se il processo esiste e non è uno zombie
+    #[cfg(target_os = "linux")]
+    fn process_alive(pid: &str) -> bool {
+        std::fs::read_to_string(format!("/proc/{pid}/stat"))
+            .map(|stat| !stat.contains(") Z "))
+            .unwrap_or(false)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn assert_killed(pid: &str) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while process_alive(pid) {
+            assert!(Instant::now() < deadline, "process {pid} survived the step");
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_run_step_timeout_kills_process_group() {
+        let err = run_step(
+            sh("sleep 30 & echo $!; wait"),
+            "step with worker",
+            Some(Duration::from_millis(200)),
+            &CancelToken::new(),
+        )
+        .unwrap_err();
+        match err {
+            ProverError::Timeout { stdout, .. } => assert_killed(&stdout),
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_run_step_async_kills_process_group() {
+        let err = run_step_async(
+            sh("sleep 30 & echo $!; wait"),
+            "step with worker",
+            Some(Duration::from_millis(200)),
+        )
+        .await
+        .unwrap_err();
+        match err {
+            ProverError::Timeout { stdout, .. } => assert_killed(&stdout),
+            other => panic!("unexpected error: {other}"),
+        }
+
+        // Future droppato a metà step
+        let pid_file = tempfile::NamedTempFile::new().unwrap();
+        let mut command = sh("sleep 30 & echo $! > \"$0\"; wait");
+        command.arg(pid_file.path());
+        let step = tokio::spawn(run_step_async(command, "dropped step", None));
+        let pid = loop {
+            let pid = std::fs::read_to_string(pid_file.path()).unwrap();
+            if !pid.trim().is_empty() {
+                break pid.trim().to_string();
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        };
+        step.abort();
+        let _ = step.await;
+        assert_killed(&pid);
+    }
+
+    #[tokio::test]
+    async fn test_run_step_async() {
+        let output = run_step_async(sh("echo ok"), "async step", None)
+            .await
+            .unwrap();
+        assert_eq!(output.stdout, b"ok\n");
+
+        let err = run_step_async(
+            sh("echo late >&2; exec sleep 30"),
+            "hung step",
+            Some(Duration::from_millis(200)),
+        )
+        .await
+        .unwrap_err();
+        match err {
+            ProverError::Timeout { stderr, .. } => assert_eq!(stderr, "late"),
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+}
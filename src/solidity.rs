@@ -0,0 +1,448 @@
+// prover/src/solidity.rs
+// Generatore del contratto Verifier.sol a partire dalla VerifyingKey
+//
+// Sostituisce `snarkjs zkey export solidityverifier`: il contratto generato
+// espone verifyProof(uint256[2],uint256[2][2],uint256[2],uint256[]) con gli
+// input pubblici come array dinamico, la signature chiamata da
+// ZKRollupBLS._verifyGroth16Proof.
+//
+// Groth16BatchVerifier verifica n prove della stessa VK con un solo
+// controllo di pairing di n + 3 coppie (come batch_verify.rs): con ρ_i
+// derivati con keccak256 da tutte le prove,
+//   Π e(-ρ_i·A_i, B_i) · e(Σρ_i·α, β) · e(Σρ_i·vk_x_i, γ) · e(Σρ_i·C_i, δ) = 1
+// È il percorso on-chain per i batch: la prova aggregata TIPP/MIPP
+// (aggregation.rs) richiede aritmetica in GT e si verifica off-chain.
+
+use crate::export::VkExporter;
+use ark_bn254::{Bn254, Fq, Fr};
+use ark_ff::PrimeField;
+use ark_groth16::VerifyingKey;
+use num_bigint::BigUint;
+use std::fmt::Write;
+
+const VERIFIER_TEMPLATE: &str = r#"// SPDX-License-Identifier: GPL-3.0
+// Generato da bls-zk-prover: non modificare a mano
+pragma solidity >=0.8.0 <0.9.0;
+
+contract Groth16Verifier {
+    // Scalar field size
+    uint256 constant r = <%r%>;
+    // Base field size
+    uint256 constant q = <%q%>;
+
+<%vk_constants%>
+    uint256 constant nPublic = <%n_public%>;
+
+    function verifyProof(
+        uint256[2] calldata _pA,
+        uint256[2][2] calldata _pB,
+        uint256[2] calldata _pC,
+        uint256[] calldata _pubSignals
+    ) public view returns (bool) {
+        if (_pubSignals.length != nPublic) return false;
+        for (uint256 i = 0; i < nPublic; i++) {
+            if (_pubSignals[i] >= r) return false;
+        }
+        if (_pA[0] >= q || _pA[1] >= q || _pC[0] >= q || _pC[1] >= q) return false;
+        if (_pB[0][0] >= q || _pB[0][1] >= q || _pB[1][0] >= q || _pB[1][1] >= q) return false;
+
+        // vk_x = IC0 + sum(input[i] * IC[i + 1])
+        uint256[2] memory vkX = [IC0x, IC0y];
+<%ic_terms%>
+        uint256[24] memory input = [
+            // -A, B
+            _pA[0], (q - (_pA[1] % q)) % q,
+            _pB[0][0], _pB[0][1], _pB[1][0], _pB[1][1],
+            // alpha, beta
+            alphax, alphay,
+            betax1, betax2, betay1, betay2,
+            // vk_x, gamma
+            vkX[0], vkX[1],
+            gammax1, gammax2, gammay1, gammay2,
+            // C, delta
+            _pC[0], _pC[1],
+            deltax1, deltax2, deltay1, deltay2
+        ];
+        return _pairing(input);
+    }
+
+    function _ecAdd(uint256[2] memory p1, uint256[2] memory p2)
+        internal
+        view
+        returns (bool ok, uint256[2] memory out)
+    {
+        uint256[4] memory input = [p1[0], p1[1], p2[0], p2[1]];
+        assembly {
+            ok := staticcall(sub(gas(), 2000), 0x06, input, 0x80, out, 0x40)
+        }
+    }
+
+    function _ecMul(uint256[2] memory p, uint256 s)
+        internal
+        view
+        returns (bool ok, uint256[2] memory out)
+    {
+        uint256[3] memory input = [p[0], p[1], s];
+        assembly {
+            ok := staticcall(sub(gas(), 2000), 0x07, input, 0x60, out, 0x40)
+        }
+    }
+
+    function _pairing(uint256[24] memory input) internal view returns (bool) {
+        bool ok;
+        uint256[1] memory out;
+        assembly {
+            ok := staticcall(sub(gas(), 2000), 0x08, input, 0x300, out, 0x20)
+        }
+        return ok && out[0] == 1;
+    }
+}
+"#;
+
+const BATCH_VERIFIER_TEMPLATE: &str = r#"// SPDX-License-Identifier: GPL-3.0
+// Generato da bls-zk-prover: non modificare a mano
+pragma solidity >=0.8.0 <0.9.0;
+
+contract Groth16BatchVerifier {
+    // Scalar field size
+    uint256 constant r = <%r%>;
+    // Base field size
+    uint256 constant q = <%q%>;
+
+<%vk_constants%>
+    uint256 constant nPublic = <%n_public%>;
+
+    /// Verifica n prove con un solo pairing di n + 3 coppie:
+    /// prod(e(-rho_i A_i, B_i)) e(sum(rho_i) alpha, beta) e(sum(rho_i vk_x_i), gamma)
+    /// e(sum(rho_i C_i), delta) == 1, con rho_i da keccak256 di tutte le prove
+    function verifyBatch(
+        uint256[2][] calldata _pA,
+        uint256[2][2][] calldata _pB,
+        uint256[2][] calldata _pC,
+        uint256[][] calldata _pubSignals
+    ) public view returns (bool) {
+        uint256 n = _pA.length;
+        if (n == 0 || _pB.length != n || _pC.length != n || _pubSignals.length != n) {
+            return false;
+        }
+        for (uint256 i = 0; i < n; i++) {
+            if (!_inRange(_pA[i], _pB[i], _pC[i], _pubSignals[i])) return false;
+        }
+
+        uint256[] memory rho = _challenges(keccak256(abi.encode(_pA, _pB, _pC, _pubSignals)), n);
+        uint256[] memory input = new uint256[]((n + 3) * 6);
+        return _abTerms(input, rho, _pA, _pB)
+            && _vkTerms(input, rho, _pubSignals)
+            && _cTerm(input, rho, _pC)
+            && _pairing(input);
+    }
+
+    function _inRange(
+        uint256[2] calldata _pA,
+        uint256[2][2] calldata _pB,
+        uint256[2] calldata _pC,
+        uint256[] calldata _pubSignals
+    ) internal pure returns (bool) {
+        if (_pubSignals.length != nPublic) return false;
+        for (uint256 i = 0; i < nPublic; i++) {
+            if (_pubSignals[i] >= r) return false;
+        }
+        if (_pA[0] >= q || _pA[1] >= q || _pC[0] >= q || _pC[1] >= q) return false;
+        return _pB[0][0] < q && _pB[0][1] < q && _pB[1][0] < q && _pB[1][1] < q;
+    }
+
+    function _challenges(bytes32 seed, uint256 n) internal pure returns (uint256[] memory rho) {
+        rho = new uint256[](n);
+        for (uint256 i = 0; i < n; i++) {
+            rho[i] = uint256(keccak256(abi.encode(seed, i))) % r;
+        }
+    }
+
+    // -rho_i A_i, B_i
+    function _abTerms(
+        uint256[] memory input,
+        uint256[] memory rho,
+        uint256[2][] calldata _pA,
+        uint256[2][2][] calldata _pB
+    ) internal view returns (bool) {
+        bool ok;
+        uint256[2] memory term;
+        for (uint256 i = 0; i < rho.length; i++) {
+            (ok, term) = _ecMul(_pA[i], r - rho[i]);
+            if (!ok) return false;
+            _store(input, i * 6, term, [_pB[i][0][0], _pB[i][0][1], _pB[i][1][0], _pB[i][1][1]]);
+        }
+        return true;
+    }
+
+    // sum(rho_i) alpha, beta e sum(rho_i vk_x_i), gamma
+    function _vkTerms(
+        uint256[] memory input,
+        uint256[] memory rho,
+        uint256[][] calldata _pubSignals
+    ) internal view returns (bool) {
+        uint256[] memory coeff = _icCoefficients(rho, _pubSignals);
+        uint256 o = rho.length * 6;
+        bool ok;
+        uint256[2] memory term;
+
+        (ok, term) = _ecMul([alphax, alphay], coeff[0]);
+        if (!ok) return false;
+        _store(input, o, term, [betax1, betax2, betay1, betay2]);
+
+        // sum(rho_i vk_x_i) = sum(coeff[j] IC[j])
+        uint256[2] memory vkX;
+        (ok, vkX) = _ecMul([IC0x, IC0y], coeff[0]);
+        if (!ok) return false;
+<%ic_terms%>
+        _store(input, o + 6, vkX, [gammax1, gammax2, gammay1, gammay2]);
+        return true;
+    }
+
+    // Coefficienti di IC: sum(rho_i) per IC0, sum(rho_i input_i[j]) per IC[j + 1]
+    function _icCoefficients(uint256[] memory rho, uint256[][] calldata _pubSignals)
+        internal
+        pure
+        returns (uint256[] memory coeff)
+    {
+        coeff = new uint256[](nPublic + 1);
+        for (uint256 i = 0; i < rho.length; i++) {
+            coeff[0] = addmod(coeff[0], rho[i], r);
+            for (uint256 j = 0; j < nPublic; j++) {
+                coeff[j + 1] = addmod(coeff[j + 1], mulmod(rho[i], _pubSignals[i][j], r), r);
+            }
+        }
+    }
+
+    // sum(rho_i C_i), delta
+    function _cTerm(uint256[] memory input, uint256[] memory rho, uint256[2][] calldata _pC)
+        internal
+        view
+        returns (bool)
+    {
+        bool ok;
+        uint256[2] memory term;
+        uint256[2] memory acc;
+        for (uint256 i = 0; i < rho.length; i++) {
+            (ok, term) = _ecMul(_pC[i], rho[i]);
+            if (!ok) return false;
+            (ok, acc) = _ecAdd(acc, term);
+            if (!ok) return false;
+        }
+        _store(input, (rho.length + 2) * 6, acc, [deltax1, deltax2, deltay1, deltay2]);
+        return true;
+    }
+
+    function _store(
+        uint256[] memory input,
+        uint256 o,
+        uint256[2] memory p,
+        uint256[4] memory g2
+    ) internal pure {
+        input[o] = p[0];
+        input[o + 1] = p[1];
+        input[o + 2] = g2[0];
+        input[o + 3] = g2[1];
+        input[o + 4] = g2[2];
+        input[o + 5] = g2[3];
+    }
+
+    function _ecAdd(uint256[2] memory p1, uint256[2] memory p2)
+        internal
+        view
+        returns (bool ok, uint256[2] memory out)
+    {
+        uint256[4] memory input = [p1[0], p1[1], p2[0], p2[1]];
+        assembly {
+            ok := staticcall(sub(gas(), 2000), 0x06, input, 0x80, out, 0x40)
+        }
+    }
+
+    function _ecMul(uint256[2] memory p, uint256 s)
+        internal
+        view
+        returns (bool ok, uint256[2] memory out)
+    {
+        uint256[3] memory input = [p[0], p[1], s];
+        assembly {
+            ok := staticcall(sub(gas(), 2000), 0x07, input, 0x60, out, 0x40)
+        }
+    }
+
+    function _pairing(uint256[] memory input) internal view returns (bool) {
+        bool ok;
+        uint256[1] memory out;
+        assembly {
+            ok := staticcall(sub(gas(), 2000), 0x08, add(input, 0x20), mul(mload(input), 0x20), out, 0x20)
+        }
+        return ok && out[0] == 1;
+    }
+}
+"#;
+
+/// Genera il sorgente Solidity di un verifier Groth16 per `vk`
+pub fn generate_solidity_verifier(vk: &VerifyingKey<Bn254>) -> String {
+    let n_public = vk.gamma_abc_g1.len().saturating_sub(1);
+
+    // Con nPublic == 0 vk_x è IC0: niente variabili d'appoggio, che solc
+    // segnalerebbe come inutilizzate
+    let mut ic_terms = String::new();
+    if n_public > 0 {
+        ic_terms.push_str("        bool ok;\n        uint256[2] memory term;\n");
+    }
+    for i in 0..n_public {
+        let _ = writeln!(
+            ic_terms,
+            "        (ok, term) = _ecMul([IC{}x, IC{}y], _pubSignals[{}]);\n        if (!ok) return false;",
+            i + 1,
+            i + 1,
+            i
+        );
+        let _ = writeln!(
+            ic_terms,
+            "        (ok, vkX) = _ecAdd(vkX, term);\n        if (!ok) return false;"
+        );
+    }
+
+    VERIFIER_TEMPLATE
+        .replace("<%r%>", &BigUint::from(Fr::MODULUS).to_string())
+        .replace("<%q%>", &BigUint::from(Fq::MODULUS).to_string())
+        .replace(
+            "<%vk_constants%>",
+            &VkExporter::new(vk).solidity_constants(),
+        )
+        .replace("<%n_public%>", &n_public.to_string())
+        .replace("<%ic_terms%>", &ic_terms)
+}
+
+/// Genera il sorgente Solidity di Groth16BatchVerifier per `vk`: verifyBatch
+/// accetta gli array di pA, pB, pC e pubSignals di n prove (layout di
+/// soliditycalldata) e le verifica con un solo pairing
+pub fn generate_solidity_batch_verifier(vk: &VerifyingKey<Bn254>) -> String {
+    let n_public = vk.gamma_abc_g1.len().saturating_sub(1);
+
+    let mut ic_terms = String::new();
+    for j in 1..=n_public {
+        let _ = writeln!(
+            ic_terms,
+            "        (ok, term) = _ecMul([IC{}x, IC{}y], coeff[{}]);\n        if (!ok) return false;",
+            j, j, j
+        );
+        let _ = writeln!(
+            ic_terms,
+            "        (ok, vkX) = _ecAdd(vkX, term);\n        if (!ok) return false;"
+        );
+    }
+
+    BATCH_VERIFIER_TEMPLATE
+        .replace("<%r%>", &BigUint::from(Fr::MODULUS).to_string())
+        .replace("<%q%>", &BigUint::from(Fq::MODULUS).to_string())
+        .replace(
+            "<%vk_constants%>",
+            &VkExporter::new(vk).solidity_constants(),
+        )
+        .replace("<%n_public%>", &n_public.to_string())
+        .replace("<%ic_terms%>", &ic_terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::tests::test_vk;
+    use ark_bn254::{G1Affine, G2Affine};
+    use ark_ec::AffineRepr;
+
+    #[test]
+    fn test_solidity_verifier_signature() {
+        let vk = test_vk(3);
+
+        let source = generate_solidity_verifier(&vk);
+        assert!(source.contains("uint256[] calldata _pubSignals"));
+        assert!(source.contains("uint256 constant nPublic = 3;"));
+        assert!(source.contains("_ecMul([IC3x, IC3y], _pubSignals[2])"));
+        assert!(!source.contains("IC4x"));
+        assert!(!source.contains("<%"));
+        assert!(source.contains(&format!(
+            "uint256 constant r = {};",
+            BigUint::from(Fr::MODULUS)
+        )));
+    }
+
+    /// Verifier della fixture test/fixtures/multiplier, deployati dai test hardhat
+    /// (test/Groth16Verifier.test.js) contro prove snarkjs reali
+    const MULTIPLIER_VERIFIER: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/contracts/test/MultiplierVerifier.sol"
+    );
+    const MULTIPLIER_BATCH_VERIFIER: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/contracts/test/MultiplierBatchVerifier.sol"
+    );
+
+    fn assert_multiplier_contract_in_sync(
+        path: &str,
+        generate: fn(&VerifyingKey<Bn254>) -> String,
+    ) {
+        let vk = crate::SnarkjsVerificationKey::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/test/fixtures/multiplier/build/verification_key.json"
+        ))
+        .unwrap()
+        .to_arkworks_vk()
+        .unwrap();
+        let source = generate(&vk);
+        if std::env::var_os("UPDATE_FIXTURES").is_some() {
+            std::fs::write(path, &source).unwrap();
+        }
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            source,
+            "{} non aggiornato: rigenerarlo con UPDATE_FIXTURES=1 cargo test",
+            path
+        );
+    }
+
+    #[test]
+    fn test_multiplier_verifier_in_sync() {
+        assert_multiplier_contract_in_sync(MULTIPLIER_VERIFIER, generate_solidity_verifier);
+    }
+
+    #[test]
+    fn test_multiplier_batch_verifier_in_sync() {
+        assert_multiplier_contract_in_sync(
+            MULTIPLIER_BATCH_VERIFIER,
+            generate_solidity_batch_verifier,
+        );
+    }
+
+    #[test]
+    fn test_solidity_batch_verifier() {
+        let vk = test_vk(2);
+
+        let source = generate_solidity_batch_verifier(&vk);
+        assert!(source.contains("contract Groth16BatchVerifier"));
+        assert!(source.contains("uint256[][] calldata _pubSignals"));
+        assert!(source.contains("uint256 constant nPublic = 2;"));
+        assert!(source.contains("_ecMul([IC2x, IC2y], coeff[2])"));
+        assert!(!source.contains("IC3x"));
+        assert!(!source.contains("<%"));
+    }
+
+    #[test]
+    fn test_solidity_verifier_without_public_inputs() {
+        let vk = VerifyingKey::<Bn254> {
+            alpha_g1: G1Affine::generator(),
+            beta_g2: G2Affine::generator(),
+            gamma_g2: G2Affine::generator(),
+            delta_g2: G2Affine::generator(),
+            gamma_abc_g1: vec![G1Affine::generator()],
+        };
+
+        let source = generate_solidity_verifier(&vk);
+        assert!(source.contains("uint256 constant nPublic = 0;"));
+        assert!(source.contains("uint256[2] memory vkX = [IC0x, IC0y];"));
+        assert!(!source.contains("memory term"));
+        assert!(!source.contains("(ok, vkX)"));
+        assert!(!source.contains("IC1x"));
+    }
+}
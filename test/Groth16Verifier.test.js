@@ -0,0 +1,124 @@
+const { expect } = require("chai");
+const { ethers } = require("hardhat");
+const path = require("path");
+const snarkjs = require("snarkjs");
+
+/**
+ * Verifier.sol generato da generate_solidity_verifier (src/solidity.rs)
+ *
+ * contracts/test/MultiplierVerifier.sol è generato dalla VK della fixture
+ * test/fixtures/multiplier (c = a * b, un input pubblico); il test Rust
+ * test_multiplier_verifier_in_sync lo tiene allineato al generatore.
+ * Qui il contratto viene deployato e riceve prove reali prodotte da snarkjs
+ * con wasm e zkey della stessa fixture.
+ */
+describe("Groth16Verifier generato", function () {
+    const fixture = path.join(__dirname, "fixtures", "multiplier", "build");
+    const wasm = path.join(fixture, "multiplier_js", "multiplier.wasm");
+    const zkey = path.join(fixture, "multiplier_final.zkey");
+
+    let verifier;
+    let calldata;
+
+    // Stesso layout di `snarkjs zkey export soliditycalldata`
+    async function proveMultiplier(a, b) {
+        const { proof, publicSignals } = await snarkjs.groth16.fullProve({ a, b }, wasm, zkey);
+        const raw = await snarkjs.groth16.exportSolidityCallData(proof, publicSignals);
+        const [pA, pB, pC, pubSignals] = JSON.parse(`[${raw}]`);
+        return { pA, pB, pC, pubSignals };
+    }
+
+    before(async function () {
+        const Verifier = await ethers.getContractFactory(
+            "contracts/test/MultiplierVerifier.sol:Groth16Verifier"
+        );
+        verifier = await Verifier.deploy();
+        await verifier.waitForDeployment();
+
+        calldata = await proveMultiplier(3, 11);
+    });
+
+    it("Dovrebbe accettare una prova snarkjs reale", async function () {
+        const { pA, pB, pC, pubSignals } = calldata;
+        expect(pubSignals.map(BigInt)).to.deep.equal([33n]);
+        expect(await verifier.verifyProof(pA, pB, pC, pubSignals)).to.equal(true);
+    });
+
+    it("Dovrebbe rifiutare input pubblici sbagliati", async function () {
+        const { pA, pB, pC } = calldata;
+        expect(await verifier.verifyProof(pA, pB, pC, [34])).to.equal(false);
+        expect(await verifier.verifyProof(pA, pB, pC, [])).to.equal(false);
+        expect(await verifier.verifyProof(pA, pB, pC, [33, 0])).to.equal(false);
+    });
+
+    it("Dovrebbe rifiutare una prova manomessa", async function () {
+        const { pA, pB, pC, pubSignals } = calldata;
+        // C di un'altra prova valida: punto sulla curva ma equazione non soddisfatta
+        const other = await proveMultiplier(5, 7);
+        expect(await verifier.verifyProof(pA, pB, other.pC, pubSignals)).to.equal(false);
+        // A fuori dalla curva: il precompile di pairing fallisce
+        const badA = [pA[0], (BigInt(pA[1]) + 1n).toString()];
+        expect(await verifier.verifyProof(badA, pB, pC, pubSignals)).to.equal(false);
+    });
+});
+
+/**
+ * Groth16BatchVerifier generato da generate_solidity_batch_verifier
+ * (contracts/test/MultiplierBatchVerifier.sol, tenuto allineato da
+ * test_multiplier_batch_verifier_in_sync): n prove con un solo pairing.
+ */
+describe("Groth16BatchVerifier generato", function () {
+    const fixture = path.join(__dirname, "fixtures", "multiplier", "build");
+    const wasm = path.join(fixture, "multiplier_js", "multiplier.wasm");
+    const zkey = path.join(fixture, "multiplier_final.zkey");
+
+    let verifier;
+    let batch;
+
+    async function proveMultiplier(a, b) {
+        const { proof, publicSignals } = await snarkjs.groth16.fullProve({ a, b }, wasm, zkey);
+        const raw = await snarkjs.groth16.exportSolidityCallData(proof, publicSignals);
+        return JSON.parse(`[${raw}]`);
+    }
+
+    // Array di pA, pB, pC e pubSignals nell'ordine di verifyBatch
+    function toBatch(calldata) {
+        return [0, 1, 2, 3].map((k) => calldata.map((c) => c[k]));
+    }
+
+    before(async function () {
+        const Verifier = await ethers.getContractFactory(
+            "contracts/test/MultiplierBatchVerifier.sol:Groth16BatchVerifier"
+        );
+        verifier = await Verifier.deploy();
+        await verifier.waitForDeployment();
+
+        // Tre prove: il batch non deve essere una potenza di 2
+        batch = [await proveMultiplier(3, 11), await proveMultiplier(5, 7), await proveMultiplier(2, 9)];
+    });
+
+    it("Dovrebbe accettare un batch di prove snarkjs reali", async function () {
+        expect(await verifier.verifyBatch(...toBatch(batch))).to.equal(true);
+        expect(await verifier.verifyBatch(...toBatch(batch.slice(0, 1)))).to.equal(true);
+    });
+
+    it("Dovrebbe rifiutare input pubblici sbagliati", async function () {
+        const [pA, pB, pC, pubSignals] = toBatch(batch);
+        const wrong = [pubSignals[0], ["36"], pubSignals[2]];
+        expect(await verifier.verifyBatch(pA, pB, pC, wrong)).to.equal(false);
+        // Input scambiati tra due prove
+        const swapped = [pubSignals[1], pubSignals[0], pubSignals[2]];
+        expect(await verifier.verifyBatch(pA, pB, pC, swapped)).to.equal(false);
+        expect(await verifier.verifyBatch(pA, pB, pC, pubSignals.slice(0, 2))).to.equal(false);
+        expect(await verifier.verifyBatch([], [], [], [])).to.equal(false);
+    });
+
+    it("Dovrebbe rifiutare un batch con una prova manomessa", async function () {
+        const [pA, pB, pC, pubSignals] = toBatch(batch);
+        // C di un'altra prova del batch: la combinazione casuale non si compensa
+        const tampered = [pC[0], pC[0], pC[2]];
+        expect(await verifier.verifyBatch(pA, pB, tampered, pubSignals)).to.equal(false);
+        const badA = [pA[0], [pA[1][0], (BigInt(pA[1][1]) + 1n).toString()], pA[2]];
+        expect(await verifier.verifyBatch(badA, pB, pC, pubSignals)).to.equal(false);
+    });
+});
@@ -24,6 +24,19 @@ describe("ZKRollupBLS", function () {
         };
     }
 
+    // Input pubblici di bls_verify per una firma: H(m) in 4 parole e pk in 8
+    function messageWords(seed) {
+        return Array.from({ length: 4 }, (_, i) => BigInt(seed) * 10n + BigInt(i));
+    }
+
+    function keyWords(seed) {
+        return Array.from({ length: 8 }, (_, i) => BigInt(seed) * 10n + BigInt(i));
+    }
+
+    function wordsHash(type, words) {
+        return ethers.keccak256(ethers.AbiCoder.defaultAbiCoder().encode([type], [words]));
+    }
+
     beforeEach(async function () {
         [deployer, sequencer, user1, user2] = await ethers.getSigners();
 
@@ -33,9 +46,13 @@ describe("ZKRollupBLS", function () {
         await mockVerifier.waitForDeployment();
         const verifierAddress = await mockVerifier.getAddress();
 
-        // Deploy ZKRollupBLS
+        // Deploy ZKRollupBLS: il deployer fa da governance
         const ZKRollupBLS = await ethers.getContractFactory("ZKRollupBLS");
-        zkContract = await ZKRollupBLS.deploy(sequencer.address, verifierAddress);
+        zkContract = await ZKRollupBLS.deploy(
+            sequencer.address,
+            verifierAddress,
+            deployer.address
+        );
         await zkContract.waitForDeployment();
 
         console.log("    Verifier:", verifierAddress);
@@ -57,24 +74,23 @@ describe("ZKRollupBLS", function () {
         it("Dovrebbe avere verifier configurato", async function () {
             expect(await zkContract.verifier()).to.equal(await mockVerifier.getAddress());
         });
+
+        it("NON dovrebbe accettare il sequencer come governance", async function () {
+            const ZKRollupBLS = await ethers.getContractFactory("ZKRollupBLS");
+            await expect(
+                ZKRollupBLS.deploy(
+                    sequencer.address,
+                    await mockVerifier.getAddress(),
+                    sequencer.address
+                )
+            ).to.be.revertedWithCustomError(zkContract, "InvalidGovernance");
+        });
     });
 
     describe("Batch Submission con Groth16 Proof", function () {
         it("Dovrebbe accettare batch con prova valida (struct)", async function () {
-            const messageHashes = [
-                ethers.keccak256(ethers.toUtf8Bytes("message1")),
-                ethers.keccak256(ethers.toUtf8Bytes("message2")),
-            ];
-
-            const publicKeysX = [
-                BigInt("98765432109876543210"),
-                BigInt("98765432109876543211"),
-            ];
-
-            const publicKeysY = [
-                BigInt("11111111111111111111"),
-                BigInt("11111111111111111112"),
-            ];
+            const messageHashes = [messageWords(1), messageWords(2)];
+            const publicKeys = [keyWords(1), keyWords(2)];
 
             const stateRoot = ethers.keccak256(ethers.toUtf8Bytes("new_state"));
             const proof = createMockProof();
@@ -82,14 +98,18 @@ describe("ZKRollupBLS", function () {
             const tx = await zkContract.connect(sequencer).submitBatchWithProof(
                 stateRoot,
                 messageHashes,
-                publicKeysX,
-                publicKeysY,
+                publicKeys,
                 proof
             );
 
             const receipt = await tx.wait();
             console.log("    Gas utilizzato:", receipt.gasUsed.toString());
 
+            // Submission impegnate sulle parole di H(m) e pk
+            const submission = await zkContract.submissions(1);
+            expect(submission.messageHash).to.equal(wordsHash("uint256[4]", messageHashes[1]));
+            expect(submission.publicKeyHash).to.equal(wordsHash("uint256[8]", publicKeys[1]));
+
             // Verifica batch creato
             const batch = await zkContract.batches(0);
             expect(batch.stateRoot).to.equal(stateRoot);
@@ -105,30 +125,26 @@ describe("ZKRollupBLS", function () {
             // Imposta mock per rifiutare
             await mockVerifier.setShouldVerify(false);
 
-            const messageHash = ethers.keccak256(ethers.toUtf8Bytes("msg"));
             const proof = createMockProof();
 
             await expect(
                 zkContract.connect(sequencer).submitBatchWithProof(
                     ethers.ZeroHash,
-                    [messageHash],
-                    [BigInt(123)],
-                    [BigInt(456)],
+                    [messageWords(1)],
+                    [keyWords(1)],
                     proof
                 )
             ).to.be.revertedWithCustomError(zkContract, "InvalidZKProof");
         });
 
         it("NON dovrebbe accettare batch da non-sequencer", async function () {
-            const messageHash = ethers.keccak256(ethers.toUtf8Bytes("msg"));
             const proof = createMockProof();
 
             await expect(
                 zkContract.connect(user1).submitBatchWithProof(
                     ethers.ZeroHash,
-                    [messageHash],
-                    [BigInt(123)],
-                    [BigInt(456)],
+                    [messageWords(1)],
+                    [keyWords(1)],
                     proof
                 )
             ).to.be.revertedWithCustomError(zkContract, "OnlySequencer");
@@ -142,22 +158,19 @@ describe("ZKRollupBLS", function () {
                     ethers.ZeroHash,
                     [],
                     [],
-                    [],
                     proof
                 )
             ).to.be.revertedWithCustomError(zkContract, "EmptyBatch");
         });
 
         it("NON dovrebbe accettare array con length mismatch", async function () {
-            const messageHash = ethers.keccak256(ethers.toUtf8Bytes("msg"));
             const proof = createMockProof();
 
             await expect(
                 zkContract.connect(sequencer).submitBatchWithProof(
                     ethers.ZeroHash,
-                    [messageHash],
-                    [BigInt(123)],
-                    [BigInt(456), BigInt(789)],  // Mismatch!
+                    [messageWords(1)],
+                    [keyWords(1), keyWords(2)],  // Mismatch!
                     proof
                 )
             ).to.be.revertedWithCustomError(zkContract, "LengthMismatch");
@@ -166,12 +179,8 @@ describe("ZKRollupBLS", function () {
 
     describe("Batch Submission con Bytes", function () {
         it("Dovrebbe accettare batch con prova in bytes", async function () {
-            const messageHashes = [
-                ethers.keccak256(ethers.toUtf8Bytes("message1")),
-            ];
-
-            const publicKeysX = [BigInt("12345678901234567890")];
-            const publicKeysY = [BigInt("98765432109876543210")];
+            const messageHashes = [messageWords(1)];
+            const publicKeys = [keyWords(1)];
             const stateRoot = ethers.keccak256(ethers.toUtf8Bytes("state"));
 
             // Crea proof bytes (8 * 32 = 256 bytes)
@@ -180,8 +189,7 @@ describe("ZKRollupBLS", function () {
             const tx = await zkContract.connect(sequencer).submitBatchWithProofBytes(
                 stateRoot,
                 messageHashes,
-                publicKeysX,
-                publicKeysY,
+                publicKeys,
                 proofBytes
             );
 
@@ -192,44 +200,193 @@ describe("ZKRollupBLS", function () {
         });
     });
 
+    describe("Committee Batch con firma aggregata", function () {
+        const committeeRoot = ethers.keccak256(ethers.toUtf8Bytes("committee"));
+
+        async function registerCommittee(verifierAddress) {
+            await zkContract.connect(deployer).setCommittee(
+                committeeRoot,
+                verifierAddress || await mockVerifier.getAddress()
+            );
+        }
+
+        it("Dovrebbe accettare batch del comitato con una sola prova", async function () {
+            await registerCommittee();
+
+            const stateRoot = ethers.keccak256(ethers.toUtf8Bytes("committee_state"));
+            // Partecipanti 0, 2, 3 e 130 di un comitato da 256
+            const bitmap = [BigInt(0b1101), BigInt(1) << BigInt(2)];
+
+            const tx = await zkContract.connect(sequencer).submitCommitteeBatchWithProof(
+                stateRoot,
+                bitmap,
+                createMockProof()
+            );
+
+            const receipt = await tx.wait();
+            console.log("    Gas utilizzato:", receipt.gasUsed.toString());
+
+            const batch = await zkContract.batches(0);
+            expect(batch.numSignatures).to.equal(4);
+            expect(batch.verified).to.be.true;
+            expect(await zkContract.currentStateRoot()).to.equal(stateRoot);
+        });
+
+        it("Dovrebbe legare la prova allo state root e al batch", async function () {
+            const InputsVerifier = await ethers.getContractFactory("MockInputsVerifier");
+            const inputsVerifier = await InputsVerifier.deploy();
+            await inputsVerifier.waitForDeployment();
+            await registerCommittee(await inputsVerifier.getAddress());
+
+            // La prova vale per gli input del batch 0 con questo state root
+            const stateRoot = ethers.keccak256(ethers.toUtf8Bytes("committee_state"));
+            const bitmap = [BigInt(0b1011)];
+            const messageHash = await zkContract.committeeMessageHash(stateRoot);
+            await inputsVerifier.setExpectedInputs([
+                BigInt(committeeRoot),
+                ...messageHash,
+                ...bitmap
+            ]);
+
+            // Stessa prova con un altro state root: H(m) cambia e la prova non vale
+            const otherRoot = ethers.keccak256(ethers.toUtf8Bytes("other_state"));
+            await expect(
+                zkContract.connect(sequencer).submitCommitteeBatchWithProof(
+                    otherRoot,
+                    bitmap,
+                    createMockProof()
+                )
+            ).to.be.revertedWithCustomError(zkContract, "InvalidZKProof");
+
+            await zkContract.connect(sequencer).submitCommitteeBatchWithProof(
+                stateRoot,
+                bitmap,
+                createMockProof()
+            );
+            expect(await zkContract.currentStateRoot()).to.equal(stateRoot);
+
+            // Ripresentata per il batch 1: il messaggio contiene batchCount
+            await expect(
+                zkContract.connect(sequencer).submitCommitteeBatchWithProof(
+                    stateRoot,
+                    bitmap,
+                    createMockProof()
+                )
+            ).to.be.revertedWithCustomError(zkContract, "InvalidZKProof");
+        });
+
+        it("Dovrebbe esporre il messaggio da firmare per il prossimo batch", async function () {
+            const stateRoot = ethers.keccak256(ethers.toUtf8Bytes("committee_state"));
+            const { chainId } = await ethers.provider.getNetwork();
+            const expected = ethers.AbiCoder.defaultAbiCoder().encode(
+                ["uint256", "address", "uint256", "bytes32"],
+                [chainId, await zkContract.getAddress(), 0, stateRoot]
+            );
+            expect(await zkContract.committeeMessage(stateRoot)).to.equal(expected);
+        });
+
+        it("Dovrebbe calcolare H(m) con la suite SVDW di BN254", async function () {
+            const Harness = await ethers.getContractFactory("HashToG1Harness");
+            const harness = await Harness.deploy();
+            await harness.waitForDeployment();
+
+            // Vettori di test di RFC 9380 (BN254G1_XMD:SHA-256_SVDW_RO_)
+            const dst = ethers.toUtf8Bytes("QUUX-V01-CS02-with-BN254G1_XMD:SHA-256_SVDW_RO_");
+            const vectors = [
+                [
+                    "",
+                    "0x0a976ab906170db1f9638d376514dbf8c42aef256a54bbd48521f20749e59e86",
+                    "0x02925ead66b9e68bfc309b014398640ab55f6619ab59bc1fab2210ad4c4d53d5"
+                ],
+                [
+                    "abc",
+                    "0x23f717bee89b1003957139f193e6be7da1df5f1374b26a4643b0378b5baf53d1",
+                    "0x04142f826b71ee574452dbc47e05bc3e1a647478403a7ba38b7b93948f4e151d"
+                ]
+            ];
+            for (const [message, x, y] of vectors) {
+                const [hx, hy] = await harness.hashToG1(ethers.toUtf8Bytes(message), dst);
+                expect(hx).to.equal(BigInt(x));
+                expect(hy).to.equal(BigInt(y));
+            }
+
+            // committeeMessageHash usa lo stesso calcolo con il DST del comitato
+            const stateRoot = ethers.keccak256(ethers.toUtf8Bytes("committee_state"));
+            expect(await zkContract.committeeMessageHash(stateRoot)).to.deep.equal(
+                await harness.hashToG1Words(
+                    await zkContract.committeeMessage(stateRoot),
+                    await zkContract.COMMITTEE_DST()
+                )
+            );
+        });
+
+        it("Dovrebbe permettere solo alla governance di registrare il comitato", async function () {
+            await expect(
+                zkContract.connect(sequencer).setCommittee(
+                    committeeRoot,
+                    await mockVerifier.getAddress()
+                )
+            ).to.be.revertedWithCustomError(zkContract, "OnlyGovernance");
+
+            await expect(registerCommittee())
+                .to.emit(zkContract, "CommitteeUpdated")
+                .withArgs(committeeRoot, await mockVerifier.getAddress());
+        });
+
+        it("NON dovrebbe accettare batch senza comitato registrato", async function () {
+            await expect(
+                zkContract.connect(sequencer).submitCommitteeBatchWithProof(
+                    ethers.ZeroHash,
+                    [BigInt(1)],
+                    createMockProof()
+                )
+            ).to.be.revertedWithCustomError(zkContract, "CommitteeNotSet");
+        });
+
+        it("NON dovrebbe accettare bitmap vuote", async function () {
+            await registerCommittee();
+
+            await expect(
+                zkContract.connect(sequencer).submitCommitteeBatchWithProof(
+                    ethers.ZeroHash,
+                    [BigInt(0)],
+                    createMockProof()
+                )
+            ).to.be.revertedWithCustomError(zkContract, "EmptyBitmap");
+        });
+    });
+
     describe("Signature Submission", function () {
         it("Dovrebbe permettere a chiunque di sottomettere firme", async function () {
-            const messageHash = ethers.keccak256(ethers.toUtf8Bytes("my message"));
-            const publicKeyX = BigInt(12345);
-            const publicKeyY = BigInt(67890);
+            const messageHash = messageWords(7);
+            const publicKey = keyWords(7);
 
             await expect(
-                zkContract.connect(user1).submitSignature(
-                    messageHash,
-                    publicKeyX,
-                    publicKeyY
-                )
-            ).to.emit(zkContract, "SignatureSubmitted");
+                zkContract.connect(user1).submitSignature(messageHash, publicKey)
+            ).to.emit(zkContract, "SignatureSubmitted").withArgs(
+                0,
+                wordsHash("uint256[4]", messageHash),
+                wordsHash("uint256[8]", publicKey)
+            );
 
             const submission = await zkContract.submissions(0);
-            expect(submission.messageHash).to.equal(messageHash);
+            expect(submission.messageHash).to.equal(wordsHash("uint256[4]", messageHash));
+            expect(submission.publicKeyHash).to.equal(wordsHash("uint256[8]", publicKey));
             expect(submission.included).to.be.false;
         });
     });
 
     describe("View Functions", function () {
         beforeEach(async function () {
-            const messageHashes = [
-                ethers.keccak256(ethers.toUtf8Bytes("msg1")),
-                ethers.keccak256(ethers.toUtf8Bytes("msg2")),
-                ethers.keccak256(ethers.toUtf8Bytes("msg3")),
-            ];
-
-            const publicKeysX = [BigInt(100), BigInt(200), BigInt(300)];
-            const publicKeysY = [BigInt(400), BigInt(500), BigInt(600)];
+            const messageHashes = [messageWords(1), messageWords(2), messageWords(3)];
+            const publicKeys = [keyWords(1), keyWords(2), keyWords(3)];
             const stateRoot = ethers.keccak256(ethers.toUtf8Bytes("state"));
             const proof = createMockProof();
 
             await zkContract.connect(sequencer).submitBatchWithProof(
                 stateRoot,
                 messageHashes,
-                publicKeysX,
-                publicKeysY,
+                publicKeys,
                 proof
             );
         });
@@ -297,15 +454,11 @@ describe("ZKRollupBLS", function () {
 
             for (const size of sizes) {
                 const messageHashes = Array.from({ length: size }, (_, i) =>
-                    ethers.keccak256(ethers.toUtf8Bytes(`msg${i}`))
+                    messageWords(1000 + i)
                 );
 
-                const publicKeysX = Array.from({ length: size }, (_, i) =>
-                    BigInt(1000 + i)
-                );
-
-                const publicKeysY = Array.from({ length: size }, (_, i) =>
-                    BigInt(2000 + i)
+                const publicKeys = Array.from({ length: size }, (_, i) =>
+                    keyWords(2000 + i)
                 );
 
                 const stateRoot = ethers.keccak256(
@@ -318,8 +471,7 @@ describe("ZKRollupBLS", function () {
                     .submitBatchWithProof(
                         stateRoot,
                         messageHashes,
-                        publicKeysX,
-                        publicKeysY,
+                        publicKeys,
                         proof
                     );
 
@@ -343,13 +495,10 @@ describe("ZKRollupBLS", function () {
             const proof = createMockProof();
 
             const messageHashes = Array.from({ length: numTx }, (_, i) =>
-                ethers.keccak256(ethers.toUtf8Bytes(`msg${i}`))
-            );
-            const publicKeysX = Array.from({ length: numTx }, (_, i) =>
-                BigInt(1000 + i)
+                messageWords(1000 + i)
             );
-            const publicKeysY = Array.from({ length: numTx }, (_, i) =>
-                BigInt(2000 + i)
+            const publicKeys = Array.from({ length: numTx }, (_, i) =>
+                keyWords(2000 + i)
             );
             const stateRoot = ethers.keccak256(ethers.toUtf8Bytes("state"));
 
@@ -358,8 +507,7 @@ describe("ZKRollupBLS", function () {
                 .submitBatchWithProof(
                     stateRoot,
                     messageHashes,
-                    publicKeysX,
-                    publicKeysY,
+                    publicKeys,
                     proof
                 );
             const receipt = await tx.wait();
@@ -388,21 +536,16 @@ describe("ZKRollupBLS", function () {
             // Batch 1
             await zkContract.connect(sequencer).submitBatchWithProof(
                 ethers.keccak256(ethers.toUtf8Bytes("state1")),
-                [ethers.keccak256(ethers.toUtf8Bytes("msg1"))],
-                [BigInt(100)],
-                [BigInt(200)],
+                [messageWords(1)],
+                [keyWords(1)],
                 proof
             );
 
             // Batch 2
             await zkContract.connect(sequencer).submitBatchWithProof(
                 ethers.keccak256(ethers.toUtf8Bytes("state2")),
-                [
-                    ethers.keccak256(ethers.toUtf8Bytes("msg2")),
-                    ethers.keccak256(ethers.toUtf8Bytes("msg3"))
-                ],
-                [BigInt(300), BigInt(400)],
-                [BigInt(500), BigInt(600)],
+                [messageWords(2), messageWords(3)],
+                [keyWords(2), keyWords(3)],
                 proof
             );
 